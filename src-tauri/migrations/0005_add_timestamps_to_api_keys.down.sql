@@ -0,0 +1,2 @@
+ALTER TABLE api_keys DROP COLUMN created_at;
+ALTER TABLE api_keys DROP COLUMN updated_at;
@@ -0,0 +1,36 @@
+-- Back to one scenario per tool. A tool linked to several scenarios keeps its
+-- earliest link; global, agent-only and unlinked tools cannot be represented and
+-- are dropped.
+CREATE TABLE tools_old (
+  id                TEXT PRIMARY KEY,
+  scenario_id       TEXT NOT NULL REFERENCES scenarios(id) ON DELETE CASCADE,
+  name              TEXT NOT NULL,
+  description       TEXT,
+  parameters_json   TEXT NOT NULL DEFAULT '[]',
+  mock_response     TEXT,
+  mock_mode         TEXT NOT NULL DEFAULT 'json' CHECK (mock_mode IN ('json', 'code')),
+  code              TEXT,
+  is_enabled        INTEGER NOT NULL DEFAULT 1 CHECK (is_enabled IN (0, 1)),
+  sort_order        INTEGER NOT NULL DEFAULT 0,
+  created_at        INTEGER NOT NULL,
+  updated_at        INTEGER NOT NULL,
+  archived_at       INTEGER
+);
+
+INSERT INTO tools_old
+  SELECT t.id,
+         (SELECT l.toolable_id FROM tool_links l
+          WHERE l.tool_id = t.id AND l.toolable_type = 'scenario'
+          ORDER BY l.created_at, l.id LIMIT 1),
+         t.name, t.description, t.parameters_json, t.mock_response, t.mock_mode,
+         t.code, t.is_enabled, t.sort_order, t.created_at, t.updated_at, t.archived_at
+  FROM tools t
+  WHERE t.is_global = 0
+    AND EXISTS (SELECT 1 FROM tool_links l WHERE l.tool_id = t.id AND l.toolable_type = 'scenario');
+
+DROP TABLE tool_links;
+DROP TABLE tools;
+ALTER TABLE tools_old RENAME TO tools;
+
+CREATE INDEX idx_tools_scenario_id ON tools(scenario_id);
+CREATE INDEX idx_tools_scenario_name ON tools(scenario_id, name);
@@ -0,0 +1 @@
+ALTER TABLE agents DROP COLUMN human_in_the_loop;
@@ -0,0 +1,22 @@
+-- Shadow traffic: a proxied request mirrored to extra provider/model targets.
+-- Rows sharing a group_id come from the same incoming request; role 'primary' is the
+-- response returned to the caller, role 'shadow' a mirrored one kept for comparison.
+CREATE TABLE IF NOT EXISTS shadow_responses (
+  id             TEXT    PRIMARY KEY,     -- ULID
+  group_id       TEXT    NOT NULL,        -- ULID, also returned in X-Shadow-Group-Id
+  role           TEXT    NOT NULL CHECK (role IN ('primary', 'shadow')),
+  provider       TEXT    NOT NULL,
+  model          TEXT    NOT NULL,
+  request_json   TEXT    NOT NULL,        -- body sent upstream (after model/params rewrite)
+  status_code    INTEGER,                 -- null when the request never got a response
+  response_body  TEXT,                    -- raw body (JSON or SSE stream)
+  output_text    TEXT,                    -- assistant text extracted from response_body
+  usage_json     TEXT,                    -- provider-reported usage (optional)
+  latency_ms     INTEGER,
+  error          TEXT,
+  created_at     INTEGER NOT NULL,
+  updated_at     INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_shadow_responses_group ON shadow_responses(group_id, role);
+CREATE INDEX IF NOT EXISTS idx_shadow_responses_created_at ON shadow_responses(created_at DESC);
@@ -0,0 +1,22 @@
+-- Request log: one row per upstream call made by the local proxy.
+-- timing_json: { reused, dns_ms, connect_ms, tls_ms, send_ms, ttfb_ms, body_ms, total_ms }
+--   connect_ms is the TCP connect, tls_ms the TLS handshake; body_ms/total_ms are filled in
+--   once the response body has been fully streamed to the caller.
+CREATE TABLE IF NOT EXISTS proxy_requests (
+  id           TEXT    PRIMARY KEY,     -- ULID, also returned in X-Proxy-Request-Id
+  provider     TEXT,
+  method       TEXT    NOT NULL,
+  url          TEXT    NOT NULL,
+  model        TEXT,
+  status_code  INTEGER,                 -- null when the request failed before a response
+  error        TEXT,
+  latency_ms   INTEGER,                 -- time to response headers (X-Request-Latency-Ms)
+  timing_json  TEXT,
+  started_at   INTEGER NOT NULL,
+  ended_at     INTEGER,
+  created_at   INTEGER NOT NULL,
+  updated_at   INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_proxy_requests_started_at ON proxy_requests(started_at DESC);
+CREATE INDEX IF NOT EXISTS idx_proxy_requests_provider_time ON proxy_requests(provider, started_at DESC);
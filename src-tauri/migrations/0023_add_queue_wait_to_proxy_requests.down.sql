@@ -0,0 +1 @@
+ALTER TABLE proxy_requests DROP COLUMN queue_wait_ms;
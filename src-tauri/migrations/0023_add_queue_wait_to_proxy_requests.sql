@@ -0,0 +1,2 @@
+-- Time a proxied request spent waiting in the client-side rate limiter before being sent
+ALTER TABLE proxy_requests ADD COLUMN queue_wait_ms INTEGER;
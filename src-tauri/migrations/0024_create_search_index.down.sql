@@ -0,0 +1,17 @@
+DROP TRIGGER IF EXISTS scenarios_search_insert;
+DROP TRIGGER IF EXISTS scenarios_search_update;
+DROP TRIGGER IF EXISTS scenarios_search_delete;
+DROP TRIGGER IF EXISTS agents_search_insert;
+DROP TRIGGER IF EXISTS agents_search_update;
+DROP TRIGGER IF EXISTS agents_search_delete;
+DROP TRIGGER IF EXISTS prompt_templates_search_insert;
+DROP TRIGGER IF EXISTS prompt_templates_search_update;
+DROP TRIGGER IF EXISTS prompt_templates_search_delete;
+DROP TRIGGER IF EXISTS tools_search_insert;
+DROP TRIGGER IF EXISTS tools_search_update;
+DROP TRIGGER IF EXISTS tools_search_delete;
+DROP TRIGGER IF EXISTS executions_search_insert;
+DROP TRIGGER IF EXISTS executions_search_update;
+DROP TRIGGER IF EXISTS executions_search_delete;
+DROP TABLE IF EXISTS search_index;
+DROP TABLE IF EXISTS search_entities;
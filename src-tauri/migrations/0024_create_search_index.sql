@@ -0,0 +1,126 @@
+-- Full-text search over scenarios, agents, prompt templates, tools and execution results.
+-- search_entities maps each indexed row to a stable integer rowid (an explicit INTEGER
+-- PRIMARY KEY, so VACUUM keeps it) that is also the rowid of its search_index row; the
+-- triggers below keep both in sync with the source tables.
+CREATE TABLE IF NOT EXISTS search_entities (
+  rowid        INTEGER PRIMARY KEY,
+  entity_type  TEXT NOT NULL,            -- scenario|agent|prompt_template|tool|execution
+  entity_id    TEXT NOT NULL,            -- id in the source table
+  UNIQUE (entity_type, entity_id)
+);
+
+CREATE VIRTUAL TABLE IF NOT EXISTS search_index USING fts5(
+  title,
+  body,
+  tokenize = 'porter unicode61 remove_diacritics 2'
+);
+
+-- scenarios
+INSERT INTO search_entities (entity_type, entity_id) SELECT 'scenario', id FROM scenarios;
+INSERT INTO search_index (rowid, title, body)
+  SELECT e.rowid, t.title, concat_ws(char(10), t.description, t.system_prompt, t.user_prompt)
+  FROM scenarios t JOIN search_entities e ON e.entity_type = 'scenario' AND e.entity_id = t.id;
+
+CREATE TRIGGER IF NOT EXISTS scenarios_search_insert AFTER INSERT ON scenarios BEGIN
+  INSERT INTO search_entities (entity_type, entity_id) VALUES ('scenario', new.id);
+  INSERT INTO search_index (rowid, title, body)
+    VALUES ((SELECT rowid FROM search_entities WHERE entity_type = 'scenario' AND entity_id = new.id), new.title, concat_ws(char(10), new.description, new.system_prompt, new.user_prompt));
+END;
+
+CREATE TRIGGER IF NOT EXISTS scenarios_search_update AFTER UPDATE OF title, description, system_prompt, user_prompt ON scenarios BEGIN
+  UPDATE search_index SET title = new.title, body = concat_ws(char(10), new.description, new.system_prompt, new.user_prompt)
+    WHERE rowid = (SELECT rowid FROM search_entities WHERE entity_type = 'scenario' AND entity_id = new.id);
+END;
+
+CREATE TRIGGER IF NOT EXISTS scenarios_search_delete AFTER DELETE ON scenarios BEGIN
+  DELETE FROM search_index WHERE rowid = (SELECT rowid FROM search_entities WHERE entity_type = 'scenario' AND entity_id = old.id);
+  DELETE FROM search_entities WHERE entity_type = 'scenario' AND entity_id = old.id;
+END;
+
+-- agents
+INSERT INTO search_entities (entity_type, entity_id) SELECT 'agent', id FROM agents;
+INSERT INTO search_index (rowid, title, body)
+  SELECT e.rowid, t.name, concat_ws(char(10), t.description, t.agent_goal, t.system_instructions)
+  FROM agents t JOIN search_entities e ON e.entity_type = 'agent' AND e.entity_id = t.id;
+
+CREATE TRIGGER IF NOT EXISTS agents_search_insert AFTER INSERT ON agents BEGIN
+  INSERT INTO search_entities (entity_type, entity_id) VALUES ('agent', new.id);
+  INSERT INTO search_index (rowid, title, body)
+    VALUES ((SELECT rowid FROM search_entities WHERE entity_type = 'agent' AND entity_id = new.id), new.name, concat_ws(char(10), new.description, new.agent_goal, new.system_instructions));
+END;
+
+CREATE TRIGGER IF NOT EXISTS agents_search_update AFTER UPDATE OF name, description, agent_goal, system_instructions ON agents BEGIN
+  UPDATE search_index SET title = new.name, body = concat_ws(char(10), new.description, new.agent_goal, new.system_instructions)
+    WHERE rowid = (SELECT rowid FROM search_entities WHERE entity_type = 'agent' AND entity_id = new.id);
+END;
+
+CREATE TRIGGER IF NOT EXISTS agents_search_delete AFTER DELETE ON agents BEGIN
+  DELETE FROM search_index WHERE rowid = (SELECT rowid FROM search_entities WHERE entity_type = 'agent' AND entity_id = old.id);
+  DELETE FROM search_entities WHERE entity_type = 'agent' AND entity_id = old.id;
+END;
+
+-- prompt_templates
+INSERT INTO search_entities (entity_type, entity_id) SELECT 'prompt_template', id FROM prompt_templates;
+INSERT INTO search_index (rowid, title, body)
+  SELECT e.rowid, t.name, concat_ws(char(10), t.description, t.content)
+  FROM prompt_templates t JOIN search_entities e ON e.entity_type = 'prompt_template' AND e.entity_id = t.id;
+
+CREATE TRIGGER IF NOT EXISTS prompt_templates_search_insert AFTER INSERT ON prompt_templates BEGIN
+  INSERT INTO search_entities (entity_type, entity_id) VALUES ('prompt_template', new.id);
+  INSERT INTO search_index (rowid, title, body)
+    VALUES ((SELECT rowid FROM search_entities WHERE entity_type = 'prompt_template' AND entity_id = new.id), new.name, concat_ws(char(10), new.description, new.content));
+END;
+
+CREATE TRIGGER IF NOT EXISTS prompt_templates_search_update AFTER UPDATE OF name, description, content ON prompt_templates BEGIN
+  UPDATE search_index SET title = new.name, body = concat_ws(char(10), new.description, new.content)
+    WHERE rowid = (SELECT rowid FROM search_entities WHERE entity_type = 'prompt_template' AND entity_id = new.id);
+END;
+
+CREATE TRIGGER IF NOT EXISTS prompt_templates_search_delete AFTER DELETE ON prompt_templates BEGIN
+  DELETE FROM search_index WHERE rowid = (SELECT rowid FROM search_entities WHERE entity_type = 'prompt_template' AND entity_id = old.id);
+  DELETE FROM search_entities WHERE entity_type = 'prompt_template' AND entity_id = old.id;
+END;
+
+-- tools
+INSERT INTO search_entities (entity_type, entity_id) SELECT 'tool', id FROM tools;
+INSERT INTO search_index (rowid, title, body)
+  SELECT e.rowid, t.name, concat_ws(char(10), t.description, t.mock_response, t.code)
+  FROM tools t JOIN search_entities e ON e.entity_type = 'tool' AND e.entity_id = t.id;
+
+CREATE TRIGGER IF NOT EXISTS tools_search_insert AFTER INSERT ON tools BEGIN
+  INSERT INTO search_entities (entity_type, entity_id) VALUES ('tool', new.id);
+  INSERT INTO search_index (rowid, title, body)
+    VALUES ((SELECT rowid FROM search_entities WHERE entity_type = 'tool' AND entity_id = new.id), new.name, concat_ws(char(10), new.description, new.mock_response, new.code));
+END;
+
+CREATE TRIGGER IF NOT EXISTS tools_search_update AFTER UPDATE OF name, description, mock_response, code ON tools BEGIN
+  UPDATE search_index SET title = new.name, body = concat_ws(char(10), new.description, new.mock_response, new.code)
+    WHERE rowid = (SELECT rowid FROM search_entities WHERE entity_type = 'tool' AND entity_id = new.id);
+END;
+
+CREATE TRIGGER IF NOT EXISTS tools_search_delete AFTER DELETE ON tools BEGIN
+  DELETE FROM search_index WHERE rowid = (SELECT rowid FROM search_entities WHERE entity_type = 'tool' AND entity_id = old.id);
+  DELETE FROM search_entities WHERE entity_type = 'tool' AND entity_id = old.id;
+END;
+
+-- executions
+INSERT INTO search_entities (entity_type, entity_id) SELECT 'execution', id FROM executions;
+INSERT INTO search_index (rowid, title, body)
+  SELECT e.rowid, NULL, t.result_json
+  FROM executions t JOIN search_entities e ON e.entity_type = 'execution' AND e.entity_id = t.id;
+
+CREATE TRIGGER IF NOT EXISTS executions_search_insert AFTER INSERT ON executions BEGIN
+  INSERT INTO search_entities (entity_type, entity_id) VALUES ('execution', new.id);
+  INSERT INTO search_index (rowid, title, body)
+    VALUES ((SELECT rowid FROM search_entities WHERE entity_type = 'execution' AND entity_id = new.id), NULL, new.result_json);
+END;
+
+CREATE TRIGGER IF NOT EXISTS executions_search_update AFTER UPDATE OF result_json ON executions BEGIN
+  UPDATE search_index SET title = NULL, body = new.result_json
+    WHERE rowid = (SELECT rowid FROM search_entities WHERE entity_type = 'execution' AND entity_id = new.id);
+END;
+
+CREATE TRIGGER IF NOT EXISTS executions_search_delete AFTER DELETE ON executions BEGIN
+  DELETE FROM search_index WHERE rowid = (SELECT rowid FROM search_entities WHERE entity_type = 'execution' AND entity_id = old.id);
+  DELETE FROM search_entities WHERE entity_type = 'execution' AND entity_id = old.id;
+END;
@@ -0,0 +1,4 @@
+DROP TRIGGER IF EXISTS scenarios_version_snapshot;
+DROP TRIGGER IF EXISTS agents_version_snapshot;
+DROP TABLE IF EXISTS scenario_versions;
+DROP TABLE IF EXISTS agent_versions;
@@ -0,0 +1,98 @@
+-- Version history for scenarios and agents. Whenever `version` changes, the row as it
+-- was before the change (and the tools linked to it) is kept as a snapshot of the old
+-- version; the live row is always the current version.
+
+-- scenarios
+CREATE TABLE IF NOT EXISTS scenario_versions (
+  id             TEXT PRIMARY KEY,
+  scenario_id    TEXT NOT NULL REFERENCES scenarios(id) ON DELETE CASCADE,
+  version        INTEGER NOT NULL,
+  snapshot_json  TEXT NOT NULL,              -- the scenarios row as it was at this version
+  tools_json     TEXT NOT NULL DEFAULT '[]', -- linked tools rows, by sort_order
+  created_at     INTEGER NOT NULL,           -- when the version was superseded
+  updated_at     INTEGER NOT NULL,
+  UNIQUE (scenario_id, version)
+);
+
+CREATE TRIGGER IF NOT EXISTS scenarios_version_snapshot AFTER UPDATE OF version ON scenarios
+WHEN old.version IS NOT new.version BEGIN
+  INSERT OR REPLACE INTO scenario_versions (id, scenario_id, version, snapshot_json, tools_json, created_at, updated_at)
+  VALUES (
+    lower(hex(randomblob(16))),
+    old.id,
+    old.version,
+    json_object(
+      'id', old.id, 'collection_id', old.collection_id, 'title', old.title,
+      'description', old.description, 'provider', old.provider, 'model', old.model,
+      'system_prompt', old.system_prompt, 'user_prompt', old.user_prompt,
+      'history_json', old.history_json, 'variables_json', old.variables_json,
+      'params_json', old.params_json, 'response_format_json', old.response_format_json,
+      'tools_json', old.tools_json, 'provider_meta_json', old.provider_meta_json,
+      'version', old.version, 'created_at', old.created_at,
+      'updated_at', old.updated_at, 'archived_at', old.archived_at,
+      'attachments_json', old.attachments_json
+    ),
+    (SELECT json_group_array(json(tool)) FROM (
+      SELECT json_object(
+        'id', t.id, 'name', t.name, 'description', t.description,
+        'parameters_json', t.parameters_json, 'mock_response', t.mock_response,
+        'mock_mode', t.mock_mode, 'code', t.code, 'is_enabled', t.is_enabled,
+        'is_global', t.is_global, 'sort_order', t.sort_order, 'created_at', t.created_at,
+        'updated_at', t.updated_at, 'archived_at', t.archived_at
+      ) AS tool
+      FROM tool_links l JOIN tools t ON t.id = l.tool_id
+      WHERE l.toolable_type = 'scenario' AND l.toolable_id = old.id
+      ORDER BY t.sort_order, t.id
+    )),
+    CAST(unixepoch('subsec') * 1000 AS INTEGER),
+    CAST(unixepoch('subsec') * 1000 AS INTEGER)
+  );
+END;
+
+-- agents
+CREATE TABLE IF NOT EXISTS agent_versions (
+  id             TEXT PRIMARY KEY,
+  agent_id       TEXT NOT NULL REFERENCES agents(id) ON DELETE CASCADE,
+  version        INTEGER NOT NULL,
+  snapshot_json  TEXT NOT NULL,              -- the agents row as it was at this version
+  tools_json     TEXT NOT NULL DEFAULT '[]', -- linked tools rows, by sort_order
+  created_at     INTEGER NOT NULL,           -- when the version was superseded
+  updated_at     INTEGER NOT NULL,
+  UNIQUE (agent_id, version)
+);
+
+CREATE TRIGGER IF NOT EXISTS agents_version_snapshot AFTER UPDATE OF version ON agents
+WHEN old.version IS NOT new.version BEGIN
+  INSERT OR REPLACE INTO agent_versions (id, agent_id, version, snapshot_json, tools_json, created_at, updated_at)
+  VALUES (
+    lower(hex(randomblob(16))),
+    old.id,
+    old.version,
+    json_object(
+      'id', old.id, 'name', old.name, 'description', old.description,
+      'provider', old.provider, 'model', old.model, 'params_json', old.params_json,
+      'agent_goal', old.agent_goal, 'system_instructions', old.system_instructions,
+      'tools_json', old.tools_json, 'max_iterations', old.max_iterations,
+      'timeout_seconds', old.timeout_seconds, 'retry_policy', old.retry_policy,
+      'tool_call_strategy', old.tool_call_strategy,
+      'memory_enabled', old.memory_enabled, 'memory_source', old.memory_source,
+      'version', old.version, 'created_at', old.created_at,
+      'updated_at', old.updated_at, 'archived_at', old.archived_at,
+      'human_in_the_loop', old.human_in_the_loop
+    ),
+    (SELECT json_group_array(json(tool)) FROM (
+      SELECT json_object(
+        'id', t.id, 'name', t.name, 'description', t.description,
+        'parameters_json', t.parameters_json, 'mock_response', t.mock_response,
+        'mock_mode', t.mock_mode, 'code', t.code, 'is_enabled', t.is_enabled,
+        'is_global', t.is_global, 'sort_order', t.sort_order, 'created_at', t.created_at,
+        'updated_at', t.updated_at, 'archived_at', t.archived_at
+      ) AS tool
+      FROM tool_links l JOIN tools t ON t.id = l.tool_id
+      WHERE l.toolable_type = 'agent' AND l.toolable_id = old.id
+      ORDER BY t.sort_order, t.id
+    )),
+    CAST(unixepoch('subsec') * 1000 AS INTEGER),
+    CAST(unixepoch('subsec') * 1000 AS INTEGER)
+  );
+END;
@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS idx_exec_created_at;
+DROP INDEX IF EXISTS idx_eval_runs_created_at;
+ALTER TABLE executions DROP COLUMN starred;
+ALTER TABLE eval_runs DROP COLUMN starred;
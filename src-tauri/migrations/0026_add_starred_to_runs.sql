@@ -0,0 +1,6 @@
+-- Starred runs are exempt from retention pruning (see retention.rs).
+ALTER TABLE executions ADD COLUMN starred INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE eval_runs ADD COLUMN starred INTEGER NOT NULL DEFAULT 0;
+
+CREATE INDEX IF NOT EXISTS idx_exec_created_at ON executions(created_at);
+CREATE INDEX IF NOT EXISTS idx_eval_runs_created_at ON eval_runs(created_at);
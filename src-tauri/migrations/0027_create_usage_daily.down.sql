@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS idx_exec_updated_at;
+DROP INDEX IF EXISTS idx_eval_runs_updated_at;
+DROP TABLE IF EXISTS usage_daily;
+DELETE FROM settings WHERE key = 'usage_daily_refreshed_at';
@@ -0,0 +1,25 @@
+-- Daily usage rollup, refreshed by analytics::refresh_daily_usage (on demand and
+-- before retention pruning). Rows outlive the runs they summarize, so spend history
+-- survives pruning.
+CREATE TABLE usage_daily (
+  day            TEXT    NOT NULL,   -- YYYY-MM-DD, local time
+  source         TEXT    NOT NULL CHECK (source IN ('executions', 'eval_runs')),
+  provider       TEXT    NOT NULL,
+  model          TEXT    NOT NULL,
+  runnable_type  TEXT    NOT NULL,
+  runnable_id    TEXT    NOT NULL,
+  status         TEXT    NOT NULL,
+  runs           INTEGER NOT NULL,
+  input_tokens   INTEGER NOT NULL,
+  output_tokens  INTEGER NOT NULL,
+  cached_tokens  INTEGER NOT NULL,
+  total_tokens   INTEGER NOT NULL,
+  cost_usd       REAL    NOT NULL,
+  latency_ms_sum REAL    NOT NULL,
+  latency_count  INTEGER NOT NULL,
+  refreshed_at   INTEGER NOT NULL,
+  PRIMARY KEY (day, source, provider, model, runnable_type, runnable_id, status)
+);
+
+CREATE INDEX IF NOT EXISTS idx_exec_updated_at ON executions(updated_at);
+CREATE INDEX IF NOT EXISTS idx_eval_runs_updated_at ON eval_runs(updated_at);
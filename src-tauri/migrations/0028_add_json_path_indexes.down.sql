@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS idx_exec_usage_total_tokens;
+DROP INDEX IF EXISTS idx_exec_snapshot_model;
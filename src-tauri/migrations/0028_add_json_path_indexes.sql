@@ -0,0 +1,5 @@
+-- Indexes on JSON paths the run history filters and sorts by. The query DSL emits
+-- `json_extract(column, '$.path')` in exactly this form, so SQLite can use them for
+-- `usage_json.$.totalTokens` and `snapshot_json.$.configuration.model` keys.
+CREATE INDEX IF NOT EXISTS idx_exec_usage_total_tokens ON executions(json_extract(usage_json, '$.totalTokens'));
+CREATE INDEX IF NOT EXISTS idx_exec_snapshot_model ON executions(json_extract(snapshot_json, '$.configuration.model'));
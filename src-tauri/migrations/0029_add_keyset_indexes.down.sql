@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS idx_exec_started_at_id;
+DROP INDEX IF EXISTS idx_telemetry_events_occurred_at_id;
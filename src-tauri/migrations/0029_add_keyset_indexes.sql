@@ -0,0 +1,4 @@
+-- Keyset pagination (db_page) orders by the sort key and then id; these let the
+-- Runs history and telemetry pages seek straight to the cursor.
+CREATE INDEX IF NOT EXISTS idx_exec_started_at_id ON executions(started_at, id);
+CREATE INDEX IF NOT EXISTS idx_telemetry_events_occurred_at_id ON telemetry_events(occurred_at, id);
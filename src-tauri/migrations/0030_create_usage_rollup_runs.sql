@@ -0,0 +1,25 @@
+-- What each run last contributed to usage_daily. A refresh subtracts a changed run's
+-- previous contribution and adds its new one, so the rollup accumulates per run and
+-- never recomputes a day from the runs that survived pruning.
+CREATE TABLE usage_rollup_runs (
+  source         TEXT    NOT NULL,
+  run_id         TEXT    NOT NULL,
+  day            TEXT    NOT NULL,
+  provider       TEXT    NOT NULL,
+  model          TEXT    NOT NULL,
+  runnable_type  TEXT    NOT NULL,
+  runnable_id    TEXT    NOT NULL,
+  status         TEXT    NOT NULL,
+  input_tokens   INTEGER NOT NULL,
+  output_tokens  INTEGER NOT NULL,
+  cached_tokens  INTEGER NOT NULL,
+  total_tokens   INTEGER NOT NULL,
+  cost_usd       REAL    NOT NULL,
+  latency_ms_sum REAL    NOT NULL,
+  latency_count  INTEGER NOT NULL,
+  PRIMARY KEY (source, run_id)
+);
+
+-- Rows built by whole-day recomputation have no per-run record; rebuild from live runs.
+DELETE FROM usage_daily;
+DELETE FROM settings WHERE key = 'usage_daily_refreshed_at';
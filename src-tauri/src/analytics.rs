@@ -0,0 +1,611 @@
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::{anyhow, Result as AnyhowResult};
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params, params_from_iter, Connection};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use crate::database::now_ms;
+use crate::pool::DbState;
+use crate::versions::RunnableType;
+
+// ── Usage analytics ──────────────────────────────────────────────────────────
+//
+// Token, cost, run count and latency breakdowns over `executions` and `eval_runs`,
+// computed in SQLite. Provider and model come from `snapshot_json`, tokens from
+// `usage_json` (snake_case or the older camelCase keys); JSON that does not parse
+// counts as missing. Costs are priced per provider/model group (pricing is linear
+// in tokens), unless the run stored its own `cost_usd`.
+//
+// `usage_daily` keeps the same figures per day so charts still cover runs removed
+// by retention; it is refreshed on demand and before each pruning pass. It is built
+// from per-run contributions (`usage_rollup_runs`): a refresh swaps a changed run's
+// old contribution for its new one and never recomputes a day from the live rows.
+
+/// `settings.key` holding when `usage_daily` was last refreshed (unix ms).
+const ROLLUP_SETTING: &str = "usage_daily_refreshed_at";
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum UsageSource {
+    Executions,
+    EvalRuns,
+}
+
+impl UsageSource {
+    fn as_str(self) -> &'static str {
+        match self {
+            UsageSource::Executions => "executions",
+            UsageSource::EvalRuns => "eval_runs",
+        }
+    }
+
+    /// One row per run with the columns the aggregations use.
+    fn runs_sql(self) -> String {
+        let provider = "COALESCE(json_extract(snapshot, '$.configuration.provider'), json_extract(snapshot, '$.provider'), '')";
+        let model = "COALESCE(json_extract(snapshot, '$.configuration.model'), json_extract(snapshot, '$.model'), '')";
+        let runs = match self {
+            UsageSource::Executions => format!(
+                "SELECT id, type AS runnable_type, runnable_id, status, updated_at,
+                        COALESCE(started_at, created_at) AS at,
+                        {provider} AS provider, {model} AS model,
+                        {tokens},
+                        NULLIF(json_extract(usage, '$.cost_usd'), 0) AS stored_cost,
+                        COALESCE(json_extract(usage, '$.latency_ms'),
+                                 CASE WHEN ended_at >= started_at THEN ended_at - started_at END) AS latency_ms,
+                        0 AS cases_passed, 0 AS cases_failed, 0 AS cases_errored
+                 FROM (
+                   SELECT *, CASE WHEN json_valid(snapshot_json) THEN snapshot_json END AS snapshot,
+                             CASE WHEN json_valid(usage_json) THEN usage_json END AS usage
+                   FROM executions
+                 )",
+                provider = provider,
+                model = model,
+                tokens = token_columns("usage", ""),
+            ),
+            // Tokens are summed over the run's results; latency is the run's average per case.
+            UsageSource::EvalRuns => format!(
+                "SELECT r.id, r.runnable_type, r.runnable_id, r.status, r.updated_at,
+                        COALESCE(r.started_at, r.created_at) AS at,
+                        {provider} AS provider, {model} AS model,
+                        COALESCE(t.input_tokens, 0) AS input_tokens,
+                        COALESCE(t.output_tokens, 0) AS output_tokens,
+                        COALESCE(t.cached_tokens, 0) AS cached_tokens,
+                        COALESCE(t.reported_total, 0) AS reported_total,
+                        NULLIF(r.total_cost_usd, 0) AS stored_cost,
+                        r.avg_latency_ms AS latency_ms,
+                        r.pass_count AS cases_passed, r.fail_count AS cases_failed, r.error_count AS cases_errored
+                 FROM (
+                   SELECT *, CASE WHEN json_valid(snapshot_json) THEN snapshot_json END AS snapshot FROM eval_runs
+                 ) r
+                 LEFT JOIN (
+                   SELECT eval_run_id, {tokens}
+                   FROM (
+                     SELECT eval_run_id, CASE WHEN json_valid(usage_json) THEN usage_json END AS usage FROM eval_results
+                   )
+                   GROUP BY eval_run_id
+                 ) t ON t.eval_run_id = r.id",
+                provider = provider,
+                model = model,
+                tokens = token_columns("usage", "SUM"),
+            ),
+        };
+        format!(
+            "SELECT *, date(at / 1000, 'unixepoch', 'localtime') AS day,
+                    CASE WHEN input_tokens + output_tokens > 0 THEN input_tokens + output_tokens
+                         ELSE reported_total END AS total_tokens
+             FROM ({})",
+            runs
+        )
+    }
+}
+
+/// input/output/cached/reported total token columns read from the `usage` JSON,
+/// optionally wrapped in an aggregate.
+fn token_columns(usage: &str, aggregate: &str) -> String {
+    [
+        ("input_tokens", ["input_tokens", "inputTokens"]),
+        ("output_tokens", ["output_tokens", "outputTokens"]),
+        ("cached_tokens", ["cached_tokens", "cachedTokens"]),
+        ("reported_total", ["total_tokens", "totalTokens"]),
+    ]
+    .iter()
+    .map(|(alias, [snake, camel])| {
+        format!(
+            "{}(COALESCE(json_extract({usage}, '$.{}'), json_extract({usage}, '$.{}'), 0)) AS {}",
+            aggregate,
+            snake,
+            camel,
+            alias,
+            usage = usage
+        )
+    })
+    .collect::<Vec<_>>()
+    .join(", ")
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Dimension {
+    /// YYYY-MM-DD, local time
+    Day,
+    Provider,
+    Model,
+    /// Scenario runs only, by scenario
+    Scenario,
+    /// Agent runs only, by agent
+    Agent,
+    Status,
+}
+
+impl Dimension {
+    fn name(self) -> &'static str {
+        match self {
+            Dimension::Day => "day",
+            Dimension::Provider => "provider",
+            Dimension::Model => "model",
+            Dimension::Scenario => "scenario",
+            Dimension::Agent => "agent",
+            Dimension::Status => "status",
+        }
+    }
+
+    fn column(self) -> &'static str {
+        match self {
+            Dimension::Scenario | Dimension::Agent => "runnable_id",
+            other => other.name(),
+        }
+    }
+
+    fn runnable(self) -> Option<RunnableType> {
+        match self {
+            Dimension::Scenario => Some(RunnableType::Scenario),
+            Dimension::Agent => Some(RunnableType::Agent),
+            _ => None,
+        }
+    }
+}
+
+/// Which runs to aggregate.
+#[derive(Default)]
+struct Filter {
+    /// unix ms, inclusive
+    from: Option<i64>,
+    /// unix ms, exclusive
+    to: Option<i64>,
+    runnable_type: Option<RunnableType>,
+    /// unix ms, inclusive, on the run's `updated_at`
+    updated_since: Option<i64>,
+}
+
+impl Filter {
+    fn where_sql(&self, extra: &str) -> (String, Vec<SqlValue>) {
+        let mut clauses = Vec::new();
+        let mut params = Vec::new();
+        if let Some(from) = self.from {
+            params.push(SqlValue::Integer(from));
+            clauses.push(format!("at >= ?{}", params.len()));
+        }
+        if let Some(to) = self.to {
+            params.push(SqlValue::Integer(to));
+            clauses.push(format!("at < ?{}", params.len()));
+        }
+        if let Some(kind) = self.runnable_type {
+            params.push(SqlValue::Text(kind.as_str().to_string()));
+            clauses.push(format!("runnable_type = ?{}", params.len()));
+        }
+        if let Some(since) = self.updated_since {
+            params.push(SqlValue::Integer(since));
+            clauses.push(format!("updated_at >= ?{}", params.len()));
+        }
+        if !extra.is_empty() {
+            clauses.push(extra.to_string());
+        }
+        if clauses.is_empty() {
+            (String::new(), params)
+        } else {
+            (format!(" WHERE {}", clauses.join(" AND ")), params)
+        }
+    }
+}
+
+/// Sums for one (dimensions, provider, model, status) group.
+struct Partial {
+    dims: Vec<Value>,
+    provider: String,
+    model: String,
+    status: String,
+    runs: i64,
+    input_tokens: f64,
+    output_tokens: f64,
+    cached_tokens: f64,
+    total_tokens: f64,
+    stored_cost: f64,
+    /// Tokens of runs without a stored cost, priced from the model
+    unpriced_tokens: (f64, f64, f64),
+    unpriced_runs: i64,
+    latency_sum: f64,
+    latency_count: i64,
+    cases: (i64, i64, i64),
+}
+
+impl Partial {
+    /// Cost in USD, and the number of runs that could not be priced.
+    fn cost(&self) -> (f64, i64) {
+        let (input, output, cached) = self.unpriced_tokens;
+        if self.unpriced_runs == 0 {
+            return (self.stored_cost, 0);
+        }
+        match crate::pricing::calculate_request_cost(&self.provider, &self.model, input as u64, output as u64, cached as u64) {
+            Some(cost) => (self.stored_cost + cost, 0),
+            None => (self.stored_cost, self.unpriced_runs),
+        }
+    }
+}
+
+fn grouped(conn: &Connection, source: UsageSource, dims: &[&str], filter: &Filter) -> AnyhowResult<Vec<Partial>> {
+    let (where_sql, params) = filter.where_sql("");
+    let mut columns: Vec<String> = dims.iter().enumerate().map(|(i, dim)| format!("{} AS d{}", dim, i)).collect();
+    columns.extend(
+        [
+            "provider",
+            "model",
+            "status",
+            "COUNT(*)",
+            "SUM(input_tokens)",
+            "SUM(output_tokens)",
+            "SUM(cached_tokens)",
+            "SUM(total_tokens)",
+            "SUM(stored_cost)",
+            "SUM(CASE WHEN stored_cost IS NULL THEN input_tokens ELSE 0 END)",
+            "SUM(CASE WHEN stored_cost IS NULL THEN output_tokens ELSE 0 END)",
+            "SUM(CASE WHEN stored_cost IS NULL THEN cached_tokens ELSE 0 END)",
+            "SUM(CASE WHEN stored_cost IS NULL AND total_tokens > 0 THEN 1 ELSE 0 END)",
+            "SUM(latency_ms)",
+            "COUNT(latency_ms)",
+            "SUM(cases_passed)",
+            "SUM(cases_failed)",
+            "SUM(cases_errored)",
+        ]
+        .map(String::from),
+    );
+    let mut group_by: Vec<String> = (0..dims.len()).map(|i| format!("d{}", i)).collect();
+    group_by.extend(["provider", "model", "status"].map(String::from));
+    let sql = format!(
+        "WITH runs AS ({}) SELECT {} FROM runs{} GROUP BY {} ORDER BY {}",
+        source.runs_sql(),
+        columns.join(", "),
+        where_sql,
+        group_by.join(", "),
+        group_by.join(", ")
+    );
+
+    let mut stmt = conn.prepare(&sql)?;
+    let n = dims.len();
+    let rows = stmt.query_map(params_from_iter(params), |row| {
+        let mut dims = Vec::with_capacity(n);
+        for i in 0..n {
+            dims.push(crate::database::rusqlite_value_to_json(&row.get_ref(i)?).unwrap_or(Value::Null));
+        }
+        let number = |i: usize| -> rusqlite::Result<f64> { Ok(row.get::<_, Option<f64>>(n + i)?.unwrap_or(0.0)) };
+        Ok(Partial {
+            dims,
+            provider: row.get::<_, Option<String>>(n)?.unwrap_or_default(),
+            model: row.get::<_, Option<String>>(n + 1)?.unwrap_or_default(),
+            status: row.get(n + 2)?,
+            runs: row.get(n + 3)?,
+            input_tokens: number(4)?,
+            output_tokens: number(5)?,
+            cached_tokens: number(6)?,
+            total_tokens: number(7)?,
+            stored_cost: number(8)?,
+            unpriced_tokens: (number(9)?, number(10)?, number(11)?),
+            unpriced_runs: row.get(n + 12)?,
+            latency_sum: number(13)?,
+            latency_count: row.get(n + 14)?,
+            cases: (row.get(n + 15)?, row.get(n + 16)?, row.get(n + 17)?),
+        })
+    })?;
+    Ok(rows.collect::<Result<_, _>>()?)
+}
+
+/// Nearest-rank latency percentiles (50, 90, 99) per group of `dims`.
+fn latency_percentiles(
+    conn: &Connection,
+    source: UsageSource,
+    dims: &[&str],
+    filter: &Filter,
+) -> AnyhowResult<HashMap<String, [Option<f64>; 3]>> {
+    let (where_sql, params) = filter.where_sql("latency_ms IS NOT NULL");
+    let partition = if dims.is_empty() { String::new() } else { format!("PARTITION BY {}", dims.join(", ")) };
+    let mut columns: Vec<String> = dims.iter().enumerate().map(|(i, dim)| format!("{} AS d{}", dim, i)).collect();
+    columns.push(format!("ROW_NUMBER() OVER ({} ORDER BY latency_ms) AS position", partition));
+    columns.push(format!("COUNT(*) OVER ({}) AS n", partition));
+    columns.push("latency_ms".to_string());
+    let sql = format!(
+        "WITH runs AS ({}), ranked AS (SELECT {} FROM runs{})
+         SELECT * FROM ranked WHERE position IN ((50 * n + 99) / 100, (90 * n + 99) / 100, (99 * n + 99) / 100)",
+        source.runs_sql(),
+        columns.join(", "),
+        where_sql
+    );
+
+    let mut stmt = conn.prepare(&sql)?;
+    let n = dims.len();
+    let mut rows = stmt.query(params_from_iter(params))?;
+    let mut percentiles: HashMap<String, [Option<f64>; 3]> = HashMap::new();
+    while let Some(row) = rows.next()? {
+        let mut key = Vec::with_capacity(n);
+        for i in 0..n {
+            key.push(crate::database::rusqlite_value_to_json(&row.get_ref(i)?)?);
+        }
+        let position: i64 = row.get(n)?;
+        let count: i64 = row.get(n + 1)?;
+        let latency: f64 = row.get(n + 2)?;
+        let entry = percentiles.entry(Value::Array(key).to_string()).or_default();
+        for (slot, p) in [50, 90, 99].into_iter().enumerate() {
+            if position == (p * count + 99) / 100 {
+                entry[slot] = Some(latency);
+            }
+        }
+    }
+    Ok(percentiles)
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct LatencyStats {
+    pub avg_ms: Option<f64>,
+    pub p50_ms: Option<f64>,
+    pub p90_ms: Option<f64>,
+    pub p99_ms: Option<f64>,
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct UsageGroup {
+    /// Value of each requested dimension, plus `scenario_title` / `agent_name`
+    pub key: Map<String, Value>,
+    pub runs: i64,
+    /// Runs by status
+    pub statuses: BTreeMap<String, i64>,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cached_tokens: i64,
+    pub total_tokens: i64,
+    pub cost_usd: f64,
+    /// Runs with tokens but no known pricing, left out of `cost_usd`
+    pub unpriced_runs: i64,
+    pub latency: LatencyStats,
+    /// Test case outcomes (eval runs only)
+    pub cases_passed: i64,
+    pub cases_failed: i64,
+    pub cases_errored: i64,
+    #[serde(skip)]
+    latency_sum: f64,
+    #[serde(skip)]
+    latency_count: i64,
+}
+
+/// Title of a scenario or name of an agent, if it still exists.
+fn runnable_name(conn: &Connection, kind: RunnableType, id: &str) -> AnyhowResult<Option<String>> {
+    let column = match kind {
+        RunnableType::Scenario => "title",
+        RunnableType::Agent => "name",
+    };
+    Ok(crate::database::db_select(conn, kind.table(), json!({ "where": { "id": id }, "columns": [column] }))?
+        .pop()
+        .and_then(|row| row.get(column).and_then(|v| v.as_str()).map(String::from)))
+}
+
+/// Aggregate `source` runs between `from` and `to` (unix ms), grouped by `dims` in order.
+pub fn usage_breakdown(
+    conn: &Connection,
+    source: UsageSource,
+    dims: &[Dimension],
+    from: Option<i64>,
+    to: Option<i64>,
+    runnable_type: Option<RunnableType>,
+) -> AnyhowResult<Vec<UsageGroup>> {
+    let mut runnable_type = runnable_type;
+    for dim in dims {
+        if let Some(kind) = dim.runnable() {
+            if runnable_type.is_some_and(|other| other != kind) {
+                return Err(anyhow!("Cannot group {} runs by {}", runnable_type.unwrap().as_str(), dim.name()));
+            }
+            runnable_type = Some(kind);
+        }
+    }
+    let filter = Filter { from, to, runnable_type, updated_since: None };
+    let columns: Vec<&str> = dims.iter().map(|dim| dim.column()).collect();
+
+    let mut groups: Vec<UsageGroup> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    for partial in grouped(conn, source, &columns, &filter)? {
+        let key = Value::Array(partial.dims.clone()).to_string();
+        let slot = *index.entry(key).or_insert_with(|| {
+            let mut group = UsageGroup::default();
+            for (dim, value) in dims.iter().zip(&partial.dims) {
+                group.key.insert(dim.name().to_string(), value.clone());
+            }
+            groups.push(group);
+            groups.len() - 1
+        });
+        let group = &mut groups[slot];
+        let (cost, unpriced) = partial.cost();
+        group.runs += partial.runs;
+        *group.statuses.entry(partial.status.clone()).or_default() += partial.runs;
+        group.input_tokens += partial.input_tokens as i64;
+        group.output_tokens += partial.output_tokens as i64;
+        group.cached_tokens += partial.cached_tokens as i64;
+        group.total_tokens += partial.total_tokens as i64;
+        group.cost_usd += cost;
+        group.unpriced_runs += unpriced;
+        group.latency_sum += partial.latency_sum;
+        group.latency_count += partial.latency_count;
+        group.cases_passed += partial.cases.0;
+        group.cases_failed += partial.cases.1;
+        group.cases_errored += partial.cases.2;
+    }
+
+    let percentiles = latency_percentiles(conn, source, &columns, &filter)?;
+    for group in &mut groups {
+        let dims_key: Vec<Value> = dims.iter().map(|dim| group.key[dim.name()].clone()).collect();
+        if let Some([p50, p90, p99]) = percentiles.get(&Value::Array(dims_key).to_string()) {
+            group.latency.p50_ms = *p50;
+            group.latency.p90_ms = *p90;
+            group.latency.p99_ms = *p99;
+        }
+        if group.latency_count > 0 {
+            group.latency.avg_ms = Some(group.latency_sum / group.latency_count as f64);
+        }
+        for dim in dims {
+            let (Some(kind), Some(id)) = (dim.runnable(), group.key.get(dim.name()).and_then(|v| v.as_str())) else {
+                continue;
+            };
+            let label = match kind {
+                RunnableType::Scenario => "scenario_title",
+                RunnableType::Agent => "agent_name",
+            };
+            let name = runnable_name(conn, kind, id)?;
+            group.key.insert(label.to_string(), json!(name));
+        }
+    }
+    Ok(groups)
+}
+
+// ── Daily rollup ─────────────────────────────────────────────────────────────
+
+/// Figures a run contributes to `usage_daily`, as stored in `usage_rollup_runs`.
+const CONTRIBUTION_COLUMNS: &str = "input_tokens, output_tokens, cached_tokens, total_tokens, cost_usd, latency_ms_sum, latency_count";
+const ROLLUP_KEY_COLUMNS: &str = "day, provider, model, runnable_type, runnable_id, status";
+
+/// Add (`sign` 1) or remove (`sign` -1) the contribution recorded for a run to its `usage_daily` row.
+fn apply_contribution(conn: &Connection, source: UsageSource, run_id: &str, sign: i64, now: i64) -> AnyhowResult<()> {
+    let totals: Vec<String> = CONTRIBUTION_COLUMNS
+        .split(", ")
+        .map(|column| format!("{column} = {column} + excluded.{column}"))
+        .collect();
+    let signed: Vec<String> = CONTRIBUTION_COLUMNS.split(", ").map(|column| format!("?3 * {column}")).collect();
+    conn.execute(
+        &format!(
+            "INSERT INTO usage_daily (source, {key}, runs, {columns}, refreshed_at)
+             SELECT source, {key}, ?3, {signed}, ?4 FROM usage_rollup_runs WHERE source = ?1 AND run_id = ?2
+             ON CONFLICT (day, source, provider, model, runnable_type, runnable_id, status)
+             DO UPDATE SET runs = runs + excluded.runs, {totals}, refreshed_at = excluded.refreshed_at",
+            key = ROLLUP_KEY_COLUMNS,
+            columns = CONTRIBUTION_COLUMNS,
+            signed = signed.join(", "),
+            totals = totals.join(", "),
+        ),
+        params![source.as_str(), run_id, sign, now],
+    )?;
+    Ok(())
+}
+
+/// Roll up every run written since the last refresh: its previous contribution (if any)
+/// is taken out of `usage_daily` and its current one added. Pruned runs are never
+/// revisited, so their days keep what they contributed. Returns the number of runs rolled up.
+pub fn refresh_daily_usage(conn: &mut Connection) -> AnyhowResult<usize> {
+    let tx = conn.transaction()?;
+    let since: i64 = crate::settings::get_json(&tx, ROLLUP_SETTING).unwrap_or(0);
+    let now = now_ms();
+
+    let mut refreshed = 0;
+    for source in [UsageSource::Executions, UsageSource::EvalRuns] {
+        // Rows written in the same millisecond as the last refresh are rolled up again,
+        // which is harmless since a run's old contribution is replaced.
+        let filter = Filter { updated_since: Some(since), ..Filter::default() };
+        for run in grouped(&tx, source, &["id", "day", "runnable_type", "runnable_id"], &filter)? {
+            let (cost, _) = run.cost();
+            let text = |i: usize| run.dims[i].as_str().unwrap_or_default().to_string();
+            let run_id = text(0);
+            apply_contribution(&tx, source, &run_id, -1, now)?;
+            tx.execute(
+                &format!(
+                    "INSERT OR REPLACE INTO usage_rollup_runs (source, run_id, {}, {})
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+                    ROLLUP_KEY_COLUMNS, CONTRIBUTION_COLUMNS
+                ),
+                params![
+                    source.as_str(),
+                    run_id,
+                    text(1),
+                    run.provider,
+                    run.model,
+                    text(2),
+                    text(3),
+                    run.status,
+                    run.input_tokens as i64,
+                    run.output_tokens as i64,
+                    run.cached_tokens as i64,
+                    run.total_tokens as i64,
+                    cost,
+                    run.latency_sum,
+                    run.latency_count,
+                ],
+            )?;
+            apply_contribution(&tx, source, &run_id, 1, now)?;
+            refreshed += 1;
+        }
+
+        // Records of runs that are gone are no longer needed; their figures stay in `usage_daily`.
+        tx.execute(
+            &format!(
+                "DELETE FROM usage_rollup_runs WHERE source = ?1 AND run_id NOT IN (SELECT id FROM {})",
+                source.as_str()
+            ),
+            [source.as_str()],
+        )?;
+    }
+    tx.execute("DELETE FROM usage_daily WHERE runs <= 0", [])?;
+
+    crate::settings::set_json(&tx, ROLLUP_SETTING, &now)?;
+    tx.commit()?;
+    Ok(refreshed)
+}
+
+// ── Commands ─────────────────────────────────────────────────────────────────
+
+/// Usage of runs between `from` and `to`, grouped by `group_by` (in that order).
+///
+/// `source`        – executions | eval_runs
+/// `group_by`      – any of day | provider | model | scenario | agent | status
+/// `from`, `to`    – unix ms range of the run start (default: all runs)
+/// `runnable_type` – only scenario or agent runs
+#[tauri::command]
+pub async fn usage_breakdown_cmd(
+    source: UsageSource,
+    group_by: Vec<Dimension>,
+    from: Option<i64>,
+    to: Option<i64>,
+    runnable_type: Option<RunnableType>,
+    state: tauri::State<'_, DbState>,
+) -> Result<Vec<UsageGroup>, String> {
+    state
+        .read(move |conn| usage_breakdown(conn, source, &group_by, from, to, runnable_type))
+        .await
+}
+
+/// Refresh the daily rollup, then return its rows for days `from_day`..=`to_day` (YYYY-MM-DD).
+#[tauri::command]
+pub async fn usage_daily(
+    from_day: Option<String>,
+    to_day: Option<String>,
+    state: tauri::State<'_, DbState>,
+) -> Result<Vec<Map<String, Value>>, String> {
+    state
+        .write(move |conn| {
+            refresh_daily_usage(conn)?;
+            crate::database::db_select(conn, "usage_daily", json!({
+                "where": {
+                    "day": {
+                        "gte": from_day.unwrap_or_default(),
+                        "lte": to_day.unwrap_or_else(|| "9999-12-31".to_string())
+                    }
+                },
+                "orderBy": "day",
+                "orderDirection": "asc"
+            }))
+        })
+        .await
+}
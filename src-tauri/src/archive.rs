@@ -0,0 +1,455 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result as AnyhowResult};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+use tauri::AppHandle;
+use ulid::Ulid;
+
+use crate::database::{now_ms, ChangeOp, DbChange};
+use crate::pool::DbState;
+
+// ── Workspace archives ───────────────────────────────────────────────────────
+//
+// An export is a single file in SQLite's archive format (the `sqlar` table, also
+// readable with `sqlite3 -A`):
+//
+//   manifest.json                 Manifest
+//   tables/<table>/<000000>.json  the rows of the table in chunks of `CHUNK_ROWS`,
+//                                 each a JSON array of objects
+//   blobs/<sha256>                attachment files from workspaces/<account_id>/blobs
+//
+// Version 1 archives hold each table in a single `tables/<table>.json`; they still import.
+//
+// API keys, environment variables and settings are machine-local (and secrets are
+// encrypted with a per-install key), so they are not part of an export.
+
+pub const ARCHIVE_FORMAT: &str = "reticle-workspace";
+pub const ARCHIVE_VERSION: u32 = 2;
+
+/// Rows per table entry, so neither export nor import holds a whole table in memory.
+const CHUNK_ROWS: usize = 500;
+
+/// Exported tables, parents before the tables that reference them.
+const TABLES: &[&str] = &[
+    "accounts",
+    "collections",
+    "scenarios",
+    "attachments",
+    "prompt_templates",
+    "agents",
+    "agent_memories",
+    "scenario_versions",
+    "agent_versions",
+    "tools",
+    "tool_links",
+    "eval_test_cases",
+    "eval_runs",
+    "eval_results",
+    "executions",
+    "telemetry_events",
+];
+
+enum RefTarget {
+    Table(&'static str),
+    /// `'scenario'|'agent'` in the given type column
+    Runnable(&'static str),
+}
+
+/// Columns holding ids of other exported rows, rewritten when rows are re-IDed.
+const REFERENCES: &[(&str, &str, RefTarget)] = &[
+    ("scenarios", "collection_id", RefTarget::Table("collections")),
+    ("attachments", "scenario_id", RefTarget::Table("scenarios")),
+    ("agent_memories", "agent_id", RefTarget::Table("agents")),
+    ("scenario_versions", "scenario_id", RefTarget::Table("scenarios")),
+    ("agent_versions", "agent_id", RefTarget::Table("agents")),
+    ("tool_links", "tool_id", RefTarget::Table("tools")),
+    ("tool_links", "toolable_id", RefTarget::Runnable("toolable_type")),
+    ("eval_test_cases", "runnable_id", RefTarget::Runnable("runnable_type")),
+    ("eval_runs", "runnable_id", RefTarget::Runnable("runnable_type")),
+    ("eval_results", "eval_run_id", RefTarget::Table("eval_runs")),
+    ("eval_results", "test_case_id", RefTarget::Table("eval_test_cases")),
+    ("executions", "runnable_id", RefTarget::Runnable("type")),
+];
+
+const MANIFEST_ENTRY: &str = "manifest.json";
+const FILE_MODE: i64 = 0o100644;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Manifest {
+    pub format: String,
+    pub version: u32,
+    /// Migration version of the exporting database
+    pub schema_version: usize,
+    /// unix ms
+    pub exported_at: i64,
+    pub account_id: String,
+    /// Row count per table
+    pub tables: BTreeMap<String, usize>,
+    pub blobs: usize,
+}
+
+/// What to do with an imported row whose id already exists.
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictStrategy {
+    /// Keep the existing row
+    Skip,
+    /// Replace the existing row with the imported one
+    Overwrite,
+    /// Insert the imported row under a new id; references to it follow
+    ReId,
+}
+
+#[derive(Serialize, Clone, Default, Debug)]
+pub struct TableImport {
+    pub inserted: usize,
+    pub overwritten: usize,
+    pub skipped: usize,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ImportSummary {
+    pub manifest: Manifest,
+    pub tables: BTreeMap<String, TableImport>,
+    pub blobs: usize,
+    /// Inserted and overwritten rows, for `DB_CHANGE_EVENT`
+    #[serde(skip)]
+    pub changes: Vec<DbChange>,
+}
+
+pub fn blob_dir(data_dir: &Path, account_id: &str) -> PathBuf {
+    data_dir.join("workspaces").join(account_id).join("blobs")
+}
+
+/// Blob directory of `account_id` for an import. The id comes from the archive (or
+/// the caller), so it must be a plain id of an account that exists by now.
+fn import_blob_dir(conn: &Connection, data_dir: &Path, account_id: &str) -> AnyhowResult<PathBuf> {
+    let plain = !account_id.is_empty() && account_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !plain || crate::database::db_count(conn, "accounts", json!({ "where": { "id": account_id } }))? == 0 {
+        return Err(anyhow!("Unknown account '{}' for attachment blobs", account_id));
+    }
+    Ok(blob_dir(data_dir, account_id))
+}
+
+fn runnable_table(runnable_type: &str) -> Option<&'static str> {
+    match runnable_type {
+        "scenario" => Some("scenarios"),
+        "agent" => Some("agents"),
+        _ => None,
+    }
+}
+
+fn table_columns(conn: &Connection, table: &str) -> rusqlite::Result<HashSet<String>> {
+    let mut stmt = conn.prepare("SELECT name FROM pragma_table_info(?1)")?;
+    let columns = stmt.query_map([table], |row| row.get(0))?.collect();
+    columns
+}
+
+// ── Archive file ─────────────────────────────────────────────────────────────
+
+fn write_entry(archive: &Connection, name: &str, data: &[u8]) -> rusqlite::Result<()> {
+    archive.execute(
+        "INSERT INTO sqlar (name, mode, mtime, sz, data) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![name, FILE_MODE, now_ms() / 1000, data.len() as i64, data],
+    )?;
+    Ok(())
+}
+
+fn chunk_entry(table: &str, chunk: usize) -> String {
+    format!("tables/{}/{:06}.json", table, chunk)
+}
+
+/// Entries holding the rows of `table`, in order (a single one in version 1 archives).
+fn table_entries(archive: &Connection, table: &str) -> AnyhowResult<Vec<String>> {
+    let prefix = format!("tables/{}/", table);
+    let mut stmt = archive.prepare(
+        "SELECT name FROM sqlar WHERE substr(name, 1, length(?1)) = ?1 OR name = ?2 ORDER BY name",
+    )?;
+    let names = stmt
+        .query_map(params![prefix, format!("tables/{}.json", table)], |row| row.get(0))?
+        .collect::<Result<_, _>>()?;
+    Ok(names)
+}
+
+fn read_entry(archive: &Connection, name: &str) -> AnyhowResult<Option<Vec<u8>>> {
+    let entry: Option<(i64, Vec<u8>)> = archive
+        .query_row("SELECT sz, data FROM sqlar WHERE name = ?1", [name], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })
+        .optional()?;
+    match entry {
+        // sqlar stores compressed entries with sz != length(data)
+        Some((size, data)) if size as usize != data.len() => {
+            Err(anyhow!("Archive entry '{}' is compressed, which is not supported", name))
+        }
+        Some((_, data)) => Ok(Some(data)),
+        None => Ok(None),
+    }
+}
+
+// ── Export ───────────────────────────────────────────────────────────────────
+
+/// Copy the blob of an attachment row into the archive, once. Only files inside the
+/// workspace blob directory are exported. Returns whether a blob was written.
+fn export_blob(archive: &Connection, blobs: &Path, row: &Map<String, Value>) -> AnyhowResult<bool> {
+    let Some(path) = row.get("path").and_then(|p| p.as_str()).map(Path::new) else {
+        return Ok(false);
+    };
+    let (Some(name), true) = (path.file_name(), path.parent() == Some(blobs)) else {
+        return Ok(false);
+    };
+    let entry = format!("blobs/{}", name.to_string_lossy());
+    let exists: bool = archive.query_row("SELECT EXISTS(SELECT 1 FROM sqlar WHERE name = ?1)", [&entry], |r| r.get(0))?;
+    if exists || !path.is_file() {
+        return Ok(false);
+    }
+    write_entry(archive, &entry, &std::fs::read(path)?)?;
+    Ok(true)
+}
+
+/// Write the workspace of `account_id` to a new archive at `dest` (replaced if it exists).
+pub fn export_to(conn: &Connection, data_dir: &Path, account_id: &str, dest: &Path) -> AnyhowResult<Manifest> {
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let partial = dest.with_extension("partial");
+    if partial.exists() {
+        std::fs::remove_file(&partial)?;
+    }
+
+    let blobs = blob_dir(data_dir, account_id);
+    let mut manifest = Manifest {
+        format: ARCHIVE_FORMAT.to_string(),
+        version: ARCHIVE_VERSION,
+        schema_version: crate::database::migration_count(),
+        exported_at: now_ms(),
+        account_id: account_id.to_string(),
+        tables: BTreeMap::new(),
+        blobs: 0,
+    };
+
+    {
+        let mut archive = Connection::open(&partial)?;
+        archive.execute_batch(
+            "CREATE TABLE sqlar (name TEXT PRIMARY KEY, mode INT, mtime INT, sz INT, data BLOB)",
+        )?;
+        let tx = archive.transaction()?;
+        // One read transaction, so every table comes from the same snapshot
+        let snapshot = conn.unchecked_transaction()?;
+
+        for table in TABLES {
+            let mut stmt = snapshot.prepare(&format!("SELECT * FROM {}", crate::database::identifier(table)?))?;
+            let columns: Vec<String> = stmt.column_names().iter().map(|c| c.to_string()).collect();
+            let mut rows = stmt.query([])?;
+            let mut chunk: Vec<Map<String, Value>> = Vec::with_capacity(CHUNK_ROWS);
+            let mut count = 0;
+            while let Some(row) = rows.next()? {
+                let mut map = Map::new();
+                for (i, column) in columns.iter().enumerate() {
+                    map.insert(column.clone(), crate::database::rusqlite_value_to_json(&row.get_ref(i)?)?);
+                }
+                if *table == "attachments" && export_blob(&tx, &blobs, &map)? {
+                    manifest.blobs += 1;
+                }
+                chunk.push(map);
+                count += 1;
+                if chunk.len() == CHUNK_ROWS {
+                    write_entry(&tx, &chunk_entry(table, count / CHUNK_ROWS - 1), &serde_json::to_vec(&chunk)?)?;
+                    chunk.clear();
+                }
+            }
+            if !chunk.is_empty() {
+                write_entry(&tx, &chunk_entry(table, count / CHUNK_ROWS), &serde_json::to_vec(&chunk)?)?;
+            }
+            manifest.tables.insert(table.to_string(), count);
+        }
+
+        write_entry(&tx, MANIFEST_ENTRY, &serde_json::to_vec_pretty(&manifest)?)?;
+        tx.commit()?;
+    }
+    std::fs::rename(&partial, dest)?;
+    Ok(manifest)
+}
+
+// ── Import ───────────────────────────────────────────────────────────────────
+
+/// Read and validate the manifest of an opened archive.
+pub fn read_manifest(archive: &Connection) -> AnyhowResult<Manifest> {
+    let data = read_entry(archive, MANIFEST_ENTRY)
+        .map_err(|_| anyhow!("Not a workspace archive"))?
+        .ok_or_else(|| anyhow!("Not a workspace archive (missing manifest)"))?;
+    let manifest: Manifest = serde_json::from_slice(&data)?;
+    if manifest.format != ARCHIVE_FORMAT {
+        return Err(anyhow!("Not a workspace archive (format '{}')", manifest.format));
+    }
+    if manifest.version > ARCHIVE_VERSION || manifest.schema_version > crate::database::migration_count() {
+        return Err(anyhow!("Archive was made by a newer version of the app"));
+    }
+    Ok(manifest)
+}
+
+/// Import the archive at `source` in one transaction. Blobs go to the workspace of
+/// `account_id` (default: the exported account) and attachment paths are rewritten.
+/// Columns the current schema does not have are dropped.
+pub fn import_from(
+    conn: &mut Connection,
+    data_dir: &Path,
+    source: &Path,
+    strategy: ConflictStrategy,
+    account_id: Option<&str>,
+) -> AnyhowResult<ImportSummary> {
+    let archive = Connection::open_with_flags(source, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    let manifest = read_manifest(&archive)?;
+
+    let tx = conn.transaction()?;
+    let mut new_ids: HashMap<&str, HashMap<String, String>> = HashMap::new();
+    let mut tables = BTreeMap::new();
+    let mut blobs_written = 0;
+    let mut blobs = None;
+    let mut changes = Vec::new();
+
+    for table in TABLES {
+        let entries = table_entries(&archive, table)?;
+        if entries.is_empty() {
+            continue;
+        }
+        let columns = table_columns(&tx, table)?;
+        let mut counts = TableImport::default();
+        let (mut inserted, mut overwritten) = (Vec::new(), Vec::new());
+
+        for entry in entries {
+            let data = read_entry(&archive, &entry)?.unwrap_or_default();
+            let rows: Vec<Map<String, Value>> = serde_json::from_slice(&data)?;
+            for mut row in rows {
+                row.retain(|column, _| columns.contains(column));
+                let id = row
+                    .get("id")
+                    .and_then(|v| v.as_str())
+                    .map(String::from)
+                    .ok_or_else(|| anyhow!("Row without id in '{}'", table))?;
+
+                // Point references at rows that were re-IDed earlier in the import
+                for (_, column, target) in REFERENCES.iter().filter(|(t, _, _)| t == table) {
+                    let target_table = match target {
+                        RefTarget::Table(target_table) => Some(*target_table),
+                        RefTarget::Runnable(type_column) => {
+                            row.get(*type_column).and_then(|v| v.as_str()).and_then(runnable_table)
+                        }
+                    };
+                    let new_id = target_table
+                        .and_then(|t| new_ids.get(t))
+                        .and_then(|ids| row.get(*column).and_then(|v| v.as_str()).and_then(|old| ids.get(old)))
+                        .cloned();
+                    if let Some(new_id) = new_id {
+                        row.insert(column.to_string(), json!(new_id));
+                    }
+                }
+
+                if *table == "attachments" {
+                    if let Some(name) = row.get("path").and_then(|p| p.as_str()).and_then(|p| Path::new(p).file_name()) {
+                        let name = name.to_string_lossy().to_string();
+                        if let Some(bytes) = read_entry(&archive, &format!("blobs/{}", name))? {
+                            if format!("{:x}", Sha256::digest(&bytes)) != name {
+                                return Err(anyhow!("Blob '{}' in the archive does not match its content", name));
+                            }
+                            // Blobs go to the workspace of the (possibly re-IDed) exported account
+                            if blobs.is_none() {
+                                let account = account_id.map(String::from).unwrap_or_else(|| {
+                                    new_ids
+                                        .get("accounts")
+                                        .and_then(|ids| ids.get(&manifest.account_id))
+                                        .cloned()
+                                        .unwrap_or_else(|| manifest.account_id.clone())
+                                });
+                                blobs = Some(import_blob_dir(&tx, data_dir, &account)?);
+                            }
+                            let dir = blobs.as_ref().unwrap();
+                            std::fs::create_dir_all(dir)?;
+                            let path = dir.join(&name);
+                            // Content-addressed (checked above): an existing file already has these bytes
+                            if !path.exists() {
+                                std::fs::write(&path, bytes)?;
+                                blobs_written += 1;
+                            }
+                            row.insert("path".to_string(), json!(path.to_string_lossy()));
+                        }
+                    }
+                }
+
+                let exists = crate::database::db_count(&tx, table, json!({ "where": { "id": id } }))? > 0;
+                match (exists, strategy) {
+                    (false, _) => {
+                        inserted.push(crate::database::db_insert(&tx, table, Value::Object(row))?);
+                        counts.inserted += 1;
+                    }
+                    (true, ConflictStrategy::Skip) => counts.skipped += 1,
+                    (true, ConflictStrategy::Overwrite) => {
+                        row.remove("id");
+                        if !row.is_empty() {
+                            crate::database::db_update(&tx, table, json!({ "where": { "id": id } }), Value::Object(row))?;
+                        }
+                        overwritten.push(id);
+                        counts.overwritten += 1;
+                    }
+                    (true, ConflictStrategy::ReId) => {
+                        let new_id = Ulid::new().to_string();
+                        row.insert("id".to_string(), json!(new_id));
+                        new_ids.entry(*table).or_default().insert(id, new_id.clone());
+                        crate::database::db_insert(&tx, table, Value::Object(row))?;
+                        inserted.push(new_id);
+                        counts.inserted += 1;
+                    }
+                }
+            }
+        }
+        changes.extend(DbChange::touched(table, ChangeOp::Insert, inserted));
+        changes.extend(DbChange::touched(table, ChangeOp::Update, overwritten));
+        tables.insert(table.to_string(), counts);
+    }
+
+    tx.commit()?;
+    Ok(ImportSummary { manifest, tables, blobs: blobs_written, changes })
+}
+
+// ── Commands ─────────────────────────────────────────────────────────────────
+
+/// Export the workspace (all rows of the exported tables plus the attachment blobs
+/// of `account_id`) to an archive file at `path`.
+#[tauri::command]
+pub async fn export_workspace(
+    path: String,
+    account_id: String,
+    state: tauri::State<'_, DbState>,
+    app: AppHandle,
+) -> Result<Manifest, String> {
+    let data_dir = crate::workspaces::active_data_dir(&app)?;
+    state.read(move |conn| export_to(conn, &data_dir, &account_id, Path::new(&path))).await
+}
+
+/// Import a workspace archive. Rows are written in one transaction, so a failed
+/// import leaves the database unchanged.
+///
+/// `path`       – archive written by `export_workspace`
+/// `strategy`   – skip | overwrite | re_id, for rows whose id already exists
+/// `account_id` – workspace receiving the blobs (default: the exported account)
+#[tauri::command]
+pub async fn import_workspace(
+    path: String,
+    strategy: ConflictStrategy,
+    account_id: Option<String>,
+    state: tauri::State<'_, DbState>,
+    app: AppHandle,
+) -> Result<ImportSummary, String> {
+    let data_dir = crate::workspaces::active_data_dir(&app)?;
+    let summary = state
+        .write(move |conn| import_from(conn, &data_dir, Path::new(&path), strategy, account_id.as_deref()))
+        .await?;
+    for change in &summary.changes {
+        crate::notify_change(&app, Some(change.clone()));
+    }
+    Ok(summary)
+}
@@ -0,0 +1,213 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{anyhow, Result as AnyhowResult};
+use rusqlite::backup::Backup;
+use rusqlite::{Connection, OpenFlags};
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::database::now_ms;
+use crate::pool::DbState;
+
+// ── Backups ──────────────────────────────────────────────────────────────────
+//
+// Copies go through SQLite's online backup API, which reads a consistent snapshot
+// of the database (including pages still in the WAL) while other writers continue.
+
+const BACKUP_DIR: &str = "backups";
+/// Automatic pre-migration backups kept per data directory; older ones are deleted.
+const AUTO_BACKUPS_KEPT: usize = 5;
+const AUTO_BACKUP_PREFIX: &str = "reticle-premigrate-";
+/// `settings.key` to turn automatic pre-migration backups off ("false").
+const AUTO_BACKUP_SETTING: &str = "backup_before_migrations";
+
+const PAGES_PER_STEP: std::ffi::c_int = 256;
+const STEP_PAUSE: Duration = Duration::from_millis(5);
+
+#[derive(Serialize, Clone, Debug)]
+pub struct BackupInfo {
+    pub path: String,
+    pub size_bytes: u64,
+    /// unix ms (file modification time)
+    pub created_at: i64,
+}
+
+impl BackupInfo {
+    fn from_path(path: &Path) -> AnyhowResult<Self> {
+        let metadata = std::fs::metadata(path)?;
+        let created_at = metadata
+            .modified()?
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis() as i64)
+            .unwrap_or(0);
+        Ok(Self {
+            path: path.to_string_lossy().to_string(),
+            size_bytes: metadata.len(),
+            created_at,
+        })
+    }
+}
+
+pub fn backup_dir(data_dir: &Path) -> PathBuf {
+    data_dir.join(BACKUP_DIR)
+}
+
+fn user_version(conn: &Connection) -> rusqlite::Result<usize> {
+    conn.pragma_query_value(None, "user_version", |row| row.get::<_, i64>(0))
+        .map(|v| v.max(0) as usize)
+}
+
+/// Write a consistent snapshot of `conn` to `dest` (replaced if it exists).
+pub fn backup_to(conn: &Connection, dest: &Path) -> AnyhowResult<BackupInfo> {
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    // Write next to the destination first so a failed backup never leaves a partial file
+    let partial = dest.with_extension("partial");
+    {
+        let mut target = Connection::open(&partial)?;
+        let backup = Backup::new(conn, &mut target)?;
+        backup.run_to_completion(PAGES_PER_STEP, STEP_PAUSE, None)?;
+    }
+    std::fs::rename(&partial, dest)?;
+    BackupInfo::from_path(dest)
+}
+
+/// Replace the contents of `conn` with the database at `source`, then bring it up to
+/// the current schema. Backups from a newer app version are refused.
+pub fn restore_from(conn: &mut Connection, source: &Path) -> AnyhowResult<()> {
+    let source_conn = Connection::open_with_flags(source, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    let check: String = source_conn.query_row("PRAGMA quick_check", [], |row| row.get(0))?;
+    if check != "ok" {
+        return Err(anyhow!("Backup failed the integrity check: {}", check));
+    }
+    let version = user_version(&source_conn)?;
+    if version > crate::database::migration_count() {
+        return Err(anyhow!(
+            "Backup was made by a newer version of the app (schema version {})",
+            version
+        ));
+    }
+
+    {
+        let backup = Backup::new(&source_conn, conn)?;
+        backup.run_to_completion(PAGES_PER_STEP, STEP_PAUSE, None)?;
+    }
+    crate::database::migrate(conn)?;
+    Ok(())
+}
+
+/// Backups in `data_dir`, newest first.
+pub fn list(data_dir: &Path) -> AnyhowResult<Vec<BackupInfo>> {
+    let dir = backup_dir(data_dir);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut backups = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) == Some("db") {
+            backups.push(BackupInfo::from_path(&path)?);
+        }
+    }
+    backups.sort_by(|a, b| b.created_at.cmp(&a.created_at).then_with(|| b.path.cmp(&a.path)));
+    Ok(backups)
+}
+
+/// Before migrating an existing database, snapshot it to the backups folder and keep
+/// the last few such snapshots. Skipped for new databases, when the schema is
+/// current, or when the `backup_before_migrations` setting is "false".
+pub fn backup_before_migrations(conn: &Connection, data_dir: &Path) -> AnyhowResult<Option<BackupInfo>> {
+    let version = user_version(conn)?;
+    if version == 0 || version >= crate::database::migration_count() {
+        return Ok(None);
+    }
+    let disabled = conn
+        .query_row(
+            "SELECT value FROM settings WHERE key = ?1",
+            [AUTO_BACKUP_SETTING],
+            |row| row.get::<_, String>(0),
+        )
+        .map(|value| value == "false")
+        .unwrap_or(false);
+    if disabled {
+        return Ok(None);
+    }
+
+    let dir = backup_dir(data_dir);
+    let dest = dir.join(format!("{}{}-v{}.db", AUTO_BACKUP_PREFIX, now_ms(), version));
+    let info = backup_to(conn, &dest)?;
+
+    let mut automatic: Vec<PathBuf> = std::fs::read_dir(&dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with(AUTO_BACKUP_PREFIX) && n.ends_with(".db"))
+        })
+        .collect();
+    // Names embed the timestamp, so they sort chronologically
+    automatic.sort();
+    let excess = automatic.len().saturating_sub(AUTO_BACKUPS_KEPT);
+    for old in automatic.into_iter().take(excess) {
+        std::fs::remove_file(old).ok();
+    }
+    Ok(Some(info))
+}
+
+// ── Commands ─────────────────────────────────────────────────────────────────
+
+/// Snapshot the database. `path` defaults to a timestamped file in the backups folder.
+#[tauri::command]
+pub async fn backup_database(
+    path: Option<String>,
+    state: tauri::State<'_, DbState>,
+    app: AppHandle,
+) -> Result<BackupInfo, String> {
+    let dest = match path {
+        Some(path) => PathBuf::from(path),
+        None => {
+            let data_dir = crate::workspaces::active_data_dir(&app)?;
+            backup_dir(&data_dir).join(format!("reticle-{}.db", now_ms()))
+        }
+    };
+    state.read(move |conn| backup_to(conn, &dest)).await
+}
+
+/// Backups in the backups folder, newest first.
+#[tauri::command]
+pub async fn list_backups(app: AppHandle) -> Result<Vec<BackupInfo>, String> {
+    let data_dir = crate::workspaces::active_data_dir(&app)?;
+    list(&data_dir).map_err(|e| e.to_string())
+}
+
+/// Replace the database with a backup. The current database is backed up first; its
+/// BackupInfo is returned so the restore itself can be undone. Windows receive
+/// `workspace-changed` and should reload their data.
+#[tauri::command]
+pub async fn restore_database(
+    path: String,
+    state: tauri::State<'_, DbState>,
+    app: AppHandle,
+) -> Result<BackupInfo, String> {
+    let source = PathBuf::from(path);
+    if !source.is_file() {
+        return Err(format!("Backup not found: {}", source.display()));
+    }
+    let data_dir = crate::workspaces::active_data_dir(&app)?;
+    let safety = backup_dir(&data_dir).join(format!("reticle-{}-pre-restore.db", now_ms()));
+
+    let handle = app.clone();
+    let info = state
+        .write(move |conn| {
+            let info = backup_to(conn, &safety)?;
+            restore_from(conn, &source)?;
+            crate::workspaces::reload_state(&handle, conn)?;
+            Ok(info)
+        })
+        .await?;
+
+    crate::workspaces::notify_replaced(&app)?;
+    Ok(info)
+}
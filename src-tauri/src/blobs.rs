@@ -2,7 +2,7 @@ use base64::prelude::*;
 use sha2::{Digest, Sha256};
 
 
-/// Stores file content in app data at workspaces/<account_id>/blobs/<sha256>.
+/// Stores file content in the active workspace at workspaces/<account_id>/blobs/<sha256>.
 /// Returns the full path to the stored file.
 /// If the same content already exists (same sha256), the path is returned without overwriting.
 #[tauri::command]
@@ -20,7 +20,7 @@ pub async fn store_attachment_blob(
     let hash = hasher.finalize();
     let sha256_hex = format!("{:x}", hash);
 
-    let root = crate::paths::app_data_root(&app)?;
+    let root = crate::workspaces::active_data_dir(&app)?;
     let blob_dir = root
         .join("workspaces")
         .join(&account_id)
@@ -40,10 +40,10 @@ pub async fn store_attachment_blob(
 }
 
 /// Reads a blob file and returns its content as base64.
-/// Validates that the path is within workspaces/<account_id>/blobs/ to prevent path traversal.
+/// Validates that the path is within the active workspace's workspaces/<account_id>/blobs/ to prevent path traversal.
 #[tauri::command]
 pub async fn read_attachment_blob(app: tauri::AppHandle, blob_path: String) -> Result<String, String> {
-    let root = crate::paths::app_data_root(&app)?;
+    let root = crate::workspaces::active_data_dir(&app)?;
     let path = std::path::PathBuf::from(&blob_path);
 
     if !path.exists() {
@@ -63,20 +63,36 @@ pub async fn read_attachment_blob(app: tauri::AppHandle, blob_path: String) -> R
     Ok(BASE64_STANDARD.encode(&bytes))
 }
 
-/// Deletes a blob file from the app data workspace folder.
-/// Validates that the path is within workspaces/<account_id>/blobs/ to prevent path traversal.
+/// Deletes a blob file from the active workspace folder.
+/// Validates that the path is within the active workspace's workspaces/<account_id>/blobs/ to prevent path traversal.
+/// Blobs are shared by content (and by cloned scenarios), so a blob still referenced by an
+/// attachment row is kept; delete the attachment rows first.
 #[tauri::command]
 pub async fn delete_attachment_blob(
     app: tauri::AppHandle,
     blob_path: String,
+    state: tauri::State<'_, crate::pool::DbState>,
 ) -> Result<(), String> {
-    let root = crate::paths::app_data_root(&app)?;
+    let root = crate::workspaces::active_data_dir(&app)?;
     let path = std::path::PathBuf::from(&blob_path);
 
     if !path.exists() {
         return Ok(()); // Already gone, idempotent
     }
 
+    let referenced = state
+        .read(move |conn| {
+            Ok(conn.query_row(
+                "SELECT EXISTS (SELECT 1 FROM attachments WHERE path = ?1)",
+                [&blob_path],
+                |row| row.get::<_, bool>(0),
+            )?)
+        })
+        .await?;
+    if referenced {
+        return Ok(());
+    }
+
     let path = path
         .canonicalize()
         .map_err(|e| format!("Invalid path: {}", e))?;
@@ -0,0 +1,497 @@
+use std::collections::{BTreeMap, HashSet};
+
+use anyhow::{anyhow, Result as AnyhowResult};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::AppHandle;
+
+use crate::database::{now_ms, ChangeOp, DbChange};
+use crate::pool::DbState;
+use crate::schema::Operation;
+use crate::versions::RunnableType;
+
+// ── Delete cascades ──────────────────────────────────────────────────────────
+//
+// tool_links, eval_test_cases, eval_runs and executions point at a scenario or agent
+// through a (type, id) pair that no foreign key covers, so SQLite cannot cascade
+// deletes to them. Deleting a scenario, an agent or a collection (and so its
+// scenarios) goes through here instead, and a policy stored in `settings` decides,
+// per relation, what happens when linked rows exist:
+//
+//   cascade – the linked rows are deleted (local tools left unlinked go with them)
+//   archive – the linked rows are kept and the scenario/agent is archived instead of
+//             deleted; a collection holding such a scenario is archived as well
+//   block   – the delete is refused
+//
+// Rows with real foreign keys (attachments, versions, memories, eval_results) follow
+// their ON DELETE clause; `plan` lists them too.
+
+/// `settings.key` holding the policy (JSON DeletePolicy).
+const POLICY_SETTING: &str = "delete_policy";
+
+/// Tables whose deletes follow the policy (db_delete_cmd, batches and the domain API).
+pub const CASCADING_TABLES: &[&str] = &["scenarios", "agents", "collections"];
+
+/// Polymorphic relations: (table, type column, id column).
+const RELATIONS: [(&str, &str, &str); 4] = [
+    ("tool_links", "toolable_type", "toolable_id"),
+    ("eval_test_cases", "runnable_type", "runnable_id"),
+    ("eval_runs", "runnable_type", "runnable_id"),
+    ("executions", "type", "runnable_id"),
+];
+
+/// Tables deleted explicitly, in this order; foreign keys take care of the rest.
+const DELETE_ORDER: [&str; 6] = ["tool_links", "eval_test_cases", "eval_runs", "executions", "tools", "scenarios"];
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkPolicy {
+    Cascade,
+    Archive,
+    Block,
+}
+
+fn cascade() -> LinkPolicy {
+    LinkPolicy::Cascade
+}
+
+fn archive() -> LinkPolicy {
+    LinkPolicy::Archive
+}
+
+/// What happens to each relation's rows when their scenario or agent is deleted.
+/// By default configuration rows go with it, and runs keep it (archived) around.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct DeletePolicy {
+    #[serde(default = "cascade")]
+    pub tool_links: LinkPolicy,
+    #[serde(default = "cascade")]
+    pub eval_test_cases: LinkPolicy,
+    #[serde(default = "archive")]
+    pub eval_runs: LinkPolicy,
+    #[serde(default = "archive")]
+    pub executions: LinkPolicy,
+}
+
+impl Default for DeletePolicy {
+    fn default() -> Self {
+        Self { tool_links: cascade(), eval_test_cases: cascade(), eval_runs: archive(), executions: archive() }
+    }
+}
+
+impl DeletePolicy {
+    fn for_relation(&self, table: &str) -> LinkPolicy {
+        match table {
+            "tool_links" => self.tool_links,
+            "eval_test_cases" => self.eval_test_cases,
+            "eval_runs" => self.eval_runs,
+            _ => self.executions,
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct RowSet {
+    pub table: String,
+    pub ids: Vec<String>,
+}
+
+/// What deleting rows of `table` does.
+#[derive(Serialize, Clone, Debug)]
+pub struct DeletePlan {
+    pub table: String,
+    /// Rows of `table` that are deleted
+    pub deleted: Vec<String>,
+    /// Rows archived instead of deleted (scenarios/agents, and collections holding them)
+    pub archived: Vec<RowSet>,
+    /// Rows deleted along with them, per table
+    pub removed: Vec<RowSet>,
+    /// Linked rows of relations with the `block` policy; the delete is refused unless empty
+    pub blocked_by: Vec<RowSet>,
+}
+
+impl DeletePlan {
+    /// A plain delete of `ids` from `table`, with nothing linked.
+    pub fn deleted(table: &str, ids: Vec<String>) -> Self {
+        Self { table: table.to_string(), deleted: ids, archived: Vec::new(), removed: Vec::new(), blocked_by: Vec::new() }
+    }
+
+    /// Changes to notify windows about once the plan is carried out.
+    pub fn changes(&self) -> Vec<DbChange> {
+        let mut changes: Vec<DbChange> =
+            DbChange::touched(&self.table, ChangeOp::Delete, self.deleted.clone()).into_iter().collect();
+        for set in &self.archived {
+            changes.extend(DbChange::touched(&set.table, ChangeOp::Update, set.ids.clone()));
+        }
+        for set in &self.removed {
+            changes.extend(DbChange::touched(&set.table, ChangeOp::Delete, set.ids.clone()));
+        }
+        changes
+    }
+
+    /// Rows of `table` that are gone from view: deleted or archived.
+    pub fn affected(&self) -> usize {
+        self.deleted.len()
+            + self.archived.iter().filter(|set| set.table == self.table).map(|set| set.ids.len()).sum::<usize>()
+    }
+}
+
+/// Policy persisted in the `settings` table (the default when unset).
+pub fn stored_policy(conn: &Connection) -> DeletePolicy {
+    crate::settings::get_json(conn, POLICY_SETTING).unwrap_or_default()
+}
+
+fn select_ids<P: rusqlite::Params>(conn: &Connection, sql: &str, params: P) -> AnyhowResult<Vec<String>> {
+    let mut stmt = conn.prepare(sql)?;
+    let ids = stmt.query_map(params, |row| row.get(0))?.collect::<Result<_, _>>()?;
+    Ok(ids)
+}
+
+/// `ids` as a JSON array, for `IN (SELECT value FROM json_each(?))`.
+fn id_list(ids: &[String]) -> String {
+    serde_json::to_string(ids).unwrap_or_else(|_| "[]".to_string())
+}
+
+fn into_sets(map: BTreeMap<String, Vec<String>>) -> Vec<RowSet> {
+    map.into_iter().filter(|(_, ids)| !ids.is_empty()).map(|(table, ids)| RowSet { table, ids }).collect()
+}
+
+/// Add the rows that ON DELETE CASCADE foreign keys remove along with rows `ids` of
+/// `table` to `removed`, recursively.
+fn foreign_key_cascade(
+    conn: &Connection,
+    table: &str,
+    ids: &[String],
+    removed: &mut BTreeMap<String, Vec<String>>,
+) -> AnyhowResult<()> {
+    if ids.is_empty() {
+        return Ok(());
+    }
+    let mut stmt = conn.prepare(
+        r#"SELECT m.name, f."from" FROM sqlite_master m JOIN pragma_foreign_key_list(m.name) f
+           WHERE m.type = 'table' AND lower(f."table") = lower(?1) AND f.on_delete = 'CASCADE'"#,
+    )?;
+    let children: Vec<(String, String)> =
+        stmt.query_map([table], |row| Ok((row.get(0)?, row.get(1)?)))?.collect::<Result<_, _>>()?;
+    for (child, column) in children {
+        let found = select_ids(
+            conn,
+            &format!("SELECT id FROM {} WHERE {} IN (SELECT value FROM json_each(?1))", child, column),
+            [id_list(ids)],
+        )?;
+        let known = removed.entry(child.clone()).or_default();
+        let new: Vec<String> = found.into_iter().filter(|id| !known.contains(id)).collect();
+        known.extend(new.iter().cloned());
+        foreign_key_cascade(conn, &child, &new, removed)?;
+    }
+    Ok(())
+}
+
+/// Work out what deleting rows `ids` of `table` does under `policy`, without changing
+/// anything. Tables other than scenarios, agents and collections only have their
+/// foreign key cascades.
+pub fn plan(conn: &Connection, table: &str, ids: &[String], policy: &DeletePolicy) -> AnyhowResult<DeletePlan> {
+    let runnables: Vec<(RunnableType, String)> = match table {
+        "scenarios" => ids.iter().map(|id| (RunnableType::Scenario, id.clone())).collect(),
+        "agents" => ids.iter().map(|id| (RunnableType::Agent, id.clone())).collect(),
+        "collections" => select_ids(
+            conn,
+            "SELECT id FROM scenarios WHERE collection_id IN (SELECT value FROM json_each(?1))",
+            [id_list(ids)],
+        )?
+        .into_iter()
+        .map(|id| (RunnableType::Scenario, id))
+        .collect(),
+        _ => Vec::new(),
+    };
+
+    let mut removed: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    let mut blocked_by: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    let mut kept: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    let mut dropped: Vec<String> = Vec::new();
+    for (kind, id) in runnables {
+        let mut cascaded = Vec::new();
+        let mut keep = false;
+        for (relation, type_column, id_column) in RELATIONS {
+            let linked = select_ids(
+                conn,
+                &format!("SELECT id FROM {} WHERE {} = ?1 AND {} = ?2", relation, type_column, id_column),
+                [kind.as_str(), id.as_str()],
+            )?;
+            if linked.is_empty() {
+                continue;
+            }
+            match policy.for_relation(relation) {
+                LinkPolicy::Cascade => cascaded.push((relation, linked)),
+                LinkPolicy::Archive => keep = true,
+                LinkPolicy::Block => blocked_by.entry(relation.to_string()).or_default().extend(linked),
+            }
+        }
+        if keep {
+            kept.entry(kind.table().to_string()).or_default().push(id);
+        } else {
+            for (relation, linked) in cascaded {
+                removed.entry(relation.to_string()).or_default().extend(linked);
+            }
+            dropped.push(id);
+        }
+    }
+
+    // Local tools whose every link goes away
+    if let Some(links) = removed.get("tool_links") {
+        let tools = select_ids(
+            conn,
+            "SELECT DISTINCT t.id FROM tools t JOIN tool_links l ON l.tool_id = t.id
+             WHERE t.is_global = 0 AND l.id IN (SELECT value FROM json_each(?1))
+               AND NOT EXISTS (SELECT 1 FROM tool_links o
+                               WHERE o.tool_id = t.id AND o.id NOT IN (SELECT value FROM json_each(?1)))",
+            [id_list(links)],
+        )?;
+        removed.insert("tools".to_string(), tools);
+    }
+
+    let deleted = match table {
+        "scenarios" | "agents" => dropped,
+        "collections" => {
+            // A collection still holding an archived scenario is archived with it
+            let holding: HashSet<String> = match kept.get("scenarios") {
+                Some(scenarios) => select_ids(
+                    conn,
+                    "SELECT DISTINCT collection_id FROM scenarios WHERE id IN (SELECT value FROM json_each(?1))",
+                    [id_list(scenarios)],
+                )?
+                .into_iter()
+                .collect(),
+                None => HashSet::new(),
+            };
+            let (archived, deleted): (Vec<String>, Vec<String>) = ids.iter().cloned().partition(|id| holding.contains(id));
+            if !archived.is_empty() {
+                kept.insert("collections".to_string(), archived);
+            }
+            removed.entry("scenarios".to_string()).or_default().extend(dropped);
+            deleted
+        }
+        _ => ids.to_vec(),
+    };
+
+    let explicit: Vec<(String, Vec<String>)> = removed.iter().map(|(t, ids)| (t.clone(), ids.clone())).collect();
+    foreign_key_cascade(conn, table, &deleted, &mut removed)?;
+    for (child, ids) in explicit {
+        foreign_key_cascade(conn, &child, &ids, &mut removed)?;
+    }
+    removed.remove(table);
+
+    Ok(DeletePlan {
+        table: table.to_string(),
+        deleted,
+        archived: into_sets(kept),
+        removed: into_sets(removed),
+        blocked_by: into_sets(blocked_by),
+    })
+}
+
+/// Carry out `plan` (refused when something blocks it). Run it in a transaction.
+fn apply(conn: &Connection, plan: &DeletePlan) -> AnyhowResult<()> {
+    if !plan.blocked_by.is_empty() {
+        let blockers: Vec<String> = plan.blocked_by.iter().map(|set| format!("{} ({})", set.table, set.ids.len())).collect();
+        return Err(anyhow!(
+            "Cannot delete from {}: linked rows in {} are set to block deletes",
+            plan.table,
+            blockers.join(", ")
+        ));
+    }
+    for table in DELETE_ORDER {
+        if let Some(set) = plan.removed.iter().find(|set| set.table == table) {
+            conn.execute(
+                &format!("DELETE FROM {} WHERE id IN (SELECT value FROM json_each(?1))", table),
+                [id_list(&set.ids)],
+            )?;
+        }
+    }
+    let now = now_ms();
+    for set in &plan.archived {
+        conn.execute(
+            &format!(
+                "UPDATE {} SET archived_at = coalesce(archived_at, ?1), updated_at = ?1
+                 WHERE id IN (SELECT value FROM json_each(?2))",
+                set.table
+            ),
+            rusqlite::params![now, id_list(&set.ids)],
+        )?;
+    }
+    conn.execute(
+        &format!("DELETE FROM {} WHERE id IN (SELECT value FROM json_each(?1))", plan.table),
+        [id_list(&plan.deleted)],
+    )?;
+    Ok(())
+}
+
+/// Delete rows `ids` of `table` under the stored policy and return what was done.
+/// Run it in a transaction.
+pub fn delete_rows(conn: &Connection, table: &str, ids: &[String]) -> AnyhowResult<DeletePlan> {
+    let plan = plan(conn, table, ids, &stored_policy(conn))?;
+    apply(conn, &plan)?;
+    Ok(plan)
+}
+
+/// Delete the rows of `table` matching `query` (same shape as for `db_delete`) under
+/// the stored policy, in one transaction.
+pub fn delete_matching(conn: &mut Connection, table: &str, query: &Value) -> AnyhowResult<DeletePlan> {
+    let tx = conn.transaction()?;
+    let ids = crate::database::matching_ids(&tx, table, query)?;
+    let plan = delete_rows(&tx, table, &ids)?;
+    tx.commit()?;
+    Ok(plan)
+}
+
+/// Tell every window about the rows a delete removed or archived.
+pub fn notify(app: &AppHandle, plan: &DeletePlan) {
+    for change in plan.changes() {
+        crate::notify_change(app, Some(change));
+    }
+}
+
+// ── Commands ─────────────────────────────────────────────────────────────────
+
+#[tauri::command]
+pub async fn get_delete_policy(state: tauri::State<'_, DbState>) -> Result<DeletePolicy, String> {
+    state.read(|conn| Ok(stored_policy(conn))).await
+}
+
+#[tauri::command]
+pub async fn set_delete_policy(
+    policy: DeletePolicy,
+    state: tauri::State<'_, DbState>,
+) -> Result<DeletePolicy, String> {
+    let stored = policy.clone();
+    state.write(move |conn| crate::settings::set_json(conn, POLICY_SETTING, &stored)).await?;
+    Ok(policy)
+}
+
+/// What `db_delete_cmd` with the same arguments would delete, archive or be blocked by.
+///
+/// `table` – table to delete from
+/// `query` – same shape as for `db_delete_cmd`
+#[tauri::command]
+pub async fn preview_delete(
+    table: String,
+    query: Value,
+    state: tauri::State<'_, DbState>,
+    schema: tauri::State<'_, crate::schema::SchemaState>,
+) -> Result<DeletePlan, String> {
+    schema.authorize(&table, Operation::Delete, Some(&query), None).map_err(|e| e.to_string())?;
+    state
+        .read(move |conn| {
+            let ids = crate::database::matching_ids(conn, &table, &query)?;
+            plan(conn, &table, &ids, &stored_policy(conn))
+        })
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Migrated in-memory database with two collections:
+    /// c1 holds s1, which has an execution, an eval test case and a link to local tool t1;
+    /// c2 holds s2, whose only link is to global tool g.
+    fn workspace() -> Connection {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.pragma_update(None, "foreign_keys", true).unwrap();
+        crate::database::migrate(&mut conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO collections (id, name, created_at, updated_at) VALUES ('c1', 'One', 0, 0), ('c2', 'Two', 0, 0);
+             INSERT INTO scenarios (id, collection_id, title, provider, model, system_prompt, user_prompt, params_json, created_at, updated_at)
+               VALUES ('s1', 'c1', 'S1', 'openai', 'gpt', '', '', '{}', 0, 0), ('s2', 'c2', 'S2', 'openai', 'gpt', '', '', '{}', 0, 0);
+             INSERT INTO tools (id, name, is_global, created_at, updated_at) VALUES ('t1', 'local', 0, 0, 0), ('g', 'global', 1, 0, 0);
+             INSERT INTO tool_links (id, tool_id, toolable_id, toolable_type, created_at, updated_at)
+               VALUES ('l1', 't1', 's1', 'scenario', 0, 0), ('l2', 'g', 's2', 'scenario', 0, 0);
+             INSERT INTO eval_test_cases (id, runnable_id, runnable_type, created_at, updated_at) VALUES ('tc1', 's1', 'scenario', 0, 0);
+             INSERT INTO executions (id, type, runnable_id, snapshot_json, status, created_at, updated_at)
+               VALUES ('e1', 'scenario', 's1', '{}', 'succeeded', 0, 0);",
+        )
+        .unwrap();
+        conn
+    }
+
+    fn collections() -> Vec<String> {
+        vec!["c1".to_string(), "c2".to_string()]
+    }
+
+    fn sets(sets: &[RowSet]) -> Vec<(&str, Vec<&str>)> {
+        sets.iter().map(|set| (set.table.as_str(), set.ids.iter().map(String::as_str).collect())).collect()
+    }
+
+    fn ids(conn: &Connection, table: &str) -> Vec<String> {
+        select_ids(conn, &format!("SELECT id FROM {} ORDER BY id", table), []).unwrap()
+    }
+
+    fn archived(conn: &Connection, table: &str) -> Vec<String> {
+        select_ids(conn, &format!("SELECT id FROM {} WHERE archived_at IS NOT NULL ORDER BY id", table), []).unwrap()
+    }
+
+    #[test]
+    fn archive_keeps_collections_holding_runs() {
+        let conn = workspace();
+        let plan = plan(&conn, "collections", &collections(), &DeletePolicy::default()).unwrap();
+        assert_eq!(plan.deleted, ["c2"]);
+        assert_eq!(sets(&plan.archived), [("collections", vec!["c1"]), ("scenarios", vec!["s1"])]);
+        // l2 goes with s2, but global tool g stays
+        assert_eq!(sets(&plan.removed), [("scenarios", vec!["s2"]), ("tool_links", vec!["l2"])]);
+        assert!(plan.blocked_by.is_empty());
+        assert_eq!(plan.affected(), 2);
+
+        apply(&conn, &plan).unwrap();
+        assert_eq!(ids(&conn, "collections"), ["c1"]);
+        assert_eq!(archived(&conn, "collections"), ["c1"]);
+        assert_eq!(ids(&conn, "scenarios"), ["s1"]);
+        assert_eq!(archived(&conn, "scenarios"), ["s1"]);
+        assert_eq!(ids(&conn, "tool_links"), ["l1"]);
+        assert_eq!(ids(&conn, "tools"), ["g", "t1"]);
+        assert_eq!(ids(&conn, "executions"), ["e1"]);
+        assert_eq!(ids(&conn, "eval_test_cases"), ["tc1"]);
+    }
+
+    #[test]
+    fn cascade_removes_linked_rows_and_orphaned_local_tools() {
+        let conn = workspace();
+        let policy = DeletePolicy { executions: LinkPolicy::Cascade, ..DeletePolicy::default() };
+        crate::settings::set_json(&conn, POLICY_SETTING, &policy).unwrap();
+        let plan = delete_rows(&conn, "collections", &collections()).unwrap();
+        assert_eq!(plan.deleted, ["c1", "c2"]);
+        assert!(plan.archived.is_empty());
+        assert_eq!(
+            sets(&plan.removed),
+            [
+                ("eval_test_cases", vec!["tc1"]),
+                ("executions", vec!["e1"]),
+                ("scenarios", vec!["s1", "s2"]),
+                ("tool_links", vec!["l1", "l2"]),
+                ("tools", vec!["t1"]),
+            ]
+        );
+        assert_eq!(plan.affected(), 2);
+        let changes = plan.changes();
+        assert!(changes.iter().all(|change| change.op == ChangeOp::Delete));
+        assert_eq!(changes.len(), 6);
+
+        for table in ["collections", "scenarios", "tool_links", "executions", "eval_test_cases"] {
+            assert!(ids(&conn, table).is_empty(), "{} left behind", table);
+        }
+        assert_eq!(ids(&conn, "tools"), ["g"]);
+    }
+
+    #[test]
+    fn block_refuses_the_delete() {
+        let conn = workspace();
+        let policy = DeletePolicy { executions: LinkPolicy::Block, ..DeletePolicy::default() };
+        let plan = plan(&conn, "collections", &collections(), &policy).unwrap();
+        assert_eq!(sets(&plan.blocked_by), [("executions", vec!["e1"])]);
+
+        assert!(apply(&conn, &plan).is_err());
+        assert_eq!(ids(&conn, "collections"), ["c1", "c2"]);
+        assert_eq!(ids(&conn, "scenarios"), ["s1", "s2"]);
+        assert!(archived(&conn, "collections").is_empty());
+    }
+}
@@ -0,0 +1,178 @@
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, Result as AnyhowResult};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use tauri::AppHandle;
+
+use crate::cascade::RowSet;
+use crate::database::{ChangeOp, DbChange};
+use crate::pool::DbState;
+use crate::versions::RunnableType;
+
+// ── Cloning ──────────────────────────────────────────────────────────────────
+//
+// Deep copies of a scenario, an agent or a collection with its scenarios. Rows are
+// copied column for column under new ids, so nothing the typed models leave out is
+// lost. Local tools are copied and linked to the copy; global tools are linked as
+// they are. Attachments are new rows pointing at the same content-addressed blobs
+// (blobs::delete_attachment_blob keeps a blob while any row still uses it). Runs
+// and version history are not copied: the copy starts at version 1.
+
+const DEFAULT_SUFFIX: &str = " (copy)";
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CloneType {
+    Scenario,
+    Agent,
+    Collection,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct CloneResult {
+    pub entity_type: CloneType,
+    /// Id of the copy
+    pub id: String,
+    /// Every row created, per table
+    pub created: Vec<RowSet>,
+}
+
+/// Copies rows and remembers what it created.
+struct Copier<'a> {
+    conn: &'a Connection,
+    created: BTreeMap<String, Vec<String>>,
+}
+
+impl<'a> Copier<'a> {
+    fn rows(&self, table: &str, filter: Value) -> AnyhowResult<Vec<Map<String, Value>>> {
+        crate::database::db_select(self.conn, table, json!({ "where": filter, "orderBy": "created_at", "orderDirection": "asc" }))
+    }
+
+    fn row(&self, table: &str, id: &str) -> AnyhowResult<Map<String, Value>> {
+        self.rows(table, json!({ "id": id }))?
+            .pop()
+            .ok_or_else(|| anyhow!("{} '{}' not found", table, id))
+    }
+
+    /// Insert a copy of `row` with a new id and timestamps, `changes` applied.
+    fn copy(&mut self, table: &str, mut row: Map<String, Value>, changes: Value) -> AnyhowResult<String> {
+        for column in ["id", "created_at", "updated_at"] {
+            row.remove(column);
+        }
+        if let Value::Object(changes) = changes {
+            row.extend(changes);
+        }
+        let id = crate::database::db_insert(self.conn, table, Value::Object(row))?;
+        self.created.entry(table.to_string()).or_default().push(id.clone());
+        Ok(id)
+    }
+
+    /// Copy a scenario or agent with its tools, eval test cases, attachments and
+    /// (when `memories`) agent memories. `changes` apply to the copied row.
+    fn runnable(&mut self, kind: RunnableType, id: &str, changes: Value, memories: bool) -> AnyhowResult<String> {
+        let row = self.row(kind.table(), id)?;
+        let mut changes = changes;
+        changes["version"] = json!(1);
+        changes["archived_at"] = Value::Null;
+        let copy_id = self.copy(kind.table(), row, changes)?;
+
+        for link in self.rows("tool_links", json!({ "toolable_type": kind.as_str(), "toolable_id": id }))? {
+            let tool_id = link.get("tool_id").and_then(|v| v.as_str()).unwrap_or_default().to_string();
+            let tool = self.row("tools", &tool_id)?;
+            let global = tool.get("is_global").and_then(|v| v.as_i64()).unwrap_or(0) != 0;
+            let linked_id = if global { tool_id } else { self.copy("tools", tool, json!({}))? };
+            self.copy("tool_links", link, json!({ "tool_id": linked_id, "toolable_id": copy_id }))?;
+        }
+        for case in self.rows("eval_test_cases", json!({ "runnable_type": kind.as_str(), "runnable_id": id }))? {
+            self.copy("eval_test_cases", case, json!({ "runnable_id": copy_id }))?;
+        }
+        match kind {
+            RunnableType::Scenario => {
+                for attachment in self.rows("attachments", json!({ "scenario_id": id }))? {
+                    self.copy("attachments", attachment, json!({ "scenario_id": copy_id }))?;
+                }
+            }
+            RunnableType::Agent if memories => {
+                for memory in self.rows("agent_memories", json!({ "agent_id": id }))? {
+                    self.copy("agent_memories", memory, json!({ "agent_id": copy_id }))?;
+                }
+            }
+            RunnableType::Agent => {}
+        }
+        Ok(copy_id)
+    }
+}
+
+fn suffixed(row: &Map<String, Value>, column: &str, suffix: &str) -> Value {
+    let name = row.get(column).and_then(|v| v.as_str()).unwrap_or_default();
+    json!(format!("{}{}", name, suffix))
+}
+
+/// Deep-copy `id` in one transaction. The copy's name (a collection's, not its
+/// scenarios') gets `suffix`; archived scenarios of a collection are left out.
+pub fn deep_clone(
+    conn: &mut Connection,
+    entity_type: CloneType,
+    id: &str,
+    suffix: &str,
+    memories: bool,
+) -> AnyhowResult<CloneResult> {
+    let tx = conn.transaction()?;
+    let mut copier = Copier { conn: &tx, created: BTreeMap::new() };
+    let copy_id = match entity_type {
+        CloneType::Scenario => {
+            let title = suffixed(&copier.row("scenarios", id)?, "title", suffix);
+            copier.runnable(RunnableType::Scenario, id, json!({ "title": title }), memories)?
+        }
+        CloneType::Agent => {
+            let name = suffixed(&copier.row("agents", id)?, "name", suffix);
+            copier.runnable(RunnableType::Agent, id, json!({ "name": name }), memories)?
+        }
+        CloneType::Collection => {
+            let collection = copier.row("collections", id)?;
+            let name = suffixed(&collection, "name", suffix);
+            let copy_id = copier.copy("collections", collection, json!({ "name": name, "archived_at": null }))?;
+            for scenario in copier.rows("scenarios", json!({ "collection_id": id, "archived_at": null }))? {
+                let scenario_id = scenario.get("id").and_then(|v| v.as_str()).unwrap_or_default().to_string();
+                copier.runnable(RunnableType::Scenario, &scenario_id, json!({ "collection_id": copy_id }), memories)?;
+            }
+            copy_id
+        }
+    };
+    let created = copier
+        .created
+        .into_iter()
+        .map(|(table, ids)| RowSet { table, ids })
+        .collect();
+    tx.commit()?;
+    Ok(CloneResult { entity_type, id: copy_id, created })
+}
+
+// ── Commands ─────────────────────────────────────────────────────────────────
+
+/// Deep-copy a scenario, an agent or a collection (see `deep_clone`).
+///
+/// `entity_type`      – scenario | agent | collection
+/// `id`               – row to copy
+/// `suffix`           – appended to the copy's title/name (default: " (copy)")
+/// `include_memories` – also copy agent memories (default: false)
+#[tauri::command]
+pub async fn clone_entity(
+    entity_type: CloneType,
+    id: String,
+    suffix: Option<String>,
+    include_memories: Option<bool>,
+    state: tauri::State<'_, DbState>,
+    app: AppHandle,
+) -> Result<CloneResult, String> {
+    let suffix = suffix.unwrap_or_else(|| DEFAULT_SUFFIX.to_string());
+    let result = state
+        .write(move |conn| deep_clone(conn, entity_type, &id, &suffix, include_memories.unwrap_or(false)))
+        .await?;
+    for set in &result.created {
+        crate::notify_change(&app, DbChange::touched(&set.table, ChangeOp::Insert, set.ids.clone()));
+    }
+    Ok(result)
+}
@@ -0,0 +1,362 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use futures_util::StreamExt;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use tauri::ipc::Channel;
+use tauri::{AppHandle, Manager};
+use tokio::task::{AbortHandle, JoinSet};
+use ulid::Ulid;
+
+use crate::database::now_ms;
+use crate::governor::{estimate_tokens, GovernorState};
+use crate::providers::{find_provider, rewrite_request_body};
+
+// ── State ────────────────────────────────────────────────────────────────────
+
+/// In-flight comparisons, so a run can be cancelled from another command.
+pub struct CompareManager {
+    client: Client,
+    runs: HashMap<String, Vec<AbortHandle>>,
+}
+
+impl CompareManager {
+    pub fn new() -> Self {
+        Self {
+            client: Client::builder()
+                .user_agent("reticle/1.0")
+                .build()
+                .expect("Failed to create HTTP client"),
+            runs: HashMap::new(),
+        }
+    }
+}
+
+pub type CompareState = Arc<Mutex<CompareManager>>;
+
+// ── Types ────────────────────────────────────────────────────────────────────
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct CompareTarget {
+    pub provider: String,
+    pub model: String,
+}
+
+/// A target with everything needed to call it, resolved up front so the
+/// comparison itself does not depend on app state (reusable from a headless runner).
+pub struct ResolvedTarget {
+    pub target: CompareTarget,
+    pub api_key: Option<String>,
+}
+
+#[derive(Serialize, Clone, Default, Debug)]
+pub struct CompareUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cached_tokens: u64,
+    pub total_tokens: u64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct TargetResult {
+    pub target: usize,
+    pub provider: String,
+    pub model: String,
+    pub status: &'static str, // succeeded|failed|canceled
+    pub output: String,
+    pub finish_reason: Option<String>,
+    pub error: Option<String>,
+    pub latency_ms: u64,
+    pub time_to_first_token_ms: Option<u64>,
+    pub usage: CompareUsage,
+    pub cost_usd: Option<f64>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Comparison {
+    pub id: String,
+    pub started_at: i64,
+    pub ended_at: i64,
+    pub results: Vec<TargetResult>,
+}
+
+/// Events streamed to the caller while a comparison runs, tagged by target index.
+#[derive(Serialize, Clone, Debug)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum CompareEvent {
+    Started { target: usize, provider: String, model: String },
+    Delta { target: usize, text: String },
+    Finished { target: usize, result: TargetResult },
+}
+
+// ── Core ─────────────────────────────────────────────────────────────────────
+
+/// Run one normalized chat request (an OpenAI-style chat body without `model`)
+/// against every target concurrently.
+///
+/// `on_spawn` receives the abort handles of the per-target tasks so the caller can
+/// cancel them; aborted targets are reported with status "canceled". When a
+/// `governor` is given, each target waits for the client-side rate limiter first.
+pub async fn run_comparison<F>(
+    client: &Client,
+    governor: Option<GovernorState>,
+    id: String,
+    request: Map<String, Value>,
+    targets: Vec<ResolvedTarget>,
+    on_event: F,
+    on_spawn: impl FnOnce(Vec<AbortHandle>),
+) -> Comparison
+where
+    F: Fn(CompareEvent) + Clone + Send + Sync + 'static,
+{
+    let started_at = now_ms();
+    let mut tasks = JoinSet::new();
+    let mut task_targets = HashMap::new();
+    let mut handles = Vec::new();
+    let mut results: Vec<Option<TargetResult>> = vec![None; targets.len()];
+
+    for (index, resolved) in targets.into_iter().enumerate() {
+        on_event(CompareEvent::Started {
+            target: index,
+            provider: resolved.target.provider.clone(),
+            model: resolved.target.model.clone(),
+        });
+        let client = client.clone();
+        let request = request.clone();
+        let on_event = on_event.clone();
+        let target = resolved.target.clone();
+        let resolved_target = target.clone();
+        let governor = governor.clone();
+        let handle = tasks.spawn(async move {
+            let _admission = match &governor {
+                Some(governor) => {
+                    let body_len = serde_json::to_string(&request).map_or(0, |s| s.len());
+                    let tokens = estimate_tokens(Some(&request), body_len);
+                    Some(governor.admit(&target.provider, Some(&target.model), tokens).await)
+                }
+                None => None,
+            };
+            (index, run_target(&client, index, request, resolved, &on_event).await)
+        });
+        task_targets.insert(handle.id(), (index, resolved_target));
+        handles.push(handle);
+    }
+    on_spawn(handles);
+
+    while let Some(joined) = tasks.join_next_with_id().await {
+        let (index, result) = match joined {
+            Ok((_, (index, result))) => (index, result),
+            Err(e) => {
+                let Some((index, target)) = task_targets.get(&e.id()).cloned() else {
+                    continue;
+                };
+                let mut result = empty_result(index, &target);
+                result.status = if e.is_cancelled() { "canceled" } else { "failed" };
+                result.error = Some(e.to_string());
+                on_event(CompareEvent::Finished { target: index, result: result.clone() });
+                (index, result)
+            }
+        };
+        results[index] = Some(result);
+    }
+
+    Comparison {
+        id,
+        started_at,
+        ended_at: now_ms(),
+        results: results.into_iter().flatten().collect(),
+    }
+}
+
+fn empty_result(index: usize, target: &CompareTarget) -> TargetResult {
+    TargetResult {
+        target: index,
+        provider: target.provider.clone(),
+        model: target.model.clone(),
+        status: "succeeded",
+        output: String::new(),
+        finish_reason: None,
+        error: None,
+        latency_ms: 0,
+        time_to_first_token_ms: None,
+        usage: CompareUsage::default(),
+        cost_usd: None,
+    }
+}
+
+async fn run_target<F>(
+    client: &Client,
+    index: usize,
+    mut body: Map<String, Value>,
+    resolved: ResolvedTarget,
+    on_event: &F,
+) -> TargetResult
+where
+    F: Fn(CompareEvent),
+{
+    let start_time = std::time::Instant::now();
+    let mut result = empty_result(index, &resolved.target);
+
+    if let Err(e) = stream_target(client, index, &mut body, &resolved, &mut result, start_time, on_event).await {
+        result.status = "failed";
+        result.error = Some(e);
+    }
+
+    result.latency_ms = start_time.elapsed().as_millis() as u64;
+    result.cost_usd = crate::pricing::calculate_request_cost(
+        &result.provider,
+        &result.model,
+        result.usage.input_tokens.saturating_sub(result.usage.cached_tokens),
+        result.usage.output_tokens,
+        result.usage.cached_tokens,
+    );
+    on_event(CompareEvent::Finished { target: index, result: result.clone() });
+    result
+}
+
+async fn stream_target<F>(
+    client: &Client,
+    index: usize,
+    body: &mut Map<String, Value>,
+    resolved: &ResolvedTarget,
+    result: &mut TargetResult,
+    start_time: std::time::Instant,
+    on_event: &F,
+) -> Result<(), String>
+where
+    F: Fn(CompareEvent),
+{
+    let provider = find_provider(&resolved.target.provider)
+        .ok_or_else(|| format!("Unknown provider '{}'", resolved.target.provider))?;
+
+    rewrite_request_body(body, provider.id, &resolved.target.model);
+    body.insert("stream".to_string(), json!(true));
+    body.insert("stream_options".to_string(), json!({ "include_usage": true }));
+
+    let url = format!("{}{}/chat/completions", provider.base_url, provider.api_path);
+    let mut request_builder = client.post(&url).json(&body);
+    for (name, value) in provider.extra_headers {
+        request_builder = request_builder.header(*name, *value);
+    }
+    if let Some(api_key) = &resolved.api_key {
+        request_builder = crate::server::apply_api_key(request_builder, provider.auth_header, api_key);
+    }
+
+    let response = request_builder.send().await.map_err(|e| e.to_string())?;
+    let status = response.status();
+    if !status.is_success() {
+        let text = response.text().await.unwrap_or_default();
+        return Err(format!("{} {}", status.as_u16(), text));
+    }
+
+    // Server-sent events: one `data: {chunk}` per line, terminated by `data: [DONE]`.
+    let mut stream = response.bytes_stream();
+    let mut pending = String::new();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| e.to_string())?;
+        pending.push_str(&String::from_utf8_lossy(&chunk));
+
+        while let Some(newline) = pending.find('\n') {
+            let line: String = pending.drain(..=newline).collect();
+            let Some(data) = line.trim().strip_prefix("data:").map(str::trim) else {
+                continue;
+            };
+            let Ok(event) = serde_json::from_str::<Value>(data) else {
+                continue; // "[DONE]" or keep-alive
+            };
+
+            if let Some(text) = event.pointer("/choices/0/delta/content").and_then(|v| v.as_str()) {
+                if !text.is_empty() {
+                    result.time_to_first_token_ms.get_or_insert(start_time.elapsed().as_millis() as u64);
+                    result.output.push_str(text);
+                    on_event(CompareEvent::Delta { target: index, text: text.to_string() });
+                }
+            }
+            if let Some(reason) = event.pointer("/choices/0/finish_reason").and_then(|v| v.as_str()) {
+                result.finish_reason = Some(reason.to_string());
+            }
+            if let Some(usage) = event.get("usage").filter(|u| u.is_object()) {
+                let count = |ptr: &str| usage.pointer(ptr).and_then(|v| v.as_u64()).unwrap_or(0);
+                result.usage = CompareUsage {
+                    input_tokens: count("/prompt_tokens"),
+                    output_tokens: count("/completion_tokens"),
+                    cached_tokens: count("/prompt_tokens_details/cached_tokens"),
+                    total_tokens: count("/total_tokens"),
+                };
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// ── Commands ─────────────────────────────────────────────────────────────────
+
+/// Run one chat request against several provider/model targets side by side.
+///
+/// `id`       – optional caller-supplied id (used by `compare_cancel`); a ULID otherwise
+/// `request`  – OpenAI-style chat body without `model` (messages, temperature, max_tokens…)
+/// `targets`  – provider/model pairs; results keep the same order
+/// `on_event` – channel receiving CompareEvent { started | delta | finished } per target
+#[tauri::command]
+pub async fn compare_models(
+    id: Option<String>,
+    request: Map<String, Value>,
+    targets: Vec<CompareTarget>,
+    on_event: Channel<CompareEvent>,
+    state: tauri::State<'_, CompareState>,
+    app: AppHandle,
+) -> Result<Comparison, String> {
+    if targets.is_empty() {
+        return Err("At least one target is required".to_string());
+    }
+    let id = id.unwrap_or_else(|| Ulid::new().to_string());
+
+    let mut resolved = Vec::with_capacity(targets.len());
+    for target in targets {
+        let api_key = crate::server::lookup_api_key(&app, &target.provider).await?;
+        resolved.push(ResolvedTarget { api_key, target });
+    }
+
+    let governor = app.state::<GovernorState>().inner().clone();
+    let client = {
+        let manager = state.lock().unwrap();
+        if manager.runs.contains_key(&id) {
+            return Err(format!("comparison '{}' is already running", id));
+        }
+        manager.client.clone()
+    };
+
+    let state_arc = state.inner().clone();
+    let run_id = id.clone();
+    let comparison = run_comparison(
+        &client,
+        Some(governor),
+        id.clone(),
+        request,
+        resolved,
+        move |event| {
+            on_event.send(event).ok();
+        },
+        move |handles| {
+            state_arc.lock().unwrap().runs.insert(run_id, handles);
+        },
+    )
+    .await;
+
+    state.lock().unwrap().runs.remove(&id);
+    Ok(comparison)
+}
+
+/// Cancel a running comparison. Targets still in flight finish as "canceled".
+#[tauri::command]
+pub fn compare_cancel(id: String, state: tauri::State<'_, CompareState>) -> Result<(), String> {
+    if let Some(handles) = state.lock().unwrap().runs.remove(&id) {
+        for handle in handles {
+            handle.abort();
+        }
+    }
+    Ok(())
+}
@@ -1,61 +1,117 @@
 use rusqlite::{Connection, params_from_iter};
-use rusqlite_migration::{Migrations, M};
-use std::sync::{Arc, Mutex};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use tauri::AppHandle;
+use serde::{Deserialize, Serialize};
 use serde_json::{Value, Map, json};
 use anyhow::{anyhow, Result as AnyhowResult};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
 use ulid::Ulid; // Added Ulid
 
-// Define the migrations
-fn get_migrations() -> Migrations<'static> {
-    Migrations::new(vec![
-        M::up(include_str!("../migrations/0001_initial_schema.sql")),
-        M::up(include_str!("../migrations/0002_create_collections_table.sql")),
-        M::up(include_str!("../migrations/0003_create_scenarios_table.sql")),
-        M::up(include_str!("../migrations/0004_create_executions_table.sql")),
-        M::up(include_str!("../migrations/0005_add_timestamps_to_api_keys.sql")),
-        M::up(include_str!("../migrations/0006_create_settings_table.sql")),
-        M::up(include_str!("../migrations/0007_create_prompt_templates_table.sql")),
-        M::up(include_str!("../migrations/0008_add_attachments_to_scenarios.sql")),
-        M::up(include_str!("../migrations/0009_create_accounts_table.sql")),
-        M::up(include_str!("../migrations/0010_create_tools_table.sql")),
-        M::up(include_str!("../migrations/0011_create_attachments_table.sql")),
-        M::up(include_str!("../migrations/0012_add_tool_calls_to_executions.sql")),
-        M::up(include_str!("../migrations/0013_add_steps_json_to_executions.sql")),
-        M::up(include_str!("../migrations/0014_create_agents_table.sql")),
-        M::up(include_str!("../migrations/0015_create_telemetry_events_table.sql")),
-        M::up(include_str!("../migrations/0016_restructure_tools.sql")),
-        M::up(include_str!("../migrations/0017_create_eval_tables.sql")),
-        M::up(include_str!("../migrations/0018_create_env_variables_table.sql")),
-        M::up(include_str!("../migrations/0019_create_agent_memories.sql")),
-        M::up(include_str!("../migrations/0020_add_human_in_the_loop_to_agents.sql")),
-    ])
-}
-
-// Initialize the database connection and run migrations
-pub fn init_database(app_handle: &AppHandle) -> AnyhowResult<Arc<Mutex<Connection>>> {
-    let app_dir = crate::paths::app_data_root(app_handle).map_err(|e| anyhow!("{}", e))?;
-
-    std::fs::create_dir_all(&app_dir).expect("Failed to create app data directory");
-    let db_path = app_dir.join("reticle.db");
-    
-    let mut conn = Connection::open(&db_path)?;
-    conn.execute_batch("
-        PRAGMA journal_mode = WAL;
-        PRAGMA synchronous = NORMAL;
-        PRAGMA temp_store = MEMORY;
-        PRAGMA foreign_keys = ON;
-        PRAGMA busy_timeout = 5000;
-    ")?;
-    
-    let migrations = get_migrations();
-    migrations.to_latest(&mut conn)?;
-    
-    Ok(Arc::new(Mutex::new(conn)))
+use crate::pool::{DbPool, DbState};
+
+/// `migrations/<name>.sql` and the `<name>.down.sql` that reverts it.
+macro_rules! migration {
+    ($name:literal) => {
+        (
+            $name,
+            include_str!(concat!("../migrations/", $name, ".sql")),
+            include_str!(concat!("../migrations/", $name, ".down.sql")),
+        )
+    };
+}
+
+/// (name, up, down) in order; migration N leaves `user_version` at N.
+pub const MIGRATIONS: &[(&str, &str, &str)] = &[
+    migration!("0001_initial_schema"),
+    migration!("0002_create_collections_table"),
+    migration!("0003_create_scenarios_table"),
+    migration!("0004_create_executions_table"),
+    migration!("0005_add_timestamps_to_api_keys"),
+    migration!("0006_create_settings_table"),
+    migration!("0007_create_prompt_templates_table"),
+    migration!("0008_add_attachments_to_scenarios"),
+    migration!("0009_create_accounts_table"),
+    migration!("0010_create_tools_table"),
+    migration!("0011_create_attachments_table"),
+    migration!("0012_add_tool_calls_to_executions"),
+    migration!("0013_add_steps_json_to_executions"),
+    migration!("0014_create_agents_table"),
+    migration!("0015_create_telemetry_events_table"),
+    migration!("0016_restructure_tools"),
+    migration!("0017_create_eval_tables"),
+    migration!("0018_create_env_variables_table"),
+    migration!("0019_create_agent_memories"),
+    migration!("0020_add_human_in_the_loop_to_agents"),
+    migration!("0021_create_shadow_responses_table"),
+    migration!("0022_create_proxy_requests_table"),
+    migration!("0023_add_queue_wait_to_proxy_requests"),
+    migration!("0024_create_search_index"),
+    migration!("0025_create_version_history"),
+    migration!("0026_add_starred_to_runs"),
+    migration!("0027_create_usage_daily"),
+    migration!("0028_add_json_path_indexes"),
+    migration!("0029_add_keyset_indexes"),
+    migration!("0030_create_usage_rollup_runs"),
+];
+
+/// Schema version after all migrations (the `user_version` they leave behind).
+pub fn migration_count() -> usize {
+    MIGRATIONS.len()
+}
+
+/// Apply pending migrations to `conn` (see `migrations::migrate_to`).
+pub fn migrate(conn: &mut Connection) -> AnyhowResult<()> {
+    crate::migrations::migrate_to(conn, migration_count())?;
+    Ok(())
+}
+
+/// Database file name inside a workspace's data directory.
+pub const DB_FILE: &str = "reticle.db";
+
+// Open the database, run migrations and set up the connection pool
+pub fn init_database(app_handle: &AppHandle) -> AnyhowResult<DbState> {
+    let app_dir = crate::workspaces::active_data_dir(app_handle).map_err(|e| anyhow!("{}", e))?;
+    let (conn, db_path) = match open_migrated(&app_dir) {
+        Ok(opened) => opened,
+        Err(e) => {
+            // Start on the database as it is, so the app can report the failure and
+            // offer a backup to restore instead of being unable to launch
+            crate::migrations::set_startup_error(Some(e.to_string()));
+            let db_path = app_dir.join(DB_FILE);
+            (DbPool::open_writer(&db_path)?, db_path)
+        }
+    };
+    Ok(Arc::new(DbPool::new(conn, &db_path)?))
+}
+
+/// Open (creating it if needed) the `reticle.db` in `app_dir` and bring it up to the
+/// current schema. Returns the writer connection and the database path.
+pub fn open_migrated(app_dir: &Path) -> AnyhowResult<(Connection, PathBuf)> {
+    std::fs::create_dir_all(app_dir)?;
+    let db_path = app_dir.join(DB_FILE);
+
+    let mut conn = DbPool::open_writer(&db_path)?;
+
+    if crate::migrations::user_version(&conn)? < migration_count() {
+        // Try the pending migrations on a copy first; the real file is only touched
+        // once they are known to apply
+        if let Some(error) = crate::migrations::dry_run(&conn, migration_count())?.error {
+            return Err(anyhow!("Migrations failed on a copy of the database: {}", error));
+        }
+        // Keep a copy to roll back to if a migration goes wrong
+        if let Err(e) = crate::backup::backup_before_migrations(&conn, app_dir) {
+            log::warn!("Pre-migration backup failed: {}", e);
+        }
+    }
+
+    migrate(&mut conn)?;
+    Ok((conn, db_path))
 }
 
 // Helper to convert rusqlite::types::ValueRef to serde_json::Value
-fn rusqlite_value_to_json(value_ref: &rusqlite::types::ValueRef) -> AnyhowResult<Value> {
+pub(crate) fn rusqlite_value_to_json(value_ref: &rusqlite::types::ValueRef) -> AnyhowResult<Value> {
     match value_ref {
         rusqlite::types::ValueRef::Null => Ok(Value::Null),
         rusqlite::types::ValueRef::Integer(i) => Ok(Value::Number(serde_json::Number::from(*i))),
@@ -85,6 +141,217 @@ fn json_value_to_sql<'a>(value: &'a Value) -> anyhow::Result<Box<dyn rusqlite::T
     Ok(boxed)
 }
 
+// ── Query DSL ────────────────────────────────────────────────────────────────
+//
+// `where` maps columns to conditions, joined by AND:
+//   { "status": "failed" }                        col = value (null → IS NULL)
+//   { "started_at": { "gte": 1700000000000 } }    eq, ne, gt, gte, lt, lte, like
+//   { "status": { "in": ["failed", "canceled"] } } in, not_in
+//   { "ended_at": { "is_null": true } }           is_null, not_null
+//   { "started_at": { "between": [a, b] } }       inclusive range
+// Several operators on one column are ANDed. "and"/"or" take a list of nested
+// `where` objects, "not" a single one:
+//   { "type": "scenario", "or": [{ "status": "failed" }, { "ended_at": { "is_null": true } }] }
+// A key (or `orderBy`) may also name a value inside a JSON column as `column.$path`,
+// compared with json_extract:
+//   { "usage_json.$.totalTokens": { "gt": 10000 } }, "orderBy": "snapshot_json.$.configuration.model"
+// Paths are limited to `.key` and `[index]` steps so they can be spliced as literals,
+// which lets SQLite use an index on the same json_extract expression.
+//
+// Table and column names are spliced too, so every one of them must be a plain
+// identifier (`identifier`), whoever the caller is.
+
+type SqlParams<'a> = Vec<Box<dyn rusqlite::ToSql + 'a>>;
+
+/// Split a `column.$path` key into the column and the JSON path (starting at `$`).
+pub fn split_json_path(key: &str) -> Option<(&str, &str)> {
+    let at = key.find(".$")?;
+    Some((&key[..at], &key[at + 1..]))
+}
+
+/// `$` followed by `.key` and `[index]` steps, e.g. `$.configuration.model` or `$.stop[0]`.
+pub fn is_valid_json_path(path: &str) -> bool {
+    let Some(mut rest) = path.strip_prefix('$') else {
+        return false;
+    };
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('.') {
+            let len = after.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_')).unwrap_or(after.len());
+            if len == 0 {
+                return false;
+            }
+            rest = &after[len..];
+        } else if let Some(after) = rest.strip_prefix('[') {
+            let len = after.find(|c: char| !c.is_ascii_digit()).unwrap_or(after.len());
+            if len == 0 || !after[len..].starts_with(']') {
+                return false;
+            }
+            rest = &after[len + 1..];
+        } else {
+            return false;
+        }
+    }
+    true
+}
+
+/// `name` if it is a plain SQL identifier (letters, digits, `_`, not starting with a digit).
+pub fn identifier(name: &str) -> AnyhowResult<&str> {
+    let valid = name.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if valid {
+        Ok(name)
+    } else {
+        Err(anyhow!("Invalid identifier '{}'", name))
+    }
+}
+
+/// The query's `columns` projection, or `*`.
+fn columns_sql(query_map: &Map<String, Value>) -> AnyhowResult<String> {
+    match query_map.get("columns").and_then(|v| v.as_array()) {
+        Some(cols) if !cols.is_empty() => Ok(cols
+            .iter()
+            .map(|c| {
+                c.as_str()
+                    .ok_or_else(|| anyhow!("'columns' must be an array of column names"))
+                    .and_then(identifier)
+            })
+            .collect::<AnyhowResult<Vec<_>>>()?
+            .join(", ")),
+        _ => Ok("*".to_string()),
+    }
+}
+
+/// SQL expression for a `where`/`orderBy` key: the column itself, or json_extract for
+/// a `column.$path` key.
+fn column_sql(key: &str) -> AnyhowResult<String> {
+    match split_json_path(key) {
+        None => Ok(identifier(key)?.to_string()),
+        Some((column, path)) if is_valid_json_path(path) => {
+            Ok(format!("json_extract({}, '{}')", identifier(column)?, path))
+        }
+        Some(_) => Err(anyhow!("Invalid JSON path in '{}'", key)),
+    }
+}
+
+fn push_param<'a>(params: &mut SqlParams<'a>, value: &'a Value) -> AnyhowResult<String> {
+    params.push(json_value_to_sql(value)?);
+    Ok(format!("?{}", params.len()))
+}
+
+fn build_condition<'a>(col: &str, cond: &'a Value, params: &mut SqlParams<'a>) -> AnyhowResult<String> {
+    let ops = match cond {
+        Value::Null => return Ok(format!("{} IS NULL", col)),
+        Value::Object(ops) => ops,
+        _ => return Ok(format!("{} = {}", col, push_param(params, cond)?)),
+    };
+    if ops.is_empty() {
+        return Err(anyhow!("Empty condition for column '{}'", col));
+    }
+
+    let mut clauses = Vec::new();
+    for (op, value) in ops {
+        let clause = match op.as_str() {
+            "eq" if value.is_null() => format!("{} IS NULL", col),
+            "ne" if value.is_null() => format!("{} IS NOT NULL", col),
+            "eq" => format!("{} = {}", col, push_param(params, value)?),
+            "ne" => format!("{} != {}", col, push_param(params, value)?),
+            "gt" => format!("{} > {}", col, push_param(params, value)?),
+            "gte" => format!("{} >= {}", col, push_param(params, value)?),
+            "lt" => format!("{} < {}", col, push_param(params, value)?),
+            "lte" => format!("{} <= {}", col, push_param(params, value)?),
+            "like" => format!("{} LIKE {}", col, push_param(params, value)?),
+            "in" | "not_in" => {
+                let values = value
+                    .as_array()
+                    .ok_or_else(|| anyhow!("'{}' on column '{}' expects an array", op, col))?;
+                if values.is_empty() {
+                    // Nothing is in an empty list
+                    if op == "in" { "0".to_string() } else { "1".to_string() }
+                } else {
+                    let placeholders = values
+                        .iter()
+                        .map(|v| push_param(params, v))
+                        .collect::<AnyhowResult<Vec<_>>>()?;
+                    let keyword = if op == "in" { "IN" } else { "NOT IN" };
+                    format!("{} {} ({})", col, keyword, placeholders.join(", "))
+                }
+            }
+            "is_null" | "not_null" => {
+                let want_null = (op == "is_null") == value.as_bool().unwrap_or(true);
+                format!("{} {}", col, if want_null { "IS NULL" } else { "IS NOT NULL" })
+            }
+            "between" => match value.as_array().map(|v| v.as_slice()) {
+                Some([low, high]) => {
+                    let low = push_param(params, low)?;
+                    let high = push_param(params, high)?;
+                    format!("{} BETWEEN {} AND {}", col, low, high)
+                }
+                _ => return Err(anyhow!("'between' on column '{}' expects [low, high]", col)),
+            },
+            _ => return Err(anyhow!("Unknown operator '{}' on column '{}'", op, col)),
+        };
+        clauses.push(clause);
+    }
+    Ok(clauses.join(" AND "))
+}
+
+fn build_group<'a>(op: &str, value: &'a Value, params: &mut SqlParams<'a>) -> AnyhowResult<Option<String>> {
+    let items = value
+        .as_array()
+        .ok_or_else(|| anyhow!("'{}' expects an array of conditions", op))?;
+    let mut clauses = Vec::new();
+    for item in items {
+        if let Some(clause) = build_where(item, params)? {
+            clauses.push(format!("({})", clause));
+        }
+    }
+    if clauses.is_empty() {
+        return Ok(None);
+    }
+    let joiner = if op == "or" { " OR " } else { " AND " };
+    Ok(Some(clauses.join(joiner)))
+}
+
+/// SQL for a `where` object (without the WHERE keyword), or None when it has no conditions.
+fn build_where<'a>(where_value: &'a Value, params: &mut SqlParams<'a>) -> AnyhowResult<Option<String>> {
+    let where_obj = where_value
+        .as_object()
+        .ok_or_else(|| anyhow!("'where' must be a JSON object"))?;
+
+    let mut clauses = Vec::new();
+    for (key, cond) in where_obj {
+        match key.as_str() {
+            "and" | "or" => {
+                if let Some(group) = build_group(key, cond, params)? {
+                    clauses.push(format!("({})", group));
+                }
+            }
+            "not" => {
+                if let Some(inner) = build_where(cond, params)? {
+                    clauses.push(format!("NOT ({})", inner));
+                }
+            }
+            key => clauses.push(build_condition(&column_sql(key)?, cond, params)?),
+        }
+    }
+    Ok((!clauses.is_empty()).then(|| clauses.join(" AND ")))
+}
+
+/// " WHERE …" for the query's `where`, or "" when there is none.
+fn where_sql<'a>(query_map: &'a Map<String, Value>, params: &mut SqlParams<'a>) -> AnyhowResult<String> {
+    match query_map.get("where") {
+        Some(where_value) if !where_value.is_null() => Ok(build_where(where_value, params)?
+            .map(|clause| format!(" WHERE {}", clause))
+            .unwrap_or_default()),
+        _ => Ok(String::new()),
+    }
+}
+
+/// Current time as unix ms, the unit of every timestamp column.
+pub fn now_ms() -> i64 {
+    std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_millis() as i64
+}
+
 // Generic INSERT operation
 pub fn db_insert(conn: &Connection, table: &str, mut data: Value) -> AnyhowResult<String> {
     let data_map_original = data.as_object_mut().ok_or_else(|| anyhow!("Data must be a JSON object for insert"))?;
@@ -98,7 +365,7 @@ pub fn db_insert(conn: &Connection, table: &str, mut data: Value) -> AnyhowResul
         ulid
     };
 
-    let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_millis() as i64;
+    let now = now_ms();
 
     // Automatically set created_at and updated_at on insert if not provided
     if !data_map_original.contains_key("created_at") {
@@ -110,12 +377,12 @@ pub fn db_insert(conn: &Connection, table: &str, mut data: Value) -> AnyhowResul
     
     let data_map = data_map_original;
 
-    let columns: Vec<&str> = data_map.keys().map(|s| s.as_str()).collect();
+    let columns = data_map.keys().map(|s| identifier(s)).collect::<AnyhowResult<Vec<_>>>()?;
     let placeholders: Vec<String> = (0..columns.len()).map(|i| format!("?{}", i + 1)).collect();
 
     let sql = format!(
         "INSERT INTO {} ({}) VALUES ({})",
-        table,
+        identifier(table)?,
         columns.join(", "),
         placeholders.join(", ")
     );
@@ -134,20 +401,12 @@ pub fn db_insert(conn: &Connection, table: &str, mut data: Value) -> AnyhowResul
 pub fn db_select(conn: &Connection, table: &str, query: Value) -> AnyhowResult<Vec<Map<String, Value>>> {
     let query_map = query.as_object().ok_or_else(|| anyhow!("Query must be a JSON object for select"))?;
 
-    let mut sql = format!("SELECT * FROM {}", table);
-    let mut where_clauses = Vec::new();
-    let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
-
-    if let Some(where_obj) = query_map.get("where").and_then(|v| v.as_object()) {
-        for (i, (col, val)) in where_obj.iter().enumerate() {
-            where_clauses.push(format!("{} = ?{}", col, i + 1));
-            params_vec.push(json_value_to_sql(val)?);
-        }
-    }
+    // Optional projection: "columns": ["id", "name"]
+    let columns = columns_sql(query_map)?;
 
-    if !where_clauses.is_empty() {
-        sql.push_str(&format!(" WHERE {}", where_clauses.join(" AND ")));
-    }
+    let mut params_vec: SqlParams = Vec::new();
+    let mut sql = format!("SELECT {} FROM {}", columns, identifier(table)?);
+    sql.push_str(&where_sql(query_map, &mut params_vec)?);
 
     if let Some(order_by) = query_map.get("orderBy").and_then(|v| v.as_str()) {
         let direction = query_map
@@ -155,7 +414,7 @@ pub fn db_select(conn: &Connection, table: &str, query: Value) -> AnyhowResult<V
             .and_then(|v| v.as_str())
             .unwrap_or("ASC");
         let dir = if direction.eq_ignore_ascii_case("desc") { "DESC" } else { "ASC" };
-        sql.push_str(&format!(" ORDER BY {} {}", order_by, dir));
+        sql.push_str(&format!(" ORDER BY {} {}", column_sql(order_by)?, dir));
     }
 
     if let Some(limit) = query_map.get("limit").and_then(|v| v.as_i64()) {
@@ -170,7 +429,12 @@ pub fn db_select(conn: &Connection, table: &str, query: Value) -> AnyhowResult<V
         }
     }
 
-    let mut stmt = conn.prepare(&sql)?;
+    query_rows(conn, &sql, params_vec)
+}
+
+/// Run a SELECT and return each row as a column → value map.
+fn query_rows(conn: &Connection, sql: &str, params_vec: SqlParams) -> AnyhowResult<Vec<Map<String, Value>>> {
+    let mut stmt = conn.prepare(sql)?;
     let column_names: Vec<String> = stmt.column_names().iter().map(|s| s.to_string()).collect();
 
     let rows = stmt.query_map(params_from_iter(params_vec.into_iter()), |row| { // Fix: use params_from_iter
@@ -186,6 +450,162 @@ pub fn db_select(conn: &Connection, table: &str, query: Value) -> AnyhowResult<V
     Ok(rows)
 }
 
+// ── Keyset pagination ────────────────────────────────────────────────────────
+//
+// OFFSET makes SQLite step over every skipped row, so deep pages get slower and
+// slower. `db_page` instead continues after the last row it returned: rows are
+// ordered by `orderBy` then `id`, and the opaque cursor carries that row's key and
+// id. The query takes the `db_select` fields plus:
+//   "cursor": "…"       next_cursor of the previous page (omit for the first page)
+//   "withTotal": true   also count every row matching `where`
+// `offset` is ignored and rows always include `id`. NULL keys sort first ascending
+// and last descending, as in SQLite.
+
+const DEFAULT_PAGE_SIZE: i64 = 50;
+/// Alias of the sort key in the page query, removed from the returned rows.
+const PAGE_KEY: &str = "_page_key";
+
+#[derive(Serialize, Debug)]
+pub struct Page {
+    pub rows: Vec<Map<String, Value>>,
+    /// Cursor for the following page; None on the last page.
+    pub next_cursor: Option<String>,
+    /// Rows matching `where`, when `withTotal` was set.
+    pub total: Option<i64>,
+}
+
+/// Position after the last row of a page, tied to the order it was produced with.
+#[derive(Serialize, Deserialize)]
+struct Cursor {
+    order_by: String,
+    desc: bool,
+    key: Value,
+    id: String,
+}
+
+impl Cursor {
+    fn encode(&self) -> AnyhowResult<String> {
+        Ok(URL_SAFE_NO_PAD.encode(serde_json::to_vec(self)?))
+    }
+
+    fn decode(cursor: &str) -> AnyhowResult<Self> {
+        URL_SAFE_NO_PAD
+            .decode(cursor)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .ok_or_else(|| anyhow!("Invalid cursor"))
+    }
+}
+
+/// Condition for the `segment`th run of rows after `cursor` in (`key`, id) order, or
+/// None when there are no more. Rows with a NULL key are read as a separate run: an OR
+/// across both would stop SQLite from walking the (key, id) index in order.
+fn after_cursor<'a>(
+    key: &str,
+    cursor: &'a Cursor,
+    segment: usize,
+    params: &mut SqlParams<'a>,
+) -> AnyhowResult<Option<String>> {
+    let cmp = if cursor.desc { "<" } else { ">" };
+    Ok(match (cursor.key.is_null(), cursor.desc, segment) {
+        (true, _, 0) => {
+            params.push(Box::new(&cursor.id));
+            Some(format!("{} IS NULL AND id {} ?{}", key, cmp, params.len()))
+        }
+        (true, false, 1) => Some(format!("{} IS NOT NULL", key)),
+        (false, _, 0) => {
+            let value = push_param(params, &cursor.key)?;
+            params.push(Box::new(&cursor.id));
+            Some(format!("({}, id) {} ({}, ?{})", key, cmp, value, params.len()))
+        }
+        (false, true, 1) => Some(format!("{} IS NULL", key)),
+        _ => None,
+    })
+}
+
+/// One page of `table` in (`orderBy`, id) order, see above.
+pub fn db_page(conn: &Connection, table: &str, query: Value) -> AnyhowResult<Page> {
+    let query_map = query.as_object().ok_or_else(|| anyhow!("Query must be a JSON object for page"))?;
+
+    let order_by = query_map.get("orderBy").and_then(|v| v.as_str()).unwrap_or("id");
+    let desc = query_map
+        .get("orderDirection")
+        .and_then(|v| v.as_str())
+        .is_some_and(|d| d.eq_ignore_ascii_case("desc"));
+    let limit = query_map
+        .get("limit")
+        .and_then(|v| v.as_i64())
+        .filter(|l| *l > 0)
+        .unwrap_or(DEFAULT_PAGE_SIZE);
+    let cursor = match query_map.get("cursor").and_then(|v| v.as_str()) {
+        Some(cursor) => Some(Cursor::decode(cursor)?),
+        None => None,
+    };
+    if cursor.as_ref().is_some_and(|c| c.order_by != order_by || c.desc != desc) {
+        return Err(anyhow!("Cursor was made for a different orderBy/orderDirection"));
+    }
+
+    let columns = columns_sql(query_map)?;
+    let key = column_sql(order_by)?;
+
+    let dir = if desc { "DESC" } else { "ASC" };
+    // One extra row tells whether there is a next page
+    let wanted = limit as usize + 1;
+    let mut rows = Vec::new();
+    for segment in 0.. {
+        let mut params_vec: SqlParams = Vec::new();
+        let mut clauses = Vec::new();
+        if let Some(where_value) = query_map.get("where").filter(|w| !w.is_null()) {
+            clauses.extend(build_where(where_value, &mut params_vec)?);
+        }
+        if let Some(cursor) = &cursor {
+            match after_cursor(&key, cursor, segment, &mut params_vec)? {
+                Some(clause) => clauses.push(clause),
+                None => break,
+            }
+        }
+
+        let mut sql = format!("SELECT {}, id, {} AS {} FROM {}", columns, key, PAGE_KEY, identifier(table)?);
+        if !clauses.is_empty() {
+            sql.push_str(&format!(" WHERE {}", clauses.join(" AND ")));
+        }
+        sql.push_str(&format!(" ORDER BY {key} {dir}, id {dir} LIMIT {}", wanted - rows.len()));
+        rows.extend(query_rows(conn, &sql, params_vec)?);
+
+        if cursor.is_none() || rows.len() >= wanted {
+            break;
+        }
+    }
+
+    let mut next_cursor = None;
+    if rows.len() as i64 > limit {
+        rows.truncate(limit as usize);
+        if let Some(last) = rows.last() {
+            next_cursor = Some(
+                Cursor {
+                    order_by: order_by.to_string(),
+                    desc,
+                    key: last.get(PAGE_KEY).cloned().unwrap_or(Value::Null),
+                    id: last.get("id").and_then(|v| v.as_str()).unwrap_or_default().to_string(),
+                }
+                .encode()?,
+            );
+        }
+    }
+    for row in &mut rows {
+        row.remove(PAGE_KEY);
+    }
+
+    let total = if query_map.get("withTotal").and_then(|v| v.as_bool()).unwrap_or(false) {
+        let where_value = query_map.get("where").cloned().unwrap_or(Value::Null);
+        Some(db_count(conn, table, json!({ "where": where_value }))?)
+    } else {
+        None
+    };
+
+    Ok(Page { rows, next_cursor, total })
+}
+
 // Generic UPDATE operation
 pub fn db_update(conn: &Connection, table: &str, query: Value, mut data: Value) -> AnyhowResult<usize> {
     let query_map = query.as_object().ok_or_else(|| anyhow!("Query must be a JSON object for update"))?;
@@ -196,29 +616,18 @@ pub fn db_update(conn: &Connection, table: &str, query: Value, mut data: Value)
     }
 
     // Automatically set updated_at on update
-    let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_millis() as i64;
+    let now = now_ms();
     data_map.insert("updated_at".to_string(), json!(now));
 
     let mut set_clauses = Vec::new();
-    let mut where_clauses = Vec::new();
-    let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+    let mut params_vec: SqlParams = Vec::new();
 
-    for (i, (col, val)) in data_map.iter().enumerate() {
-        set_clauses.push(format!("{} = ?{}", col, i + 1));
-        params_vec.push(json_value_to_sql(val)?);
+    for (col, val) in data_map.iter() {
+        set_clauses.push(format!("{} = {}", identifier(col)?, push_param(&mut params_vec, val)?));
     }
 
-    if let Some(where_obj) = query_map.get("where").and_then(|v| v.as_object()) {
-        for (i, (col, val)) in where_obj.iter().enumerate() {
-            where_clauses.push(format!("{} = ?{}", col, params_vec.len() + i + 1));
-            params_vec.push(json_value_to_sql(val)?);
-        }
-    }
-
-    let mut sql = format!("UPDATE {} SET {}", table, set_clauses.join(", "));
-    if !where_clauses.is_empty() {
-        sql.push_str(&format!(" WHERE {}", where_clauses.join(" AND ")));
-    }
+    let mut sql = format!("UPDATE {} SET {}", identifier(table)?, set_clauses.join(", "));
+    sql.push_str(&where_sql(query_map, &mut params_vec)?);
 
     let mut stmt = conn.prepare(&sql)?;
     let changes = stmt.execute(params_from_iter(params_vec.into_iter()))?; // Fix: use params_from_iter
@@ -229,20 +638,9 @@ pub fn db_update(conn: &Connection, table: &str, query: Value, mut data: Value)
 pub fn db_count(conn: &Connection, table: &str, query: Value) -> AnyhowResult<i64> {
     let query_map = query.as_object().ok_or_else(|| anyhow!("Query must be a JSON object for count"))?;
 
-    let mut sql = format!("SELECT COUNT(*) FROM {}", table);
-    let mut where_clauses = Vec::new();
-    let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
-
-    if let Some(where_obj) = query_map.get("where").and_then(|v| v.as_object()) {
-        for (i, (col, val)) in where_obj.iter().enumerate() {
-            where_clauses.push(format!("{} = ?{}", col, i + 1));
-            params_vec.push(json_value_to_sql(val)?);
-        }
-    }
-
-    if !where_clauses.is_empty() {
-        sql.push_str(&format!(" WHERE {}", where_clauses.join(" AND ")));
-    }
+    let mut params_vec: SqlParams = Vec::new();
+    let mut sql = format!("SELECT COUNT(*) FROM {}", identifier(table)?);
+    sql.push_str(&where_sql(query_map, &mut params_vec)?);
 
     let count: i64 = conn.query_row(
         &sql,
@@ -252,6 +650,166 @@ pub fn db_count(conn: &Connection, table: &str, query: Value) -> AnyhowResult<i6
     Ok(count)
 }
 
+// ── Batch ────────────────────────────────────────────────────────────────────
+
+/// One write in a `db_batch`. Values in `data`/`query` may be `{ "$ref": "alias" }`
+/// (or `{ "$ref": 0 }`, the index of an earlier insert), replaced with that insert's id.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum BatchOp {
+    Insert {
+        table: String,
+        data: Value,
+        /// Name later operations use to reference the generated id
+        #[serde(default, rename = "as")]
+        alias: Option<String>,
+    },
+    Update { table: String, query: Value, data: Value },
+    Delete { table: String, query: Value },
+}
+
+impl BatchOp {
+    pub fn table(&self) -> &str {
+        match self {
+            BatchOp::Insert { table, .. } | BatchOp::Update { table, .. } | BatchOp::Delete { table, .. } => table,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct BatchResult {
+    /// Id of the inserted row (insert only)
+    pub id: Option<String>,
+    pub changes: usize,
+}
+
+fn resolve_refs(value: &mut Value, ids: &HashMap<String, String>) -> AnyhowResult<()> {
+    match value {
+        Value::Object(map) if map.len() == 1 && map.contains_key("$ref") => {
+            let key = match &map["$ref"] {
+                Value::String(alias) => alias.clone(),
+                Value::Number(index) => index.to_string(),
+                other => return Err(anyhow!("Invalid $ref {}", other)),
+            };
+            let id = ids.get(&key).ok_or_else(|| anyhow!("Unresolved $ref '{}'", key))?;
+            *value = json!(id);
+        }
+        Value::Object(map) => {
+            for v in map.values_mut() {
+                resolve_refs(v, ids)?;
+            }
+        }
+        Value::Array(items) => {
+            for v in items {
+                resolve_refs(v, ids)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+// Run inserts/updates/deletes in order inside one transaction; nothing is written if any fails.
+// Deletes from scenarios, agents and collections follow the delete policy (see cascade.rs).
+// Secret columns are sealed once an operation's $refs are resolved (see secrets.rs).
+// Also returns the change of every operation that touched rows, for notifications.
+pub fn db_batch(
+    conn: &mut Connection,
+    ops: Vec<BatchOp>,
+    secrets: &crate::secrets::Secrets,
+) -> AnyhowResult<(Vec<BatchResult>, Vec<DbChange>)> {
+    let tx = conn.transaction()?;
+    let mut ids: HashMap<String, String> = HashMap::new();
+    let mut results = Vec::with_capacity(ops.len());
+    let mut changes = Vec::new();
+
+    for (index, op) in ops.into_iter().enumerate() {
+        let result = match op {
+            BatchOp::Insert { table, mut data, alias } => {
+                resolve_refs(&mut data, &ids)?;
+                let inserted = secrets.seal(&tx, &table, None, &mut data).and_then(|_| db_insert(&tx, &table, data));
+                inserted.map(|id| {
+                    ids.insert(index.to_string(), id.clone());
+                    if let Some(alias) = alias {
+                        ids.insert(alias, id.clone());
+                    }
+                    changes.push(DbChange { table, op: ChangeOp::Insert, ids: vec![id.clone()] });
+                    BatchResult { id: Some(id), changes: 1 }
+                })
+            }
+            BatchOp::Update { table, mut query, mut data } => {
+                resolve_refs(&mut query, &ids)?;
+                resolve_refs(&mut data, &ids)?;
+                secrets.seal(&tx, &table, Some(&query), &mut data).and_then(|_| {
+                    let affected = matching_ids(&tx, &table, &query)?;
+                    let count = db_update(&tx, &table, query, data)?;
+                    changes.extend(DbChange::touched(&table, ChangeOp::Update, affected));
+                    Ok(BatchResult { id: None, changes: count })
+                })
+            }
+            BatchOp::Delete { table, mut query } => {
+                resolve_refs(&mut query, &ids)?;
+                matching_ids(&tx, &table, &query).and_then(|affected| {
+                    if crate::cascade::CASCADING_TABLES.contains(&table.as_str()) {
+                        let plan = crate::cascade::delete_rows(&tx, &table, &affected)?;
+                        changes.extend(plan.changes());
+                        return Ok(BatchResult { id: None, changes: plan.affected() });
+                    }
+                    let count = db_delete(&tx, &table, query)?;
+                    changes.extend(DbChange::touched(&table, ChangeOp::Delete, affected));
+                    Ok(BatchResult { id: None, changes: count })
+                })
+            }
+        };
+        results.push(result.map_err(|e| anyhow!("Batch operation {} failed: {}", index, e))?);
+    }
+
+    tx.commit()?;
+    Ok((results, changes))
+}
+
+// ── Change notifications ─────────────────────────────────────────────────────
+//
+// Write commands emit DB_CHANGE_EVENT once they have succeeded, so every window
+// can refresh what it shows without polling.
+
+/// Event name; payload DbChange.
+pub const DB_CHANGE_EVENT: &str = "db-change";
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeOp {
+    Insert,
+    Update,
+    Delete,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct DbChange {
+    pub table: String,
+    pub op: ChangeOp,
+    pub ids: Vec<String>,
+}
+
+impl DbChange {
+    /// A change for `ids`, or none when no row was affected.
+    pub fn touched(table: &str, op: ChangeOp, ids: Vec<String>) -> Option<Self> {
+        (!ids.is_empty()).then(|| DbChange { table: table.to_string(), op, ids })
+    }
+}
+
+/// Ids of the rows an update or delete with `query` will affect; call it first.
+pub fn matching_ids(conn: &Connection, table: &str, query: &Value) -> AnyhowResult<Vec<String>> {
+    let mut lookup = json!({ "columns": ["id"] });
+    if let Some(where_value) = query.get("where") {
+        lookup["where"] = where_value.clone();
+    }
+    Ok(db_select(conn, table, lookup)?
+        .into_iter()
+        .filter_map(|row| row.get("id").and_then(|id| id.as_str()).map(String::from))
+        .collect())
+}
+
 // Raw SQL execution (no params) — for e2e test helpers
 pub fn db_exec(conn: &Connection, sql: &str) -> AnyhowResult<()> {
     conn.execute_batch(sql)?;
@@ -262,22 +820,161 @@ pub fn db_exec(conn: &Connection, sql: &str) -> AnyhowResult<()> {
 pub fn db_delete(conn: &Connection, table: &str, query: Value) -> AnyhowResult<usize> {
     let query_map = query.as_object().ok_or_else(|| anyhow!("Query must be a JSON object for delete"))?;
 
-    let mut sql = format!("DELETE FROM {}", table);
-    let mut where_clauses = Vec::new();
-    let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+    let mut params_vec: SqlParams = Vec::new();
+    let mut sql = format!("DELETE FROM {}", identifier(table)?);
+    sql.push_str(&where_sql(query_map, &mut params_vec)?);
+
+    let mut stmt = conn.prepare(&sql)?;
+    let changes = stmt.execute(params_from_iter(params_vec.into_iter()))?; // Fix: use params_from_iter
+    Ok(changes)
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `items` rows: a apple 1, b banana 2, c NULL 3, d date NULL.
+    fn items() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE items (id TEXT PRIMARY KEY, name TEXT, score INTEGER, meta TEXT, updated_at INTEGER);
+             INSERT INTO items (id, name, score, meta) VALUES ('a', 'apple', 1, '{\"tag\":\"red\"}'), ('b', 'banana', 2, '{\"tag\":\"yellow\"}'),
+                                      ('c', NULL, 3, NULL), ('d', 'date', NULL, '{\"tag\":\"brown\"}');",
+        )
+        .unwrap();
+        conn
+    }
+
+    fn ids(conn: &Connection, where_value: Value) -> Vec<String> {
+        db_select(conn, "items", json!({ "where": where_value, "orderBy": "id" }))
+            .unwrap()
+            .iter()
+            .map(|row| row["id"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn comparison_operators() {
+        let conn = items();
+        assert_eq!(ids(&conn, json!({ "score": 2 })), ["b"]);
+        assert_eq!(ids(&conn, json!({ "score": { "eq": 2 } })), ["b"]);
+        assert_eq!(ids(&conn, json!({ "score": { "ne": 2 } })), ["a", "c"]);
+        assert_eq!(ids(&conn, json!({ "score": { "gt": 1 } })), ["b", "c"]);
+        assert_eq!(ids(&conn, json!({ "score": { "gte": 2, "lt": 3 } })), ["b"]);
+        assert_eq!(ids(&conn, json!({ "score": { "lte": 1 } })), ["a"]);
+        assert_eq!(ids(&conn, json!({ "score": { "between": [2, 3] } })), ["b", "c"]);
+        assert_eq!(ids(&conn, json!({ "name": { "like": "%an%" } })), ["b"]);
+        assert_eq!(ids(&conn, json!({ "meta.$.tag": "red" })), ["a"]);
+    }
+
+    #[test]
+    fn null_operators() {
+        let conn = items();
+        assert_eq!(ids(&conn, json!({ "name": null })), ["c"]);
+        assert_eq!(ids(&conn, json!({ "name": { "eq": null } })), ["c"]);
+        assert_eq!(ids(&conn, json!({ "name": { "ne": null } })), ["a", "b", "d"]);
+        assert_eq!(ids(&conn, json!({ "score": { "is_null": true } })), ["d"]);
+        assert_eq!(ids(&conn, json!({ "score": { "is_null": false } })), ["a", "b", "c"]);
+        assert_eq!(ids(&conn, json!({ "score": { "not_null": true } })), ["a", "b", "c"]);
+    }
 
-    if let Some(where_obj) = query_map.get("where").and_then(|v| v.as_object()) {
-        for (i, (col, val)) in where_obj.iter().enumerate() {
-            where_clauses.push(format!("{} = ?{}", col, i + 1));
-            params_vec.push(json_value_to_sql(val)?);
+    #[test]
+    fn in_and_not_in() {
+        let conn = items();
+        assert_eq!(ids(&conn, json!({ "id": { "in": ["a", "c", "z"] } })), ["a", "c"]);
+        assert_eq!(ids(&conn, json!({ "id": { "not_in": ["a", "c"] } })), ["b", "d"]);
+        // Nothing is in an empty list, and everything is outside it
+        assert!(ids(&conn, json!({ "id": { "in": [] } })).is_empty());
+        assert_eq!(ids(&conn, json!({ "id": { "not_in": [] } })), ["a", "b", "c", "d"]);
+        assert_eq!(db_count(&conn, "items", json!({ "where": { "id": { "in": [] } } })).unwrap(), 0);
+        assert_eq!(db_delete(&conn, "items", json!({ "where": { "id": { "in": [] } } })).unwrap(), 0);
+        assert_eq!(db_update(&conn, "items", json!({ "where": { "id": { "in": [] } } }), json!({ "score": 9 })).unwrap(), 0);
+    }
+
+    #[test]
+    fn or_and_not_nesting() {
+        let conn = items();
+        assert_eq!(ids(&conn, json!({ "or": [{ "id": "a" }, { "score": { "gte": 3 } }] })), ["a", "c"]);
+        assert_eq!(ids(&conn, json!({ "not": { "id": { "in": ["a", "b"] } } })), ["c", "d"]);
+        assert_eq!(
+            ids(&conn, json!({
+                "or": [
+                    { "and": [{ "score": { "gt": 1 } }, { "not": { "name": null } }] },
+                    { "not": { "or": [{ "score": { "not_null": true } }, { "id": "x" }] } }
+                ]
+            })),
+            ["b", "d"]
+        );
+        // Sibling keys and groups are ANDed
+        assert_eq!(ids(&conn, json!({ "or": [{ "id": "a" }, { "id": "b" }], "score": 2 })), ["b"]);
+    }
+
+    #[test]
+    fn rejects_bad_conditions() {
+        let conn = items();
+        for where_value in [
+            json!({ "score": {} }),
+            json!({ "score": { "near": 1 } }),
+            json!({ "score": { "between": [1] } }),
+            json!({ "id": { "in": "a" } }),
+            json!({ "or": { "id": "a" } }),
+            json!({ "id; DROP TABLE items": 1 }),
+        ] {
+            assert!(db_select(&conn, "items", json!({ "where": where_value })).is_err());
         }
     }
 
-    if !where_clauses.is_empty() {
-        sql.push_str(&format!(" WHERE {}", where_clauses.join(" AND ")));
+    /// Every id of `items` read page by page, checking each page's size and total.
+    fn walk(conn: &Connection, mut query: Value, limit: usize) -> Vec<String> {
+        query["limit"] = json!(limit);
+        query["withTotal"] = json!(true);
+        let mut seen = Vec::new();
+        loop {
+            let page = db_page(conn, "items", query.clone()).unwrap();
+            assert!(page.rows.len() <= limit);
+            assert!(page.rows.iter().all(|row| !row.contains_key(PAGE_KEY)));
+            let total = db_count(conn, "items", json!({ "where": query.get("where").cloned().unwrap_or(Value::Null) })).unwrap();
+            assert_eq!(page.total, Some(total));
+            seen.extend(page.rows.iter().map(|row| row["id"].as_str().unwrap().to_string()));
+            match page.next_cursor {
+                Some(cursor) => {
+                    assert_eq!(page.rows.len(), limit);
+                    query["cursor"] = json!(cursor);
+                }
+                None => return seen,
+            }
+        }
     }
 
-    let mut stmt = conn.prepare(&sql)?;
-    let changes = stmt.execute(params_from_iter(params_vec.into_iter()))?; // Fix: use params_from_iter
-    Ok(changes)
-}
\ No newline at end of file
+    #[test]
+    fn page_walks_null_keys_in_both_directions() {
+        let conn = items();
+        conn.execute("INSERT INTO items (id, name, score) VALUES ('e', 'elder', NULL)", []).unwrap();
+        for limit in 1..=6 {
+            // NULLs first ascending, ties broken by id
+            assert_eq!(walk(&conn, json!({ "orderBy": "score" }), limit), ["d", "e", "a", "b", "c"]);
+            // NULLs last descending, ties broken by id descending
+            assert_eq!(
+                walk(&conn, json!({ "orderBy": "score", "orderDirection": "desc" }), limit),
+                ["c", "b", "a", "e", "d"]
+            );
+            assert_eq!(
+                walk(&conn, json!({ "orderBy": "score", "where": { "id": { "ne": "e" } } }), limit),
+                ["d", "a", "b", "c"]
+            );
+            assert_eq!(
+                walk(&conn, json!({ "orderBy": "name", "orderDirection": "desc", "where": { "score": { "not_null": true } } }), limit),
+                ["b", "a", "c"]
+            );
+        }
+    }
+
+    #[test]
+    fn page_rejects_foreign_cursors() {
+        let conn = items();
+        let page = db_page(&conn, "items", json!({ "orderBy": "score", "limit": 2 })).unwrap();
+        let cursor = page.next_cursor.unwrap();
+        assert!(db_page(&conn, "items", json!({ "orderBy": "score", "orderDirection": "desc", "cursor": cursor })).is_err());
+        assert!(db_page(&conn, "items", json!({ "orderBy": "name", "cursor": cursor })).is_err());
+        assert!(db_page(&conn, "items", json!({ "cursor": "not a cursor" })).is_err());
+    }
+}
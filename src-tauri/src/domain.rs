@@ -0,0 +1,811 @@
+use std::collections::HashSet;
+
+use anyhow::{anyhow, Result as AnyhowResult};
+use rusqlite::Connection;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use tauri::AppHandle;
+
+use crate::cascade::DeletePlan;
+use crate::database::{ChangeOp, DbChange};
+use crate::pool::DbState;
+use crate::schema::{Operation, SchemaState};
+use crate::versions::RunnableType;
+
+// ── Domain API ───────────────────────────────────────────────────────────────
+//
+// Typed models for the core tables. The generic db_*_cmd commands stay for the
+// webview's storage modules; this layer is for code that should not rebuild their
+// rules (backend features, a CLI or HTTP API). `*_json` columns are parsed on read
+// and checked on write, missing fields take the column defaults, and rows linked
+// without a foreign key (tool_links, eval_test_cases) are kept consistent.
+
+const DEFAULT_COLLECTION_NAME: &str = "Default Collection";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JsonShape {
+    Object,
+    Array,
+    Any,
+}
+
+/// A `*_json` column and the JSON it must hold.
+pub struct JsonColumn {
+    pub name: &'static str,
+    pub shape: JsonShape,
+    pub nullable: bool,
+}
+
+const fn required(name: &'static str, shape: JsonShape) -> JsonColumn {
+    JsonColumn { name, shape, nullable: false }
+}
+
+const fn optional(name: &'static str, shape: JsonShape) -> JsonColumn {
+    JsonColumn { name, shape, nullable: true }
+}
+
+pub trait Entity: Serialize + DeserializeOwned {
+    const TABLE: &'static str;
+    const JSON_COLUMNS: &'static [JsonColumn];
+
+    fn id(&self) -> Option<&str>;
+
+    /// Fill in defaults that depend on other rows and check what the column types
+    /// cannot. `creating` is false when an existing row is saved.
+    fn prepare(&mut self, _conn: &Connection, _creating: bool) -> AnyhowResult<()> {
+        Ok(())
+    }
+}
+
+/// SQLite stores booleans as 0/1.
+mod flag {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &bool, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i64(*value as i64)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<bool, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Flag {
+            Bool(bool),
+            Int(i64),
+        }
+        Ok(match Flag::deserialize(deserializer)? {
+            Flag::Bool(value) => value,
+            Flag::Int(value) => value != 0,
+        })
+    }
+}
+
+fn empty_object() -> Value {
+    json!({})
+}
+
+fn empty_array() -> Value {
+    json!([])
+}
+
+fn first_version() -> i64 {
+    1
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn require_text(table: &str, column: &str, value: &str) -> AnyhowResult<()> {
+    if value.trim().is_empty() {
+        return Err(anyhow!("{}.{} must not be empty", table, column));
+    }
+    Ok(())
+}
+
+fn runnable_exists(conn: &Connection, kind: RunnableType, id: &str) -> AnyhowResult<()> {
+    if crate::database::db_count(conn, kind.table(), json!({ "where": { "id": id } }))? == 0 {
+        return Err(anyhow!("{} '{}' not found", kind.as_str(), id));
+    }
+    Ok(())
+}
+
+// ── Models ───────────────────────────────────────────────────────────────────
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct Scenario {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// Empty: the default collection (created when missing)
+    #[serde(default)]
+    pub collection_id: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub provider: String,
+    pub model: String,
+    #[serde(default)]
+    pub system_prompt: String,
+    #[serde(default)]
+    pub user_prompt: String,
+    /// Prior conversation turns
+    pub history_json: Option<Value>,
+    /// `{{variable}}` values by name
+    pub variables_json: Option<Value>,
+    #[serde(default = "empty_object")]
+    pub params_json: Value,
+    pub response_format_json: Option<Value>,
+    pub tools_json: Option<Value>,
+    pub attachments_json: Option<Value>,
+    pub provider_meta_json: Option<Value>,
+    #[serde(default = "first_version")]
+    pub version: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<i64>,
+    pub archived_at: Option<i64>,
+}
+
+impl Entity for Scenario {
+    const TABLE: &'static str = "scenarios";
+    const JSON_COLUMNS: &'static [JsonColumn] = &[
+        optional("history_json", JsonShape::Array),
+        optional("variables_json", JsonShape::Object),
+        required("params_json", JsonShape::Object),
+        optional("response_format_json", JsonShape::Any),
+        optional("tools_json", JsonShape::Array),
+        optional("attachments_json", JsonShape::Array),
+        optional("provider_meta_json", JsonShape::Any),
+    ];
+
+    fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    fn prepare(&mut self, conn: &Connection, _creating: bool) -> AnyhowResult<()> {
+        require_text(Self::TABLE, "title", &self.title)?;
+        if self.collection_id.is_empty() {
+            self.collection_id = default_collection(conn)?;
+        } else if crate::database::db_count(conn, "collections", json!({ "where": { "id": self.collection_id } }))? == 0 {
+            return Err(anyhow!("collection '{}' not found", self.collection_id));
+        }
+        Ok(())
+    }
+}
+
+/// Id of the collection new scenarios go to when none is given.
+fn default_collection(conn: &Connection) -> AnyhowResult<String> {
+    let existing = crate::database::db_select(conn, "collections", json!({
+        "where": { "name": DEFAULT_COLLECTION_NAME },
+        "limit": 1
+    }))?
+    .pop()
+    .and_then(|row| row.get("id").and_then(|v| v.as_str()).map(String::from));
+    match existing {
+        Some(id) => Ok(id),
+        None => crate::database::db_insert(conn, "collections", json!({
+            "name": DEFAULT_COLLECTION_NAME,
+            "description": "Default collection for scenarios"
+        })),
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RetryPolicy {
+    None,
+    Fixed,
+    #[default]
+    Exponential,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolCallStrategy {
+    #[default]
+    Auto,
+    Forced,
+    Restricted,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MemorySource {
+    #[default]
+    Local,
+    File,
+    Vector,
+}
+
+fn default_agent_params() -> Value {
+    json!({ "temperature": 0.4, "top_p": 0.95, "max_tokens": 4096 })
+}
+
+fn default_max_iterations() -> i64 {
+    10
+}
+
+fn default_timeout_seconds() -> i64 {
+    60
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct Agent {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub name: String,
+    pub description: Option<String>,
+    pub provider: String,
+    pub model: String,
+    #[serde(default = "default_agent_params")]
+    pub params_json: Value,
+    pub agent_goal: Option<String>,
+    pub system_instructions: Option<String>,
+    #[serde(default = "empty_array")]
+    pub tools_json: Value,
+    #[serde(default = "default_max_iterations")]
+    pub max_iterations: i64,
+    #[serde(default = "default_timeout_seconds")]
+    pub timeout_seconds: i64,
+    #[serde(default)]
+    pub retry_policy: RetryPolicy,
+    #[serde(default)]
+    pub tool_call_strategy: ToolCallStrategy,
+    #[serde(default, with = "flag")]
+    pub memory_enabled: bool,
+    #[serde(default)]
+    pub memory_source: MemorySource,
+    #[serde(default = "default_true", with = "flag")]
+    pub human_in_the_loop: bool,
+    #[serde(default = "first_version")]
+    pub version: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<i64>,
+    pub archived_at: Option<i64>,
+}
+
+impl Entity for Agent {
+    const TABLE: &'static str = "agents";
+    const JSON_COLUMNS: &'static [JsonColumn] = &[
+        required("params_json", JsonShape::Object),
+        required("tools_json", JsonShape::Array),
+    ];
+
+    fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    fn prepare(&mut self, _conn: &Connection, _creating: bool) -> AnyhowResult<()> {
+        require_text(Self::TABLE, "name", &self.name)?;
+        if self.max_iterations < 1 {
+            return Err(anyhow!("agents.max_iterations must be at least 1"));
+        }
+        if self.timeout_seconds < 1 {
+            return Err(anyhow!("agents.timeout_seconds must be at least 1"));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MockMode {
+    #[default]
+    Json,
+    Code,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct Tool {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub name: String,
+    pub description: Option<String>,
+    /// `[{ name, type, description, required }]`
+    #[serde(default = "empty_array")]
+    pub parameters_json: Value,
+    pub mock_response: Option<String>,
+    #[serde(default)]
+    pub mock_mode: MockMode,
+    pub code: Option<String>,
+    #[serde(default = "default_true", with = "flag")]
+    pub is_enabled: bool,
+    /// Shared across scenarios and agents (see `set_linked_tools`)
+    #[serde(default, with = "flag")]
+    pub is_global: bool,
+    #[serde(default)]
+    pub sort_order: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<i64>,
+    pub archived_at: Option<i64>,
+}
+
+impl Entity for Tool {
+    const TABLE: &'static str = "tools";
+    const JSON_COLUMNS: &'static [JsonColumn] = &[required("parameters_json", JsonShape::Array)];
+
+    fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    fn prepare(&mut self, _conn: &Connection, _creating: bool) -> AnyhowResult<()> {
+        require_text(Self::TABLE, "name", &self.name)?;
+        let mut names = HashSet::new();
+        for parameter in self.parameters_json.as_array().into_iter().flatten() {
+            let name = parameter.get("name").and_then(|v| v.as_str()).unwrap_or_default();
+            require_text(Self::TABLE, "parameters_json[].name", name)?;
+            if !names.insert(name) {
+                return Err(anyhow!("Tool '{}' has two parameters named '{}'", self.name, name));
+            }
+        }
+        if self.mock_mode == MockMode::Code && self.code.as_deref().is_none_or(|code| code.trim().is_empty()) {
+            return Err(anyhow!("Tool '{}' mocks with code but has none", self.name));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct ToolLink {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub tool_id: String,
+    pub toolable_id: String,
+    pub toolable_type: RunnableType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<i64>,
+}
+
+impl Entity for ToolLink {
+    const TABLE: &'static str = "tool_links";
+    const JSON_COLUMNS: &'static [JsonColumn] = &[];
+
+    fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    fn prepare(&mut self, conn: &Connection, _creating: bool) -> AnyhowResult<()> {
+        runnable_exists(conn, self.toolable_type, &self.toolable_id)
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct EvalTestCase {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub runnable_id: String,
+    pub runnable_type: RunnableType,
+    #[serde(default)]
+    pub sort_order: i64,
+    /// Variable values by name
+    #[serde(default = "empty_object")]
+    pub inputs_json: Value,
+    #[serde(default = "empty_array")]
+    pub assertions_json: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<i64>,
+}
+
+impl Entity for EvalTestCase {
+    const TABLE: &'static str = "eval_test_cases";
+    const JSON_COLUMNS: &'static [JsonColumn] = &[
+        required("inputs_json", JsonShape::Object),
+        required("assertions_json", JsonShape::Array),
+    ];
+
+    fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    fn prepare(&mut self, conn: &Connection, _creating: bool) -> AnyhowResult<()> {
+        runnable_exists(conn, self.runnable_type, &self.runnable_id)
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutionStatus {
+    #[default]
+    Queued,
+    Running,
+    Succeeded,
+    Failed,
+    Canceled,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct Execution {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(rename = "type")]
+    pub kind: RunnableType,
+    pub runnable_id: String,
+    pub runnable_version: Option<i64>,
+    /// The scenario or agent as it was run
+    #[serde(default = "empty_object")]
+    pub snapshot_json: Value,
+    pub input_json: Option<Value>,
+    pub request_json: Option<Value>,
+    pub result_json: Option<Value>,
+    pub tool_calls_json: Option<Value>,
+    pub steps_json: Option<Value>,
+    #[serde(default)]
+    pub status: ExecutionStatus,
+    pub started_at: Option<i64>,
+    pub ended_at: Option<i64>,
+    pub usage_json: Option<Value>,
+    pub error_json: Option<Value>,
+    #[serde(default, with = "flag")]
+    pub starred: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<i64>,
+}
+
+impl Entity for Execution {
+    const TABLE: &'static str = "executions";
+    const JSON_COLUMNS: &'static [JsonColumn] = &[
+        required("snapshot_json", JsonShape::Object),
+        optional("input_json", JsonShape::Any),
+        optional("request_json", JsonShape::Any),
+        optional("result_json", JsonShape::Any),
+        optional("tool_calls_json", JsonShape::Array),
+        optional("steps_json", JsonShape::Array),
+        optional("usage_json", JsonShape::Object),
+        optional("error_json", JsonShape::Any),
+    ];
+
+    fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    /// Runs are kept after their scenario or agent is deleted, so only new ones are checked.
+    fn prepare(&mut self, conn: &Connection, creating: bool) -> AnyhowResult<()> {
+        if creating {
+            runnable_exists(conn, self.kind, &self.runnable_id)?;
+        }
+        if let (Some(started), Some(ended)) = (self.started_at, self.ended_at) {
+            if ended < started {
+                return Err(anyhow!("executions.ended_at is before started_at"));
+            }
+        }
+        Ok(())
+    }
+}
+
+// ── Rows ─────────────────────────────────────────────────────────────────────
+
+fn from_row<E: Entity>(mut row: Map<String, Value>) -> AnyhowResult<E> {
+    for column in E::JSON_COLUMNS {
+        if let Some(Value::String(text)) = row.get(column.name) {
+            let parsed = serde_json::from_str(text)
+                .map_err(|e| anyhow!("{}.{} is not valid JSON: {}", E::TABLE, column.name, e))?;
+            row.insert(column.name.to_string(), parsed);
+        }
+    }
+    serde_json::from_value(Value::Object(row)).map_err(|e| anyhow!("Invalid {} row: {}", E::TABLE, e))
+}
+
+fn to_row<E: Entity>(entity: &E) -> AnyhowResult<Map<String, Value>> {
+    let Value::Object(mut row) = serde_json::to_value(entity)? else {
+        return Err(anyhow!("{} must serialize to an object", E::TABLE));
+    };
+    for column in E::JSON_COLUMNS {
+        let value = row.remove(column.name).unwrap_or(Value::Null);
+        let fits = match (&value, column.shape) {
+            (Value::Null, _) => column.nullable,
+            (_, JsonShape::Object) => value.is_object(),
+            (_, JsonShape::Array) => value.is_array(),
+            (_, JsonShape::Any) => true,
+        };
+        if !fits {
+            let expected = match column.shape {
+                JsonShape::Object => "an object",
+                JsonShape::Array => "an array",
+                JsonShape::Any => "a value",
+            };
+            return Err(anyhow!("{}.{} must be {}", E::TABLE, column.name, expected));
+        }
+        let stored = if value.is_null() { Value::Null } else { Value::String(value.to_string()) };
+        row.insert(column.name.to_string(), stored);
+    }
+    Ok(row)
+}
+
+// ── Operations ───────────────────────────────────────────────────────────────
+
+pub fn get<E: Entity>(conn: &Connection, id: &str) -> AnyhowResult<E> {
+    let row = crate::database::db_select(conn, E::TABLE, json!({ "where": { "id": id }, "limit": 1 }))?
+        .pop()
+        .ok_or_else(|| anyhow!("{} '{}' not found", E::TABLE, id))?;
+    from_row(row)
+}
+
+/// Rows matching `query` (same shape as for `db_select`).
+pub fn list<E: Entity>(conn: &Connection, query: Value) -> AnyhowResult<Vec<E>> {
+    crate::database::db_select(conn, E::TABLE, query)?
+        .into_iter()
+        .map(from_row)
+        .collect()
+}
+
+/// Insert `entity` (with a new id unless it has one) and return it as stored.
+pub fn create<E: Entity>(conn: &Connection, mut entity: E) -> AnyhowResult<E> {
+    entity.prepare(conn, true)?;
+    let id = crate::database::db_insert(conn, E::TABLE, Value::Object(to_row(&entity)?))?;
+    get(conn, &id)
+}
+
+/// Write every column of an existing row and return it as stored.
+pub fn save<E: Entity>(conn: &Connection, mut entity: E) -> AnyhowResult<E> {
+    let id = entity.id().ok_or_else(|| anyhow!("{} row to save has no id", E::TABLE))?.to_string();
+    get::<E>(conn, &id)?;
+    entity.prepare(conn, false)?;
+    let mut row = to_row(&entity)?;
+    for column in ["id", "created_at", "updated_at"] {
+        row.remove(column);
+    }
+    crate::database::db_update(conn, E::TABLE, json!({ "where": { "id": id } }), Value::Object(row))?;
+    get(conn, &id)
+}
+
+/// Delete a row. Scenarios and agents go through `cascade::delete_matching`: their
+/// tool links, eval test cases and runs follow the delete policy, which may archive
+/// them instead or refuse.
+pub fn delete<E: Entity>(conn: &mut Connection, id: &str) -> AnyhowResult<DeletePlan> {
+    let query = json!({ "where": { "id": id } });
+    if crate::cascade::CASCADING_TABLES.contains(&E::TABLE) {
+        return crate::cascade::delete_matching(conn, E::TABLE, &query);
+    }
+    let deleted = crate::database::db_delete(conn, E::TABLE, query)?;
+    let ids = if deleted > 0 { vec![id.to_string()] } else { Vec::new() };
+    Ok(DeletePlan::deleted(E::TABLE, ids))
+}
+
+/// Tools linked to a scenario or agent, in `sort_order`.
+pub fn linked_tools(conn: &Connection, kind: RunnableType, id: &str) -> AnyhowResult<Vec<Tool>> {
+    let links: Vec<ToolLink> = list(conn, json!({
+        "where": { "toolable_type": kind.as_str(), "toolable_id": id }
+    }))?;
+    let tool_ids: Vec<String> = links.into_iter().map(|link| link.tool_id).collect();
+    list(conn, json!({
+        "where": { "id": { "in": tool_ids } },
+        "orderBy": "sort_order",
+        "orderDirection": "asc"
+    }))
+}
+
+/// Drop the links from a runnable to tools other than `keep`, then delete the
+/// local (non-global) tools that are no longer linked anywhere.
+fn unlink_tools(conn: &Connection, kind: RunnableType, id: &str, keep: &[String]) -> AnyhowResult<()> {
+    let dropped: Vec<ToolLink> = list(conn, json!({
+        "where": { "toolable_type": kind.as_str(), "toolable_id": id, "tool_id": { "not_in": keep } }
+    }))?;
+    crate::database::db_delete(conn, "tool_links", json!({
+        "where": { "toolable_type": kind.as_str(), "toolable_id": id, "tool_id": { "not_in": keep } }
+    }))?;
+    for link in dropped {
+        let still_linked = crate::database::db_count(conn, "tool_links", json!({ "where": { "tool_id": link.tool_id } }))? > 0;
+        if !still_linked {
+            crate::database::db_delete(conn, "tools", json!({ "where": { "id": link.tool_id, "is_global": 0 } }))?;
+        }
+    }
+    Ok(())
+}
+
+/// Make `tools` the tools of a scenario or agent, in this order: new tools are
+/// created, existing local ones saved, and tools left out are unlinked (and deleted
+/// when local and unused). Global tools are shared with other runnables, so they are
+/// only linked, never changed here. Returns the linked tools as stored.
+pub fn set_linked_tools(
+    conn: &mut Connection,
+    kind: RunnableType,
+    id: &str,
+    tools: Vec<Tool>,
+) -> AnyhowResult<Vec<Tool>> {
+    let tx = conn.transaction()?;
+    runnable_exists(&tx, kind, id)?;
+
+    let mut tool_ids = Vec::with_capacity(tools.len());
+    for (sort_order, mut tool) in tools.into_iter().enumerate() {
+        tool.sort_order = sort_order as i64;
+        let stored: Option<Tool> = match tool.id() {
+            Some(tool_id) => list(&tx, json!({ "where": { "id": tool_id } }))?.pop(),
+            None => None,
+        };
+        let stored = match stored {
+            Some(global) if global.is_global => global,
+            Some(_) => save(&tx, tool)?,
+            None => create(&tx, tool)?,
+        };
+        let tool_id = stored.id.ok_or_else(|| anyhow!("Stored tool has no id"))?;
+
+        let link = json!({ "tool_id": tool_id, "toolable_type": kind.as_str(), "toolable_id": id });
+        if crate::database::db_count(&tx, "tool_links", json!({ "where": link }))? == 0 {
+            crate::database::db_insert(&tx, "tool_links", link)?;
+        }
+        tool_ids.push(tool_id);
+    }
+    unlink_tools(&tx, kind, id, &tool_ids)?;
+
+    let linked = linked_tools(&tx, kind, id)?;
+    tx.commit()?;
+    Ok(linked)
+}
+
+// ── Commands ─────────────────────────────────────────────────────────────────
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EntityType {
+    Scenario,
+    Agent,
+    Tool,
+    ToolLink,
+    EvalTestCase,
+    Execution,
+}
+
+/// Run `$body` with `$E` bound to the model type of `$kind`.
+macro_rules! with_entity {
+    ($kind:expr, $E:ident => $body:expr) => {
+        match $kind {
+            EntityType::Scenario => {
+                type $E = Scenario;
+                $body
+            }
+            EntityType::Agent => {
+                type $E = Agent;
+                $body
+            }
+            EntityType::Tool => {
+                type $E = Tool;
+                $body
+            }
+            EntityType::ToolLink => {
+                type $E = ToolLink;
+                $body
+            }
+            EntityType::EvalTestCase => {
+                type $E = EvalTestCase;
+                $body
+            }
+            EntityType::Execution => {
+                type $E = Execution;
+                $body
+            }
+        }
+    };
+}
+
+impl EntityType {
+    fn table(self) -> &'static str {
+        with_entity!(self, E => E::TABLE)
+    }
+}
+
+fn id_of(entity: &Value) -> Vec<String> {
+    entity.get("id").and_then(|v| v.as_str()).map(String::from).into_iter().collect()
+}
+
+/// A row of `entity_type` by id, with its `*_json` columns parsed.
+#[tauri::command]
+pub async fn entity_get(entity_type: EntityType, id: String, state: tauri::State<'_, DbState>) -> Result<Value, String> {
+    state
+        .read(move |conn| with_entity!(entity_type, E => Ok(serde_json::to_value(get::<E>(conn, &id)?)?)))
+        .await
+}
+
+/// Rows of `entity_type` matching `query` (same shape as for `db_select_cmd`).
+#[tauri::command]
+pub async fn entity_list(
+    entity_type: EntityType,
+    query: Option<Value>,
+    state: tauri::State<'_, DbState>,
+    schema: tauri::State<'_, SchemaState>,
+) -> Result<Vec<Value>, String> {
+    let query = query.unwrap_or_else(|| json!({}));
+    let table = with_entity!(entity_type, E => E::TABLE);
+    schema.authorize(table, Operation::Select, Some(&query), None).map_err(|e| e.to_string())?;
+    state
+        .read(move |conn| {
+            with_entity!(entity_type, E => list::<E>(conn, query)?
+                .iter()
+                .map(|entity| Ok(serde_json::to_value(entity)?))
+                .collect())
+        })
+        .await
+}
+
+/// Validate and insert a row; returns it as stored (with id and defaults).
+#[tauri::command]
+pub async fn entity_create(
+    entity_type: EntityType,
+    data: Value,
+    state: tauri::State<'_, DbState>,
+    app: AppHandle,
+) -> Result<Value, String> {
+    let stored = state
+        .write(move |conn| {
+            with_entity!(entity_type, E => {
+                let entity: E = serde_json::from_value(data)?;
+                Ok(serde_json::to_value(create(conn, entity)?)?)
+            })
+        })
+        .await?;
+    crate::notify_change(&app, DbChange::touched(entity_type.table(), ChangeOp::Insert, id_of(&stored)));
+    Ok(stored)
+}
+
+/// Validate and overwrite an existing row (`data.id`); returns it as stored.
+#[tauri::command]
+pub async fn entity_save(
+    entity_type: EntityType,
+    data: Value,
+    state: tauri::State<'_, DbState>,
+    app: AppHandle,
+) -> Result<Value, String> {
+    let stored = state
+        .write(move |conn| {
+            with_entity!(entity_type, E => {
+                let entity: E = serde_json::from_value(data)?;
+                Ok(serde_json::to_value(save(conn, entity)?)?)
+            })
+        })
+        .await?;
+    crate::notify_change(&app, DbChange::touched(entity_type.table(), ChangeOp::Update, id_of(&stored)));
+    Ok(stored)
+}
+
+/// Delete a row (and the rows linked to a scenario or agent, see `delete`). Returns
+/// the number of rows deleted or archived.
+#[tauri::command]
+pub async fn entity_delete(
+    entity_type: EntityType,
+    id: String,
+    state: tauri::State<'_, DbState>,
+    app: AppHandle,
+) -> Result<usize, String> {
+    let plan = state
+        .write(move |conn| with_entity!(entity_type, E => delete::<E>(conn, &id)))
+        .await?;
+    crate::cascade::notify(&app, &plan);
+    Ok(plan.affected())
+}
+
+/// Replace the tools of a scenario or agent (see `set_linked_tools`).
+///
+/// `runnable_type` – scenario | agent
+/// `runnable_id`   – id of the scenario or agent
+/// `tools`         – the tools in order; those without an id (or an unknown one) are created
+#[tauri::command]
+pub async fn set_linked_tools_cmd(
+    runnable_type: RunnableType,
+    runnable_id: String,
+    tools: Vec<Tool>,
+    state: tauri::State<'_, DbState>,
+    app: AppHandle,
+) -> Result<Vec<Tool>, String> {
+    let (linked, link_ids) = state
+        .write(move |conn| {
+            let linked = set_linked_tools(conn, runnable_type, &runnable_id, tools)?;
+            let links: Vec<ToolLink> = list(conn, json!({
+                "where": { "toolable_type": runnable_type.as_str(), "toolable_id": runnable_id }
+            }))?;
+            Ok((linked, links.into_iter().filter_map(|link| link.id).collect()))
+        })
+        .await?;
+    let tool_ids = linked.iter().filter_map(|tool| tool.id.clone()).collect();
+    crate::notify_change(&app, DbChange::touched("tools", ChangeOp::Update, tool_ids));
+    crate::notify_change(&app, DbChange::touched("tool_links", ChangeOp::Update, link_ids));
+    Ok(linked)
+}
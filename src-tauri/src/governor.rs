@@ -0,0 +1,326 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::pool::DbState;
+
+// ── Client-side rate limiting ────────────────────────────────────────────────
+//
+// Requests to a provider (and optionally a single model) are admitted through
+// token buckets for requests/min and tokens/min plus a concurrency limit. Instead
+// of failing, excess requests wait in FIFO order: the admission mutex and the
+// concurrency semaphore are both fair in tokio.
+
+/// `settings.key` holding the persisted rules (JSON array of LimitRule).
+const RATE_LIMIT_SETTING: &str = "proxy_rate_limits";
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct LimitRule {
+    pub provider: String,
+    /// Limit a single model; the rule applies to the whole provider when omitted.
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub requests_per_minute: Option<u32>,
+    #[serde(default)]
+    pub tokens_per_minute: Option<u32>,
+    #[serde(default)]
+    pub max_concurrency: Option<u32>,
+}
+
+impl LimitRule {
+    fn key(&self) -> String {
+        limiter_key(&self.provider, self.model.as_deref())
+    }
+}
+
+fn limiter_key(provider: &str, model: Option<&str>) -> String {
+    match model {
+        Some(model) => format!("{}:{}", provider, model),
+        None => provider.to_string(),
+    }
+}
+
+struct Bucket {
+    capacity: f64,
+    available: f64,
+    refill_per_ms: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    fn per_minute(limit: u32) -> Self {
+        Self {
+            capacity: limit as f64,
+            available: limit as f64,
+            refill_per_ms: limit as f64 / 60_000.0,
+            updated: Instant::now(),
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed_ms = now.saturating_duration_since(self.updated).as_secs_f64() * 1000.0;
+        self.available = (self.available + elapsed_ms * self.refill_per_ms).min(self.capacity);
+        self.updated = now;
+    }
+
+    /// How long until `cost` is available. Costs above capacity only need a full bucket.
+    fn wait_for(&self, cost: f64) -> Duration {
+        let needed = cost.min(self.capacity) - self.available;
+        if needed <= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(needed / self.refill_per_ms / 1000.0)
+        }
+    }
+}
+
+#[derive(Default)]
+struct LimiterStats {
+    queued: usize,
+    admitted: u64,
+    total_wait_ms: u64,
+    max_wait_ms: u64,
+    last_wait_ms: u64,
+}
+
+struct Limiter {
+    rule: LimitRule,
+    /// Held while waiting for the buckets so requests are admitted in arrival order.
+    admission: tokio::sync::Mutex<()>,
+    requests: Option<Mutex<Bucket>>,
+    tokens: Option<Mutex<Bucket>>,
+    concurrency: Option<Arc<Semaphore>>,
+    stats: Mutex<LimiterStats>,
+}
+
+impl Limiter {
+    fn new(rule: LimitRule) -> Self {
+        Self {
+            requests: rule.requests_per_minute.filter(|n| *n > 0).map(|n| Mutex::new(Bucket::per_minute(n))),
+            tokens: rule.tokens_per_minute.filter(|n| *n > 0).map(|n| Mutex::new(Bucket::per_minute(n))),
+            concurrency: rule.max_concurrency.filter(|n| *n > 0).map(|n| Arc::new(Semaphore::new(n as usize))),
+            admission: tokio::sync::Mutex::new(()),
+            stats: Mutex::new(LimiterStats::default()),
+            rule,
+        }
+    }
+
+    async fn admit(&self, tokens: u64) -> (Option<OwnedSemaphorePermit>, Duration) {
+        let started = Instant::now();
+        let queued = QueuedGuard::new(&self.stats);
+
+        let permit = match &self.concurrency {
+            Some(semaphore) => semaphore.clone().acquire_owned().await.ok(),
+            None => None,
+        };
+
+        {
+            let _turn = self.admission.lock().await;
+            loop {
+                let now = Instant::now();
+                let mut wait = Duration::ZERO;
+                if let Some(bucket) = &self.requests {
+                    let mut bucket = bucket.lock().unwrap();
+                    bucket.refill(now);
+                    wait = wait.max(bucket.wait_for(1.0));
+                }
+                if let Some(bucket) = &self.tokens {
+                    let mut bucket = bucket.lock().unwrap();
+                    bucket.refill(now);
+                    wait = wait.max(bucket.wait_for(tokens as f64));
+                }
+                if wait.is_zero() {
+                    break;
+                }
+                tokio::time::sleep(wait).await;
+            }
+            if let Some(bucket) = &self.requests {
+                let mut bucket = bucket.lock().unwrap();
+                bucket.available -= 1.0_f64.min(bucket.capacity);
+            }
+            if let Some(bucket) = &self.tokens {
+                let mut bucket = bucket.lock().unwrap();
+                bucket.available -= (tokens as f64).min(bucket.capacity);
+            }
+        }
+
+        drop(queued);
+
+        let waited = started.elapsed();
+        let waited_ms = waited.as_millis() as u64;
+        let mut stats = self.stats.lock().unwrap();
+        stats.admitted += 1;
+        stats.total_wait_ms += waited_ms;
+        stats.max_wait_ms = stats.max_wait_ms.max(waited_ms);
+        stats.last_wait_ms = waited_ms;
+        (permit, waited)
+    }
+
+    fn status(&self) -> LimiterStatus {
+        let stats = self.stats.lock().unwrap();
+        let now = Instant::now();
+        let available = |bucket: &Option<Mutex<Bucket>>| {
+            bucket.as_ref().map(|b| {
+                let mut b = b.lock().unwrap();
+                b.refill(now);
+                b.available.max(0.0).floor() as u64
+            })
+        };
+        LimiterStatus {
+            rule: self.rule.clone(),
+            queue_depth: stats.queued,
+            in_flight: match (&self.concurrency, self.rule.max_concurrency) {
+                (Some(semaphore), Some(max)) => (max as usize).saturating_sub(semaphore.available_permits()),
+                _ => 0,
+            },
+            available_requests: available(&self.requests),
+            available_tokens: available(&self.tokens),
+            admitted: stats.admitted,
+            avg_wait_ms: if stats.admitted > 0 { stats.total_wait_ms as f64 / stats.admitted as f64 } else { 0.0 },
+            max_wait_ms: stats.max_wait_ms,
+            last_wait_ms: stats.last_wait_ms,
+        }
+    }
+}
+
+/// Counts a request as queued for as long as it is waiting, including when the
+/// waiting request is dropped (e.g. the caller disconnected).
+struct QueuedGuard<'a>(&'a Mutex<LimiterStats>);
+
+impl<'a> QueuedGuard<'a> {
+    fn new(stats: &'a Mutex<LimiterStats>) -> Self {
+        stats.lock().unwrap().queued += 1;
+        Self(stats)
+    }
+}
+
+impl Drop for QueuedGuard<'_> {
+    fn drop(&mut self) {
+        self.0.lock().unwrap().queued -= 1;
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct LimiterStatus {
+    #[serde(flatten)]
+    pub rule: LimitRule,
+    pub queue_depth: usize,
+    pub in_flight: usize,
+    pub available_requests: Option<u64>,
+    pub available_tokens: Option<u64>,
+    pub admitted: u64,
+    pub avg_wait_ms: f64,
+    pub max_wait_ms: u64,
+    pub last_wait_ms: u64,
+}
+
+/// Concurrency permits for an admitted request; release them by dropping it once
+/// the response has been fully received.
+pub struct Admission {
+    _permits: Vec<OwnedSemaphorePermit>,
+    pub waited: Duration,
+}
+
+#[derive(Default)]
+pub struct Governor {
+    limiters: RwLock<HashMap<String, Arc<Limiter>>>,
+}
+
+pub type GovernorState = Arc<Governor>;
+
+impl Governor {
+    /// Load the persisted rules from the `settings` table.
+    pub fn load(conn: &Connection) -> Self {
+        let governor = Governor::default();
+        governor.set_rules(stored_rules(conn));
+        governor
+    }
+
+    /// Replace the rules. Limiters whose rule is unchanged keep their state; requests
+    /// already admitted under a replaced limiter keep their permits until they finish.
+    pub fn set_rules(&self, rules: Vec<LimitRule>) {
+        let mut limiters = self.limiters.write().unwrap();
+        let mut next = HashMap::new();
+        for rule in rules {
+            let key = rule.key();
+            let limiter = match limiters.remove(&key) {
+                Some(existing) if existing.rule == rule => existing,
+                _ => Arc::new(Limiter::new(rule)),
+            };
+            next.insert(key, limiter);
+        }
+        *limiters = next;
+    }
+
+    /// Wait until a request to `provider`/`model` estimated at `tokens` may be sent.
+    pub async fn admit(&self, provider: &str, model: Option<&str>, tokens: u64) -> Admission {
+        let mut keys = vec![limiter_key(provider, None)];
+        if let Some(model) = model {
+            keys.push(limiter_key(provider, Some(model)));
+        }
+        let applicable: Vec<Arc<Limiter>> = {
+            let limiters = self.limiters.read().unwrap();
+            keys.iter().filter_map(|key| limiters.get(key).cloned()).collect()
+        };
+
+        let started = Instant::now();
+        let mut permits = Vec::new();
+        // Provider before model, so concurrent requests acquire in the same order.
+        for limiter in applicable {
+            let (permit, _) = limiter.admit(tokens).await;
+            permits.extend(permit);
+        }
+        Admission {
+            _permits: permits,
+            waited: started.elapsed(),
+        }
+    }
+
+    pub fn status(&self) -> Vec<LimiterStatus> {
+        let mut status: Vec<LimiterStatus> = self.limiters.read().unwrap().values().map(|l| l.status()).collect();
+        status.sort_by(|a, b| (&a.rule.provider, &a.rule.model).cmp(&(&b.rule.provider, &b.rule.model)));
+        status
+    }
+}
+
+/// Rules persisted in the `settings` table (none when unset or unreadable).
+pub fn stored_rules(conn: &Connection) -> Vec<LimitRule> {
+    crate::settings::get_json(conn, RATE_LIMIT_SETTING).unwrap_or_default()
+}
+
+/// Rough token cost of a chat request for the tokens/min bucket: ~4 characters per
+/// prompt token plus the requested completion budget, which providers also count.
+pub fn estimate_tokens(body: Option<&Map<String, Value>>, body_len: usize) -> u64 {
+    let completion = body
+        .and_then(|b| b.get("max_completion_tokens").or_else(|| b.get("max_tokens")))
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0);
+    (body_len as u64).div_ceil(4) + completion
+}
+
+// ── Commands ─────────────────────────────────────────────────────────────────
+
+/// Current limiter rules with queue depth, in-flight count and wait times.
+#[tauri::command]
+pub fn get_rate_limiter_status(state: tauri::State<'_, GovernorState>) -> Vec<LimiterStatus> {
+    state.status()
+}
+
+/// Replace the client-side rate limit rules and persist them.
+#[tauri::command]
+pub async fn set_rate_limiter_rules(
+    rules: Vec<LimitRule>,
+    state: tauri::State<'_, GovernorState>,
+    db: tauri::State<'_, DbState>,
+) -> Result<Vec<LimiterStatus>, String> {
+    let stored = rules.clone();
+    db.write(move |conn| crate::settings::set_json(conn, RATE_LIMIT_SETTING, &stored)).await?;
+    state.set_rules(rules);
+    Ok(state.status())
+}
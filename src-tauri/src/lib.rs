@@ -1,9 +1,19 @@
-use tauri::Manager;
+use tauri::{AppHandle, Emitter, Manager};
 use tauri::WebviewWindowBuilder;
 use serde_json::Value;
 
 mod runner;
 
+use database::{ChangeOp, DbChange};
+use schema::Operation;
+
+/// Tell every window about a successful write (see `database::DB_CHANGE_EVENT`).
+fn notify_change(app: &AppHandle, change: Option<DbChange>) {
+    if let Some(change) = change {
+        app.emit(database::DB_CHANGE_EVENT, change).ok();
+    }
+}
+
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 #[tauri::command]
 fn greet(name: &str) -> String {
@@ -13,43 +23,106 @@ fn greet(name: &str) -> String {
 #[tauri::command]
 async fn db_insert_cmd(
     table: String,
-    data: Value,
-    state: tauri::State<'_, Arc<Mutex<rusqlite::Connection>>>,
+    mut data: Value,
+    state: tauri::State<'_, DbState>,
+    schema: tauri::State<'_, schema::SchemaState>,
+    secrets: tauri::State<'_, secrets::SecretsState>,
+    app: AppHandle,
 ) -> Result<String, String> {
-    let conn = state.lock().unwrap();
-    database::db_insert(&conn, &table, data).map_err(|e| e.to_string())
+    schema.authorize(&table, Operation::Insert, None, Some(&data)).map_err(|e| e.to_string())?;
+    let secrets = secrets.inner().clone();
+    let target = table.clone();
+    let id = state
+        .write(move |conn| {
+            secrets.seal(conn, &target, None, &mut data)?;
+            database::db_insert(conn, &target, data)
+        })
+        .await?;
+    notify_change(&app, DbChange::touched(&table, ChangeOp::Insert, vec![id.clone()]));
+    Ok(id)
 }
 
 #[tauri::command]
 async fn db_select_cmd(
     table: String,
     query: Value,
-    state: tauri::State<'_, Arc<Mutex<rusqlite::Connection>>>,
+    state: tauri::State<'_, DbState>,
+    schema: tauri::State<'_, schema::SchemaState>,
+    secrets: tauri::State<'_, secrets::SecretsState>,
 ) -> Result<Value, String> {
-    let conn = state.lock().unwrap();
-    let result = database::db_select(&conn, &table, query).map_err(|e| e.to_string())?;
+    schema.authorize(&table, Operation::Select, Some(&query), None).map_err(|e| e.to_string())?;
+    let target = table.clone();
+    let mut result = state.read(move |conn| database::db_select(conn, &target, query)).await?;
+    secrets.mask(&table, &mut result);
     Ok(serde_json::to_value(result).map_err(|e| e.to_string())?)
 }
 
+#[tauri::command]
+async fn db_page_cmd(
+    table: String,
+    query: Value,
+    state: tauri::State<'_, DbState>,
+    schema: tauri::State<'_, schema::SchemaState>,
+    secrets: tauri::State<'_, secrets::SecretsState>,
+) -> Result<database::Page, String> {
+    schema.authorize(&table, Operation::Select, Some(&query), None).map_err(|e| e.to_string())?;
+    let target = table.clone();
+    let mut page = state.read(move |conn| database::db_page(conn, &target, query)).await?;
+    secrets.mask(&table, &mut page.rows);
+    Ok(page)
+}
+
 #[tauri::command]
 async fn db_update_cmd(
     table: String,
     query: Value,
-    data: Value,
-    state: tauri::State<'_, Arc<Mutex<rusqlite::Connection>>>,
+    mut data: Value,
+    state: tauri::State<'_, DbState>,
+    schema: tauri::State<'_, schema::SchemaState>,
+    secrets: tauri::State<'_, secrets::SecretsState>,
+    app: AppHandle,
 ) -> Result<usize, String> {
-    let conn = state.lock().unwrap();
-    database::db_update(&conn, &table, query, data).map_err(|e| e.to_string())
+    schema.authorize(&table, Operation::Update, Some(&query), Some(&data)).map_err(|e| e.to_string())?;
+    let secrets = secrets.inner().clone();
+    let target = table.clone();
+    let (changes, ids) = state
+        .write(move |conn| {
+            secrets.seal(conn, &target, Some(&query), &mut data)?;
+            let ids = database::matching_ids(conn, &target, &query)?;
+            let changes = database::db_update(conn, &target, query, data)?;
+            // is_secret may have been toggled
+            secrets.reconcile(conn, &target)?;
+            Ok((changes, ids))
+        })
+        .await?;
+    notify_change(&app, DbChange::touched(&table, ChangeOp::Update, ids));
+    Ok(changes)
 }
 
 #[tauri::command]
 async fn db_delete_cmd(
     table: String,
     query: Value,
-    state: tauri::State<'_, Arc<Mutex<rusqlite::Connection>>>,
+    state: tauri::State<'_, DbState>,
+    schema: tauri::State<'_, schema::SchemaState>,
+    app: AppHandle,
 ) -> Result<usize, String> {
-    let conn = state.lock().unwrap();
-    database::db_delete(&conn, &table, query).map_err(|e| e.to_string())
+    schema.authorize(&table, Operation::Delete, Some(&query), None).map_err(|e| e.to_string())?;
+    let target = table.clone();
+    if cascade::CASCADING_TABLES.contains(&table.as_str()) {
+        let plan = state.write(move |conn| cascade::delete_matching(conn, &target, &query)).await?;
+        cascade::notify(&app, &plan);
+        return Ok(plan.affected());
+    }
+    let (changes, ids) = state
+        .write(move |conn| {
+            let ids = database::matching_ids(conn, &target, &query)?;
+            let changes = database::db_delete(conn, &target, query)?;
+            Ok((changes, ids))
+        })
+        .await?;
+    notify_change(&app, DbChange::touched(&table, ChangeOp::Delete, ids));
+    Ok(changes)
 }
 
 #[tauri::command]
@@ -66,27 +139,91 @@ async fn read_import_file(path: String) -> Result<String, String> {
 async fn db_count_cmd(
     table: String,
     query: Value,
-    state: tauri::State<'_, Arc<Mutex<rusqlite::Connection>>>,
+    state: tauri::State<'_, DbState>,
+    schema: tauri::State<'_, schema::SchemaState>,
 ) -> Result<i64, String> {
-    let conn = state.lock().unwrap();
-    database::db_count(&conn, &table, query).map_err(|e| e.to_string())
+    schema.authorize(&table, Operation::Count, Some(&query), None).map_err(|e| e.to_string())?;
+    state.read(move |conn| database::db_count(conn, &table, query)).await
 }
 
+/// Run ordered insert/update/delete operations atomically (see `database::BatchOp`).
+#[tauri::command]
+async fn db_batch_cmd(
+    ops: Vec<database::BatchOp>,
+    state: tauri::State<'_, DbState>,
+    schema: tauri::State<'_, schema::SchemaState>,
+    secrets: tauri::State<'_, secrets::SecretsState>,
+    app: AppHandle,
+) -> Result<Vec<database::BatchResult>, String> {
+    for op in &ops {
+        let checked = match op {
+            database::BatchOp::Insert { data, .. } => schema.authorize(op.table(), Operation::Insert, None, Some(data)),
+            database::BatchOp::Update { query, data, .. } => {
+                schema.authorize(op.table(), Operation::Update, Some(query), Some(data))
+            }
+            database::BatchOp::Delete { query, .. } => schema.authorize(op.table(), Operation::Delete, Some(query), None),
+        };
+        checked.map_err(|e| e.to_string())?;
+    }
+    let secrets = secrets.inner().clone();
+    let (results, changes) = state
+        .write(move |conn| {
+            let tables: Vec<String> = ops.iter().map(|op| op.table().to_string()).collect();
+            let (results, changes) = database::db_batch(conn, ops, &secrets)?;
+            for table in tables {
+                secrets.reconcile(conn, &table)?;
+            }
+            Ok((results, changes))
+        })
+        .await?;
+    for change in changes {
+        notify_change(&app, Some(change));
+    }
+    Ok(results)
+}
+
+// Raw SQL bypasses the schema checks, so it is only available to the e2e suite (debug builds)
 #[tauri::command]
 async fn db_exec_cmd(
     sql: String,
-    state: tauri::State<'_, Arc<Mutex<rusqlite::Connection>>>,
+    state: tauri::State<'_, DbState>,
 ) -> Result<(), String> {
-    let conn = state.lock().unwrap();
-    database::db_exec(&conn, &sql).map_err(|e| e.to_string())
+    if !cfg!(debug_assertions) {
+        return Err("db_exec_cmd is only available in debug builds".to_string());
+    }
+    state.write(move |conn| database::db_exec(conn, &sql)).await
 }
 
+mod analytics;
+mod archive;
+mod backup;
 mod blobs;
+mod cascade;
+mod cloning;
+mod compare;
 mod database;
+mod domain;
+mod governor;
+mod maintenance;
+mod migrations;
 mod paths;
+mod pool;
+mod pricing;
+mod providers;
+mod ratelimits;
+mod retention;
+mod schema;
+mod search;
+mod secrets;
 mod server;
+mod settings;
+mod shadow;
+mod timing;
+mod versions;
+mod workspaces;
 
 use std::sync::{Arc, Mutex}; // Needed for State in commands
+use pool::DbState;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -101,13 +238,30 @@ pub fn run() {
     builder
         .setup(|app| {
             let app_handle = app.handle();
-            let db_conn = database::init_database(&app_handle)
-                .expect("Failed to initialize database");
+            // Migration failures don't stop startup (see migrations.rs); this only
+            // fails when the database file cannot be opened at all
+            let db_conn = database::init_database(&app_handle)?;
+            let schema = schema::Schema::load(&db_conn.writer())
+                .expect("Failed to read database schema");
+            let governor = Arc::new(governor::Governor::load(&db_conn.writer()));
+            let secrets = secrets::Secrets::load(&paths::app_data_root(app_handle)?)?;
+            for table in ["api_keys", "env_variables"] {
+                // Encrypt values stored before encryption at rest
+                if let Err(e) = secrets.reconcile(&db_conn.writer(), table) {
+                    log::error!("Failed to encrypt existing {}: {}", table, e);
+                }
+            }
             app.manage(db_conn);
+            app.manage(governor);
+            app.manage(Arc::new(schema));
+            app.manage(Arc::new(secrets));
             app.manage(Arc::new(Mutex::new(runner::RunnerManager::new())));
+            app.manage(Arc::new(Mutex::new(compare::CompareManager::new())));
+            app.manage(ratelimits::RateLimitState::default());
             if std::env::var("RETICLE_DISABLE_PROXY").is_err() {
                 tauri::async_runtime::spawn(server::start_proxy_server(app_handle.clone()));
             }
+            tauri::async_runtime::spawn(retention::run_background(app_handle.clone()));
 
             // Create main window with drag-drop disabled so HTML5 drop zone works
             let window_config = app.config().app.windows.first().expect("main window config");
@@ -122,6 +276,8 @@ pub fn run() {
         .plugin(tauri_plugin_process::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
+        // Background failures with no caller to report to (stdout and the app log dir)
+        .plugin(tauri_plugin_log::Builder::new().level(log::LevelFilter::Info).build())
         .invoke_handler(tauri::generate_handler![
             greet,
             write_export_file,
@@ -129,18 +285,69 @@ pub fn run() {
             blobs::store_attachment_blob,
             blobs::read_attachment_blob,
             blobs::delete_attachment_blob,
+            backup::backup_database,
+            backup::list_backups,
+            backup::restore_database,
+            migrations::schema_info,
+            migrations::dry_run_migrations,
+            migrations::migrate_database,
+            maintenance::check_integrity,
+            maintenance::check_foreign_keys,
+            maintenance::vacuum_database,
+            maintenance::analyze_database,
+            maintenance::optimize_database,
+            maintenance::checkpoint_wal,
+            maintenance::get_storage_report,
+            maintenance::list_orphans,
+            maintenance::repair_database,
+            cascade::get_delete_policy,
+            cascade::set_delete_policy,
+            cascade::preview_delete,
+            cloning::clone_entity,
+            workspaces::list_workspaces,
+            workspaces::create_workspace,
+            workspaces::rename_workspace,
+            workspaces::duplicate_workspace,
+            workspaces::delete_workspace,
+            workspaces::switch_workspace,
+            archive::export_workspace,
+            archive::import_workspace,
             db_insert_cmd,
             db_select_cmd,
+            db_page_cmd,
             db_update_cmd,
             db_delete_cmd,
             db_count_cmd,
+            db_batch_cmd,
             db_exec_cmd,
+            search::search,
+            analytics::usage_breakdown_cmd,
+            analytics::usage_daily,
+            domain::entity_get,
+            domain::entity_list,
+            domain::entity_create,
+            domain::entity_save,
+            domain::entity_delete,
+            domain::set_linked_tools_cmd,
+            retention::get_retention_policy,
+            retention::set_retention_policy,
+            retention::apply_retention_policy,
+            versions::list_versions_cmd,
+            versions::diff_versions_cmd,
+            versions::create_version_cmd,
+            versions::restore_version_cmd,
+            secrets::resolve_env_variables,
             runner::runner_spawn,
             runner::runner_send,
             runner::runner_kill,
             runner::runner_list,
             runner::write_temp_script,
             runner::delete_temp_script,
+            compare::compare_models,
+            compare::compare_cancel,
+            ratelimits::get_rate_limits,
+            governor::get_rate_limiter_status,
+            governor::set_rate_limiter_rules,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
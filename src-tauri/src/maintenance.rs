@@ -0,0 +1,422 @@
+use anyhow::{anyhow, Result as AnyhowResult};
+use rusqlite::Connection;
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::backup::BackupInfo;
+use crate::database::{now_ms, ChangeOp, DbChange};
+use crate::pool::DbState;
+
+// ── Maintenance ──────────────────────────────────────────────────────────────
+//
+// Integrity checks, compaction and a storage report for the active database, plus
+// orphan detection. executions, eval_runs, eval_test_cases and tool_links point at
+// a scenario or agent through a (type, id) pair that no foreign key enforces, so
+// rows can outlive what they belong to. `repair` puts it all together: check, back
+// up, fix orphans and foreign key violations, then compact.
+
+/// Problems reported by one integrity check at most.
+const MAX_INTEGRITY_ERRORS: usize = 100;
+
+#[derive(Serialize, Clone, Debug)]
+pub struct IntegrityReport {
+    pub ok: bool,
+    /// Empty when `ok`
+    pub problems: Vec<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ForeignKeyViolation {
+    pub table: String,
+    /// None for WITHOUT ROWID tables
+    pub rowid: Option<i64>,
+    /// Table the missing row should be in
+    pub parent: String,
+    /// Index of the foreign key in PRAGMA foreign_key_list(table)
+    pub fk_index: i64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Checkpoint {
+    /// The checkpoint could not complete because of another connection
+    pub busy: bool,
+    pub log_frames: i64,
+    pub checkpointed_frames: i64,
+}
+
+/// Size of the database file(s) before and after an operation.
+#[derive(Serialize, Clone, Debug)]
+pub struct Compaction {
+    pub bytes_before: u64,
+    pub bytes_after: u64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct TableStorage {
+    pub name: String,
+    pub rows: i64,
+    /// Bytes in the table's own pages; None without dbstat
+    pub table_bytes: Option<i64>,
+    /// Bytes in the pages of the table's indexes; None without dbstat
+    pub index_bytes: Option<i64>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct StorageReport {
+    pub page_size: i64,
+    pub page_count: i64,
+    /// Pages that are free until the next VACUUM
+    pub freelist_count: i64,
+    pub file_bytes: u64,
+    pub wal_bytes: u64,
+    /// Whether per-table sizes are available (SQLite built with the dbstat table)
+    pub dbstat: bool,
+    /// Largest first
+    pub tables: Vec<TableStorage>,
+}
+
+/// Rows of `table` whose `column` points at a scenario or agent that does not exist
+/// (or, for `tools`, local tools that nothing links to any more).
+#[derive(Serialize, Clone, Debug)]
+pub struct OrphanGroup {
+    pub table: String,
+    pub column: String,
+    pub ids: Vec<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct RepairReport {
+    pub backup: BackupInfo,
+    /// Orphans that were deleted
+    pub orphans: Vec<OrphanGroup>,
+    pub foreign_keys_fixed: usize,
+    /// Violations with no ON DELETE action to apply; left as they are
+    pub foreign_keys_left: Vec<ForeignKeyViolation>,
+    pub compaction: Compaction,
+}
+
+fn quote(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+fn pragma_i64(conn: &Connection, name: &str) -> AnyhowResult<i64> {
+    Ok(conn.pragma_query_value(None, name, |row| row.get(0))?)
+}
+
+fn wal_bytes(conn: &Connection) -> u64 {
+    conn.path()
+        .filter(|path| !path.is_empty())
+        .and_then(|path| std::fs::metadata(format!("{}-wal", path)).ok())
+        .map(|metadata| metadata.len())
+        .unwrap_or(0)
+}
+
+/// Bytes on disk: the pages of the main file plus the WAL.
+fn size_bytes(conn: &Connection) -> AnyhowResult<u64> {
+    let pages = pragma_i64(conn, "page_count")? * pragma_i64(conn, "page_size")?;
+    Ok(pages.max(0) as u64 + wal_bytes(conn))
+}
+
+/// PRAGMA integrity_check, or the faster quick_check (no index contents) when `full` is false.
+pub fn integrity_check(conn: &Connection, full: bool) -> AnyhowResult<IntegrityReport> {
+    let pragma = if full { "integrity_check" } else { "quick_check" };
+    let mut stmt = conn.prepare(&format!("PRAGMA {}({})", pragma, MAX_INTEGRITY_ERRORS))?;
+    let lines: Vec<String> = stmt.query_map([], |row| row.get(0))?.collect::<Result<_, _>>()?;
+    let ok = lines.len() == 1 && lines[0] == "ok";
+    Ok(IntegrityReport { ok, problems: if ok { Vec::new() } else { lines } })
+}
+
+pub fn foreign_key_check(conn: &Connection) -> AnyhowResult<Vec<ForeignKeyViolation>> {
+    let mut stmt = conn.prepare("PRAGMA foreign_key_check")?;
+    let violations = stmt
+        .query_map([], |row| {
+            Ok(ForeignKeyViolation {
+                table: row.get(0)?,
+                rowid: row.get(1)?,
+                parent: row.get(2)?,
+                fk_index: row.get(3)?,
+            })
+        })?
+        .collect::<Result<_, _>>()?;
+    Ok(violations)
+}
+
+pub fn vacuum(conn: &Connection) -> AnyhowResult<Compaction> {
+    let bytes_before = size_bytes(conn)?;
+    conn.execute_batch("VACUUM")?;
+    checkpoint(conn)?;
+    Ok(Compaction { bytes_before, bytes_after: size_bytes(conn)? })
+}
+
+/// Copy the WAL into the database and truncate it.
+pub fn checkpoint(conn: &Connection) -> AnyhowResult<Checkpoint> {
+    Ok(conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |row| {
+        Ok(Checkpoint {
+            busy: row.get::<_, i64>(0)? != 0,
+            log_frames: row.get(1)?,
+            checkpointed_frames: row.get(2)?,
+        })
+    })?)
+}
+
+/// Row counts and, when the dbstat virtual table is available, bytes per table.
+pub fn storage_report(conn: &Connection) -> AnyhowResult<StorageReport> {
+    let mut stmt = conn.prepare(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND rootpage != 0 ORDER BY name",
+    )?;
+    let names: Vec<String> = stmt.query_map([], |row| row.get(0))?.collect::<Result<_, _>>()?;
+
+    // Pages of each b-tree (tables and indexes), attributed to their table
+    let mut sizes: std::collections::HashMap<String, (i64, i64)> = std::collections::HashMap::new();
+    let dbstat = conn
+        .prepare(
+            "SELECT m.tbl_name, m.type, SUM(s.pgsize)
+             FROM dbstat s JOIN sqlite_master m ON m.name = s.name
+             GROUP BY m.name",
+        )
+        .and_then(|mut stmt| {
+            let rows = stmt.query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, i64>(2)?))
+            })?;
+            for row in rows {
+                let (table, kind, bytes) = row?;
+                let entry = sizes.entry(table).or_default();
+                if kind == "index" {
+                    entry.1 += bytes;
+                } else {
+                    entry.0 += bytes;
+                }
+            }
+            Ok(())
+        })
+        .is_ok();
+
+    let mut tables = Vec::with_capacity(names.len());
+    for name in names {
+        let rows: i64 = conn.query_row(&format!("SELECT count(*) FROM {}", quote(&name)), [], |row| row.get(0))?;
+        let (table_bytes, index_bytes) = match sizes.get(&name) {
+            Some(&(table, index)) => (Some(table), Some(index)),
+            None if dbstat => (Some(0), Some(0)),
+            None => (None, None),
+        };
+        tables.push(TableStorage { name, rows, table_bytes, index_bytes });
+    }
+    tables.sort_by(|a, b| {
+        let size = |t: &TableStorage| t.table_bytes.unwrap_or(0) + t.index_bytes.unwrap_or(0);
+        size(b).cmp(&size(a)).then_with(|| b.rows.cmp(&a.rows))
+    });
+
+    let page_size = pragma_i64(conn, "page_size")?;
+    let page_count = pragma_i64(conn, "page_count")?;
+    Ok(StorageReport {
+        page_size,
+        page_count,
+        freelist_count: pragma_i64(conn, "freelist_count")?,
+        file_bytes: (page_size * page_count).max(0) as u64,
+        wal_bytes: wal_bytes(conn),
+        dbstat,
+        tables,
+    })
+}
+
+/// SQL condition: the (`type_column`, `id_column`) pair names no scenario or agent.
+fn missing_runnable(type_column: &str, id_column: &str) -> String {
+    format!(
+        "(({t} = 'scenario' AND {i} NOT IN (SELECT id FROM scenarios))
+          OR ({t} = 'agent' AND {i} NOT IN (SELECT id FROM agents)))",
+        t = type_column,
+        i = id_column
+    )
+}
+
+/// (table, column, `SELECT id` of its orphans, whether the rows are runs).
+fn orphan_queries() -> Vec<(&'static str, &'static str, String, bool)> {
+    vec![
+        (
+            "tool_links",
+            "toolable_id",
+            format!("SELECT id FROM tool_links WHERE {}", missing_runnable("toolable_type", "toolable_id")),
+            false,
+        ),
+        (
+            "tools",
+            "id",
+            format!(
+                "SELECT id FROM tools WHERE is_global = 0 AND NOT EXISTS (
+                   SELECT 1 FROM tool_links l WHERE l.tool_id = tools.id AND NOT {})",
+                missing_runnable("l.toolable_type", "l.toolable_id")
+            ),
+            false,
+        ),
+        (
+            "eval_test_cases",
+            "runnable_id",
+            format!("SELECT id FROM eval_test_cases WHERE {}", missing_runnable("runnable_type", "runnable_id")),
+            false,
+        ),
+        (
+            "executions",
+            "runnable_id",
+            format!("SELECT id FROM executions WHERE {}", missing_runnable("type", "runnable_id")),
+            true,
+        ),
+        (
+            "eval_runs",
+            "runnable_id",
+            format!("SELECT id FROM eval_runs WHERE {}", missing_runnable("runnable_type", "runnable_id")),
+            true,
+        ),
+    ]
+}
+
+/// Rows left pointing at deleted scenarios and agents, per table (tables without
+/// orphans are left out). Runs are kept when their scenario or agent is deleted, so
+/// orphaned executions and eval_runs are expected; the rest is not.
+pub fn find_orphans(conn: &Connection) -> AnyhowResult<Vec<OrphanGroup>> {
+    let mut groups = Vec::new();
+    for (table, column, sql, _) in orphan_queries() {
+        let mut stmt = conn.prepare(&sql)?;
+        let ids: Vec<String> = stmt.query_map([], |row| row.get(0))?.collect::<Result<_, _>>()?;
+        if !ids.is_empty() {
+            groups.push(OrphanGroup { table: table.to_string(), column: column.to_string(), ids });
+        }
+    }
+    Ok(groups)
+}
+
+/// Delete orphans (runs only when `include_runs`) in the order links, tools, test
+/// cases, runs. eval_results go with their eval_runs (ON DELETE CASCADE).
+fn delete_orphans(conn: &Connection, include_runs: bool) -> AnyhowResult<Vec<OrphanGroup>> {
+    let mut deleted = Vec::new();
+    for (table, column, sql, is_run) in orphan_queries() {
+        if is_run && !include_runs {
+            continue;
+        }
+        let mut stmt = conn.prepare(&sql)?;
+        let ids: Vec<String> = stmt.query_map([], |row| row.get(0))?.collect::<Result<_, _>>()?;
+        if ids.is_empty() {
+            continue;
+        }
+        conn.execute(&format!("DELETE FROM {} WHERE id IN ({})", table, sql), [])?;
+        deleted.push(OrphanGroup { table: table.to_string(), column: column.to_string(), ids });
+    }
+    Ok(deleted)
+}
+
+/// Apply the ON DELETE action of each violated foreign key as if the parent row had
+/// just been deleted (CASCADE deletes the row, SET NULL clears the column). Returns
+/// how many rows were fixed and the violations that have no such action.
+fn fix_foreign_keys(conn: &Connection) -> AnyhowResult<(usize, Vec<ForeignKeyViolation>)> {
+    let mut fixed = 0;
+    let mut left = Vec::new();
+    for violation in foreign_key_check(conn)? {
+        let Some(rowid) = violation.rowid else {
+            left.push(violation);
+            continue;
+        };
+        let mut stmt = conn.prepare(r#"SELECT "from", on_delete FROM pragma_foreign_key_list(?1) WHERE id = ?2"#)?;
+        let columns: Vec<(String, String)> = stmt
+            .query_map(rusqlite::params![violation.table, violation.fk_index], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<_, _>>()?;
+        let table = quote(&violation.table);
+        match columns.first().map(|(_, action)| action.as_str()) {
+            Some("CASCADE") => {
+                fixed += conn.execute(&format!("DELETE FROM {} WHERE rowid = ?1", table), [rowid])?;
+            }
+            Some("SET NULL") => {
+                let set = columns.iter().map(|(column, _)| format!("{} = NULL", quote(column))).collect::<Vec<_>>();
+                fixed += conn.execute(&format!("UPDATE {} SET {} WHERE rowid = ?1", table, set.join(", ")), [rowid])?;
+            }
+            _ => left.push(violation),
+        }
+    }
+    Ok((fixed, left))
+}
+
+/// Check the database, back it up to `backup_dir`, delete orphans (orphaned runs
+/// only when `include_runs`), fix foreign key violations, then VACUUM, optimize and
+/// truncate the WAL. A database that fails the integrity check is left untouched.
+pub fn repair(conn: &mut Connection, backup_dir: &std::path::Path, include_runs: bool) -> AnyhowResult<RepairReport> {
+    let integrity = integrity_check(conn, true)?;
+    if !integrity.ok {
+        return Err(anyhow!(
+            "The database failed the integrity check, nothing was changed (restore a backup instead): {}",
+            integrity.problems.join("; ")
+        ));
+    }
+    let backup = crate::backup::backup_to(conn, &backup_dir.join(format!("reticle-{}-pre-repair.db", now_ms())))?;
+
+    let tx = conn.transaction()?;
+    let orphans = delete_orphans(&tx, include_runs)?;
+    let (foreign_keys_fixed, foreign_keys_left) = fix_foreign_keys(&tx)?;
+    tx.commit()?;
+
+    let compaction = vacuum(conn)?;
+    conn.execute_batch("PRAGMA optimize")?;
+    Ok(RepairReport { backup, orphans, foreign_keys_fixed, foreign_keys_left, compaction })
+}
+
+// ── Commands ─────────────────────────────────────────────────────────────────
+
+/// `full` – false runs the faster quick_check, which skips index contents (default: true)
+#[tauri::command]
+pub async fn check_integrity(full: Option<bool>, state: tauri::State<'_, DbState>) -> Result<IntegrityReport, String> {
+    state.read(move |conn| integrity_check(conn, full.unwrap_or(true))).await
+}
+
+#[tauri::command]
+pub async fn check_foreign_keys(state: tauri::State<'_, DbState>) -> Result<Vec<ForeignKeyViolation>, String> {
+    state.read(foreign_key_check).await
+}
+
+/// Rebuild the database file to give free pages back to the disk.
+#[tauri::command]
+pub async fn vacuum_database(state: tauri::State<'_, DbState>) -> Result<Compaction, String> {
+    state.write(|conn| vacuum(conn)).await
+}
+
+/// Gather query planner statistics for every table and index.
+#[tauri::command]
+pub async fn analyze_database(state: tauri::State<'_, DbState>) -> Result<(), String> {
+    state.write(|conn| Ok(conn.execute_batch("ANALYZE")?)).await
+}
+
+/// PRAGMA optimize: refresh the planner statistics that are out of date.
+#[tauri::command]
+pub async fn optimize_database(state: tauri::State<'_, DbState>) -> Result<(), String> {
+    state.write(|conn| Ok(conn.execute_batch("PRAGMA optimize")?)).await
+}
+
+#[tauri::command]
+pub async fn checkpoint_wal(state: tauri::State<'_, DbState>) -> Result<Checkpoint, String> {
+    state.write(|conn| checkpoint(conn)).await
+}
+
+#[tauri::command]
+pub async fn get_storage_report(state: tauri::State<'_, DbState>) -> Result<StorageReport, String> {
+    state.read(storage_report).await
+}
+
+#[tauri::command]
+pub async fn list_orphans(state: tauri::State<'_, DbState>) -> Result<Vec<OrphanGroup>, String> {
+    state.read(find_orphans).await
+}
+
+/// Repair and compact the database (see `repair`).
+///
+/// `include_runs` – also delete executions and eval runs of deleted scenarios and agents (default: false)
+#[tauri::command]
+pub async fn repair_database(
+    include_runs: Option<bool>,
+    state: tauri::State<'_, DbState>,
+    app: AppHandle,
+) -> Result<RepairReport, String> {
+    let backups = crate::backup::backup_dir(&crate::workspaces::active_data_dir(&app)?);
+    let report = state
+        .write(move |conn| repair(conn, &backups, include_runs.unwrap_or(false)))
+        .await?;
+    for group in &report.orphans {
+        crate::notify_change(&app, DbChange::touched(&group.table, ChangeOp::Delete, group.ids.clone()));
+    }
+    Ok(report)
+}
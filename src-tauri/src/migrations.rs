@@ -0,0 +1,256 @@
+use std::sync::Mutex;
+
+use anyhow::{anyhow, Result as AnyhowResult};
+use rusqlite::Connection;
+use serde::Serialize;
+use tauri::AppHandle;
+use ulid::Ulid;
+
+use crate::database::{migration_count, now_ms, MIGRATIONS};
+use crate::pool::{DbPool, DbState};
+
+// ── Schema migrations ────────────────────────────────────────────────────────
+//
+// Every migration has a down script, so the schema can be moved to any version from
+// 0 to the latest. Runs are recorded in `migration_history`, which lives outside
+// the migrations so that going down does not erase it.
+//
+// Pending migrations are first applied to a copy of the database (`dry_run`). If
+// they fail there, the real file is left as it is: at startup the app opens it
+// unmigrated and `schema_info` reports the error, instead of the app not starting.
+
+const HISTORY_DDL: &str = "
+    CREATE TABLE IF NOT EXISTS migration_history (
+      id          INTEGER PRIMARY KEY AUTOINCREMENT,
+      version     INTEGER NOT NULL,       -- migration number
+      name        TEXT NOT NULL,
+      direction   TEXT NOT NULL,          -- 'up' | 'down'
+      applied_at  INTEGER NOT NULL        -- unix ms
+    );
+";
+
+/// Why the database could not be migrated at startup, until a later migration succeeds.
+static STARTUP_ERROR: Mutex<Option<String>> = Mutex::new(None);
+
+pub fn set_startup_error(error: Option<String>) {
+    *STARTUP_ERROR.lock().unwrap() = error;
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct MigrationStep {
+    pub version: usize,
+    pub name: String,
+    /// "up" | "down"
+    pub direction: String,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct HistoryEntry {
+    #[serde(flatten)]
+    pub step: MigrationStep,
+    /// unix ms
+    pub applied_at: i64,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct SchemaInfo {
+    pub user_version: usize,
+    pub latest_version: usize,
+    pub pending: Vec<MigrationStep>,
+    /// Most recent first
+    pub history: Vec<HistoryEntry>,
+    pub startup_error: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct DryRun {
+    pub from_version: usize,
+    pub to_version: usize,
+    pub steps: Vec<MigrationStep>,
+    /// None when every step applied cleanly
+    pub error: Option<String>,
+}
+
+pub fn user_version(conn: &Connection) -> AnyhowResult<usize> {
+    let version: i64 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    Ok(version.max(0) as usize)
+}
+
+fn step(version: usize, direction: &str) -> MigrationStep {
+    MigrationStep {
+        version,
+        name: MIGRATIONS.get(version - 1).map(|(name, _, _)| name.to_string()).unwrap_or_default(),
+        direction: direction.to_string(),
+    }
+}
+
+/// Migrations run to go from version `from` to `to`, in order.
+fn steps(from: usize, to: usize) -> Vec<MigrationStep> {
+    if to >= from {
+        (from + 1..=to).map(|version| step(version, "up")).collect()
+    } else {
+        (to + 1..=from).rev().map(|version| step(version, "down")).collect()
+    }
+}
+
+/// Move `conn` to schema version `target`, up or down, in one transaction that also
+/// records the steps in `migration_history`.
+pub fn migrate_to(conn: &mut Connection, target: usize) -> AnyhowResult<Vec<MigrationStep>> {
+    let latest = migration_count();
+    if target > latest {
+        return Err(anyhow!("Unknown schema version {} (latest is {})", target, latest));
+    }
+    let from = user_version(conn)?;
+    if from > latest {
+        return Err(anyhow!(
+            "Database schema version {} is newer than this version of the app supports ({})",
+            from,
+            latest
+        ));
+    }
+
+    let steps = steps(from, target);
+    if steps.is_empty() {
+        return Ok(steps);
+    }
+
+    let tx = conn.transaction()?;
+    tx.execute_batch(HISTORY_DDL)?;
+    let now = now_ms();
+    for step in &steps {
+        let (_, up, down) = MIGRATIONS[step.version - 1];
+        let (script, version) = if step.direction == "up" { (up, step.version) } else { (down, step.version - 1) };
+        tx.execute_batch(script)
+            .map_err(|e| anyhow!("Migration {} {} ({}) failed: {}", step.version, step.direction, step.name, e))?;
+        tx.pragma_update(None, "user_version", version as i64)?;
+        tx.execute(
+            "INSERT INTO migration_history (version, name, direction, applied_at) VALUES (?1, ?2, ?3, ?4)",
+            rusqlite::params![step.version as i64, step.name, step.direction, now],
+        )?;
+    }
+    let violations: i64 = tx.query_row("SELECT COUNT(*) FROM pragma_foreign_key_check", [], |row| row.get(0))?;
+    if violations > 0 {
+        return Err(anyhow!("Migrating to version {} leaves {} foreign key violations", target, violations));
+    }
+    tx.commit()?;
+    Ok(steps)
+}
+
+/// Apply the migrations to `target` on a copy of `conn`'s database and report
+/// whether they succeed. The database itself is not changed.
+pub fn dry_run(conn: &Connection, target: usize) -> AnyhowResult<DryRun> {
+    let from = user_version(conn)?;
+    let copy = std::env::temp_dir().join(format!("reticle-dry-run-{}.db", Ulid::new()));
+    crate::backup::backup_to(conn, &copy)?;
+
+    let result = DbPool::open_writer(&copy).and_then(|mut copy_conn| migrate_to(&mut copy_conn, target));
+    for suffix in ["", "-wal", "-shm"] {
+        std::fs::remove_file(format!("{}{}", copy.display(), suffix)).ok();
+    }
+
+    Ok(DryRun {
+        from_version: from,
+        to_version: target,
+        steps: steps(from.min(migration_count()), target),
+        error: result.err().map(|e| e.to_string()),
+    })
+}
+
+fn history(conn: &Connection) -> AnyhowResult<Vec<HistoryEntry>> {
+    let exists: bool = conn.query_row(
+        "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'migration_history')",
+        [],
+        |row| row.get(0),
+    )?;
+    if !exists {
+        return Ok(Vec::new());
+    }
+    let mut stmt = conn.prepare(
+        "SELECT version, name, direction, applied_at FROM migration_history ORDER BY id DESC",
+    )?;
+    let entries = stmt
+        .query_map([], |row| {
+            Ok(HistoryEntry {
+                step: MigrationStep {
+                    version: row.get::<_, i64>(0)? as usize,
+                    name: row.get(1)?,
+                    direction: row.get(2)?,
+                },
+                applied_at: row.get(3)?,
+            })
+        })?
+        .collect::<Result<_, _>>()?;
+    Ok(entries)
+}
+
+pub fn info(conn: &Connection) -> AnyhowResult<SchemaInfo> {
+    let version = user_version(conn)?;
+    let latest = migration_count();
+    Ok(SchemaInfo {
+        user_version: version,
+        latest_version: latest,
+        pending: if version < latest { steps(version, latest) } else { Vec::new() },
+        history: history(conn)?,
+        startup_error: STARTUP_ERROR.lock().unwrap().clone(),
+    })
+}
+
+// ── Commands ─────────────────────────────────────────────────────────────────
+
+/// Current and latest schema version, pending migrations, migration history and
+/// the startup migration error, if any.
+#[tauri::command]
+pub async fn schema_info(state: tauri::State<'_, DbState>) -> Result<SchemaInfo, String> {
+    state.read(info).await
+}
+
+/// Try migrating a copy of the database to `target_version` (default: latest).
+#[tauri::command]
+pub async fn dry_run_migrations(
+    target_version: Option<usize>,
+    state: tauri::State<'_, DbState>,
+) -> Result<DryRun, String> {
+    let target = target_version.unwrap_or_else(migration_count);
+    state.read(move |conn| dry_run(conn, target)).await
+}
+
+/// Migrate the database up or down to `target_version` (default: latest). The steps
+/// are tried on a copy first, and the database is backed up before it is changed.
+///
+/// `allow_destructive` – must be true to migrate down, which drops tables and columns
+#[tauri::command]
+pub async fn migrate_database(
+    target_version: Option<usize>,
+    allow_destructive: Option<bool>,
+    state: tauri::State<'_, DbState>,
+    schema: tauri::State<'_, crate::schema::SchemaState>,
+    app: AppHandle,
+) -> Result<SchemaInfo, String> {
+    let target = target_version.unwrap_or_else(migration_count);
+    let data_dir = crate::workspaces::active_data_dir(&app)?;
+    let schema = schema.inner().clone();
+    state
+        .write(move |conn| {
+            let report = dry_run(conn, target)?;
+            if report.to_version < report.from_version && !allow_destructive.unwrap_or(false) {
+                return Err(anyhow!(
+                    "Migrating down from version {} to {} deletes data; pass allow_destructive to confirm",
+                    report.from_version,
+                    report.to_version
+                ));
+            }
+            if let Some(error) = report.error {
+                return Err(anyhow!("Migrations failed on a copy of the database, nothing was changed: {}", error));
+            }
+            if !report.steps.is_empty() {
+                let backup = crate::backup::backup_dir(&data_dir)
+                    .join(format!("reticle-{}-pre-migrate-v{}.db", now_ms(), report.from_version));
+                crate::backup::backup_to(conn, &backup)?;
+            }
+            migrate_to(conn, target)?;
+            set_startup_error(None);
+            schema.reload(conn)?;
+            info(conn)
+        })
+        .await
+}
@@ -0,0 +1,128 @@
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use anyhow::Result as AnyhowResult;
+use rusqlite::Connection;
+
+// ── Connection pool ──────────────────────────────────────────────────────────
+//
+// One writer and a few read-only connections on the same WAL database: readers see
+// the last committed state and never wait for the writer, so history and list
+// queries keep working while a long eval run is inserting results.
+//
+// A panic while a connection is locked poisons its mutex. The connection itself is
+// still usable (an open transaction is rolled back when it is recovered), so
+// poisoning is cleared instead of failing every later command.
+
+const READERS: usize = 4;
+
+const PRAGMAS: &str = "
+    PRAGMA synchronous = NORMAL;
+    PRAGMA temp_store = MEMORY;
+    PRAGMA foreign_keys = ON;
+    PRAGMA busy_timeout = 5000;
+";
+
+pub struct DbPool {
+    writer: Mutex<Connection>,
+    readers: Vec<Mutex<Connection>>,
+    next_reader: AtomicUsize,
+}
+
+pub type DbState = Arc<DbPool>;
+
+/// Take a lock, recovering it if a previous holder panicked.
+fn recover<'a>(mutex: &'a Mutex<Connection>) -> MutexGuard<'a, Connection> {
+    mutex.lock().unwrap_or_else(|poisoned| {
+        mutex.clear_poison();
+        let conn = poisoned.into_inner();
+        if !conn.is_autocommit() {
+            conn.execute_batch("ROLLBACK").ok();
+        }
+        conn
+    })
+}
+
+impl DbPool {
+    /// Open the connection that writes (WAL mode, foreign keys on).
+    pub fn open_writer(path: &Path) -> AnyhowResult<Connection> {
+        let conn = Connection::open(path)?;
+        conn.execute_batch("PRAGMA journal_mode = WAL;")?;
+        conn.execute_batch(PRAGMAS)?;
+        Ok(conn)
+    }
+
+    fn open_readers(path: &Path) -> AnyhowResult<Vec<Connection>> {
+        let mut readers = Vec::with_capacity(READERS);
+        for _ in 0..READERS {
+            let reader = Connection::open(path)?;
+            reader.execute_batch(PRAGMAS)?;
+            reader.execute_batch("PRAGMA query_only = ON;")?;
+            readers.push(reader);
+        }
+        Ok(readers)
+    }
+
+    /// Pool around a migrated `writer`, with readers on the database at `path`.
+    pub fn new(writer: Connection, path: &Path) -> AnyhowResult<Self> {
+        let readers = Self::open_readers(path)?.into_iter().map(Mutex::new).collect();
+        Ok(Self { writer: Mutex::new(writer), readers, next_reader: AtomicUsize::new(0) })
+    }
+
+    /// Point the pool at another database (a workspace switch). Waits for the current
+    /// write to finish and holds the writer until every reader has been replaced, so
+    /// no write lands in either database mid-swap. The old connections are closed.
+    pub fn swap(&self, writer: Connection, path: &Path) -> AnyhowResult<()> {
+        let readers = Self::open_readers(path)?;
+        let mut current_writer = recover(&self.writer);
+        for (slot, reader) in self.readers.iter().zip(readers) {
+            *recover(slot) = reader;
+        }
+        *current_writer = writer;
+        Ok(())
+    }
+
+    /// The write connection. Writes are serialized on it.
+    pub fn writer(&self) -> MutexGuard<'_, Connection> {
+        recover(&self.writer)
+    }
+
+    /// A read-only connection: the first idle one, otherwise the next in turn.
+    pub fn reader(&self) -> MutexGuard<'_, Connection> {
+        let start = self.next_reader.fetch_add(1, Ordering::Relaxed);
+        for offset in 0..self.readers.len() {
+            let reader = &self.readers[(start + offset) % self.readers.len()];
+            if let Ok(conn) = reader.try_lock() {
+                return conn;
+            }
+        }
+        recover(&self.readers[start % self.readers.len()])
+    }
+
+    /// Run `f` on a reader on a blocking thread.
+    pub async fn read<T, F>(self: &Arc<Self>, f: F) -> Result<T, String>
+    where
+        F: FnOnce(&Connection) -> AnyhowResult<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let pool = self.clone();
+        tauri::async_runtime::spawn_blocking(move || f(&pool.reader()))
+            .await
+            .map_err(|e| e.to_string())?
+            .map_err(|e| e.to_string())
+    }
+
+    /// Run `f` on the writer on a blocking thread.
+    pub async fn write<T, F>(self: &Arc<Self>, f: F) -> Result<T, String>
+    where
+        F: FnOnce(&mut Connection) -> AnyhowResult<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let pool = self.clone();
+        tauri::async_runtime::spawn_blocking(move || f(&mut pool.writer()))
+            .await
+            .map_err(|e| e.to_string())?
+            .map_err(|e| e.to_string())
+    }
+}
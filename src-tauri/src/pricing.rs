@@ -0,0 +1,107 @@
+// Model pricing in cents per million tokens.
+// Mirrors src/lib/modelPricing.ts — keep both tables in sync when prices change.
+
+#[derive(Clone, Copy)]
+pub struct ModelPricing {
+    pub input: f64,
+    pub output: f64,
+    pub cached: Option<f64>,
+}
+
+const OPENAI_PRICING: &[(&str, ModelPricing)] = &[
+    ("gpt-5.4-pro", ModelPricing { input: 3000.0, output: 18000.0, cached: None }),
+    ("gpt-5.4", ModelPricing { input: 250.0, output: 1500.0, cached: Some(25.0) }),
+    ("gpt-5.3", ModelPricing { input: 175.0, output: 1400.0, cached: Some(18.0) }),
+    ("gpt-5.2-pro", ModelPricing { input: 2100.0, output: 16800.0, cached: None }),
+    ("gpt-5.2", ModelPricing { input: 175.0, output: 1400.0, cached: Some(60.0) }),
+    ("gpt-5.1", ModelPricing { input: 175.0, output: 1400.0, cached: Some(60.0) }),
+    ("gpt-5-pro", ModelPricing { input: 1500.0, output: 12000.0, cached: None }),
+    ("gpt-5", ModelPricing { input: 125.0, output: 1000.0, cached: Some(13.0) }),
+    ("gpt-5-mini", ModelPricing { input: 25.0, output: 200.0, cached: Some(3.0) }),
+    ("gpt-5-nano", ModelPricing { input: 5.0, output: 40.0, cached: Some(1.0) }),
+    ("gpt-4o", ModelPricing { input: 250.0, output: 1000.0, cached: Some(125.0) }),
+    ("gpt-4o-mini", ModelPricing { input: 15.0, output: 60.0, cached: Some(8.0) }),
+    ("gpt-4.1", ModelPricing { input: 200.0, output: 800.0, cached: Some(50.0) }),
+    ("gpt-4.1-mini", ModelPricing { input: 40.0, output: 160.0, cached: Some(10.0) }),
+    ("gpt-4.1-nano", ModelPricing { input: 10.0, output: 40.0, cached: Some(3.0) }),
+    ("o4-mini", ModelPricing { input: 10.0, output: 40.0, cached: Some(3.0) }),
+    ("o3", ModelPricing { input: 200.0, output: 800.0, cached: Some(50.0) }),
+    ("o3-mini", ModelPricing { input: 110.0, output: 440.0, cached: Some(55.0) }),
+    ("o3-pro", ModelPricing { input: 200.0, output: 800.0, cached: None }),
+    ("o1", ModelPricing { input: 1500.0, output: 6000.0, cached: Some(750.0) }),
+    ("o1-pro", ModelPricing { input: 15000.0, output: 60000.0, cached: None }),
+];
+
+const ANTHROPIC_PRICING: &[(&str, ModelPricing)] = &[
+    ("claude-opus-4-6", ModelPricing { input: 500.0, output: 2500.0, cached: None }),
+    ("claude-sonnet-4-6", ModelPricing { input: 300.0, output: 1500.0, cached: None }),
+    ("claude-opus-4-5", ModelPricing { input: 500.0, output: 2500.0, cached: None }),
+    ("claude-haiku-4-5", ModelPricing { input: 100.0, output: 500.0, cached: None }),
+    ("claude-sonnet-4-5", ModelPricing { input: 300.0, output: 4500.0, cached: None }),
+    ("claude-opus-4-1", ModelPricing { input: 1500.0, output: 7500.0, cached: None }),
+    ("claude-opus-4", ModelPricing { input: 1500.0, output: 7500.0, cached: None }),
+    ("claude-sonnet-4", ModelPricing { input: 300.0, output: 1500.0, cached: None }),
+    ("claude-3-7-sonnet", ModelPricing { input: 300.0, output: 1500.0, cached: None }),
+    ("claude-3-5-haiku", ModelPricing { input: 80.0, output: 400.0, cached: None }),
+    ("claude-3-haiku", ModelPricing { input: 25.0, output: 125.0, cached: None }),
+];
+
+const GOOGLE_PRICING: &[(&str, ModelPricing)] = &[
+    ("gemini-3-pro-preview", ModelPricing { input: 400.0, output: 1800.0, cached: None }),
+    ("gemini-3-flash-preview", ModelPricing { input: 50.0, output: 300.0, cached: None }),
+    ("gemini-2.5-pro", ModelPricing { input: 250.0, output: 1500.0, cached: None }),
+    ("gemini-2.5-flash", ModelPricing { input: 30.0, output: 250.0, cached: None }),
+    ("gemini-2.5-flash-lite", ModelPricing { input: 10.0, output: 40.0, cached: None }),
+    ("gemini-2.0-flash", ModelPricing { input: 10.0, output: 40.0, cached: None }),
+    ("gemini-2.0-flash-lite", ModelPricing { input: 7.5, output: 30.0, cached: None }),
+];
+
+/// Strips -YYYY-MM-DD and -latest suffixes for pricing lookup
+fn base_model_id(model_id: &str) -> &str {
+    let bytes = model_id.as_bytes();
+    let has_date_suffix = bytes.len() > 11
+        && bytes[bytes.len() - 11] == b'-'
+        && bytes[bytes.len() - 10..]
+            .iter()
+            .enumerate()
+            .all(|(i, b)| if i == 4 || i == 7 { *b == b'-' } else { b.is_ascii_digit() });
+    let trimmed = if has_date_suffix { &model_id[..model_id.len() - 11] } else { model_id };
+    trimmed.strip_suffix("-latest").unwrap_or(trimmed)
+}
+
+pub fn find_pricing(provider: &str, model_id: &str) -> Option<ModelPricing> {
+    let table = match provider {
+        "openai" => OPENAI_PRICING,
+        "anthropic" => ANTHROPIC_PRICING,
+        "google" => GOOGLE_PRICING,
+        _ => return None,
+    };
+    let base = base_model_id(model_id);
+    table
+        .iter()
+        .find(|(id, _)| *id == model_id)
+        .or_else(|| table.iter().find(|(id, _)| *id == base))
+        .map(|(_, pricing)| *pricing)
+}
+
+/// Estimated request cost in USD, or None if the model's pricing is unknown.
+pub fn calculate_request_cost(
+    provider: &str,
+    model_id: &str,
+    input_tokens: u64,
+    output_tokens: u64,
+    cached_tokens: u64,
+) -> Option<f64> {
+    let pricing = find_pricing(provider, model_id)?;
+
+    let mut cost_cents = 0.0;
+    cost_cents += (input_tokens as f64 / 1_000_000.0) * pricing.input;
+    cost_cents += (output_tokens as f64 / 1_000_000.0) * pricing.output;
+    if cached_tokens > 0 {
+        if let Some(cached) = pricing.cached {
+            cost_cents += (cached_tokens as f64 / 1_000_000.0) * cached;
+        }
+    }
+
+    Some(cost_cents / 100.0)
+}
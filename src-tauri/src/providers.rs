@@ -0,0 +1,77 @@
+use serde_json::{Map, Value};
+
+/// Upstream provider as seen by the proxy.
+/// Mirrors `src/constants/providers.ts` and the gateway base paths in `src/lib/gateway/constants.ts`.
+pub struct ProviderConfig {
+    pub id: &'static str,
+    pub base_url: &'static str,
+    /// Header the API key is injected into ("Authorization" gets a Bearer prefix).
+    pub auth_header: &'static str,
+    /// Path prefix of the provider's OpenAI-compatible API, e.g. "/v1".
+    pub api_path: &'static str,
+    /// Extra headers the provider requires on every request.
+    pub extra_headers: &'static [(&'static str, &'static str)],
+}
+
+pub const PROVIDERS: &[ProviderConfig] = &[
+    ProviderConfig {
+        id: "openai",
+        base_url: "https://api.openai.com",
+        auth_header: "Authorization",
+        api_path: "/v1",
+        extra_headers: &[],
+    },
+    ProviderConfig {
+        id: "anthropic",
+        base_url: "https://api.anthropic.com",
+        auth_header: "X-Api-Key",
+        api_path: "/v1",
+        extra_headers: &[("anthropic-version", "2023-06-01")],
+    },
+    ProviderConfig {
+        id: "google",
+        base_url: "https://generativelanguage.googleapis.com",
+        auth_header: "Authorization",
+        api_path: "/v1beta/openai",
+        extra_headers: &[],
+    },
+];
+
+pub fn find_provider(id: &str) -> Option<&'static ProviderConfig> {
+    PROVIDERS.iter().find(|p| p.id == id)
+}
+
+/// The provider `id` if `target_base` is its base URL. The proxy only injects API keys and
+/// fills secret placeholders for requests bound to a known provider.
+pub fn trusted_target(id: &str, target_base: &str) -> Option<&'static ProviderConfig> {
+    find_provider(id).filter(|p| p.base_url == target_base.trim_end_matches('/'))
+}
+
+/// OpenAI reasoning models require max_completion_tokens instead of max_tokens.
+const REASONING_MODEL_PREFIXES: &[&str] = &["o1", "o3", "o4-mini", "codex-mini", "computer-use-preview", "gpt-5"];
+
+pub fn is_reasoning_model(model: &str) -> bool {
+    if model.starts_with("gpt-5-chat") {
+        return false;
+    }
+    REASONING_MODEL_PREFIXES.iter().any(|p| model.starts_with(p))
+}
+
+/// Point a chat request body at `model` on `provider`, applying the same params rewrite
+/// as the gateway's `transformRequestBody` (max_tokens <-> max_completion_tokens).
+pub fn rewrite_request_body(body: &mut Map<String, Value>, provider: &str, model: &str) {
+    body.insert("model".to_string(), Value::String(model.to_string()));
+
+    if provider == "openai" && is_reasoning_model(model) {
+        if let Some(max_tokens) = body.remove("max_tokens") {
+            if !max_tokens.is_null() {
+                body.insert("max_completion_tokens".to_string(), max_tokens);
+            }
+        }
+    } else if let Some(max_tokens) = body.remove("max_completion_tokens") {
+        // The primary was a reasoning model; other targets only understand max_tokens.
+        if !max_tokens.is_null() && !body.contains_key("max_tokens") {
+            body.insert("max_tokens".to_string(), max_tokens);
+        }
+    }
+}
@@ -0,0 +1,256 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use http::HeaderMap;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::database::now_ms;
+
+// ── State ────────────────────────────────────────────────────────────────────
+
+/// Latest provider-reported quota per (provider, key, model).
+#[derive(Default)]
+pub struct RateLimitTracker {
+    snapshots: HashMap<(String, String, String), RateLimitSnapshot>,
+}
+
+pub type RateLimitState = Arc<Mutex<RateLimitTracker>>;
+
+// ── Event payloads emitted to the frontend ───────────────────────────────────
+
+/// One quota dimension (requests, tokens…). `reset_at` is unix ms.
+#[derive(Serialize, Clone, Debug, Default, PartialEq)]
+pub struct RateLimitWindow {
+    pub limit: Option<u64>,
+    pub remaining: Option<u64>,
+    pub reset_at: Option<i64>,
+}
+
+impl RateLimitWindow {
+    fn is_empty(&self) -> bool {
+        self.limit.is_none() && self.remaining.is_none() && self.reset_at.is_none()
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct RateLimitSnapshot {
+    pub provider: String,
+    /// Short SHA-256 fingerprint of the API key, never the key itself.
+    pub key_id: String,
+    /// Model of the request that reported the limits ("" when unknown).
+    pub model: String,
+    #[serde(flatten)]
+    pub limits: RateLimits,
+    pub observed_at: i64,
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct RateLimits {
+    pub requests: Option<RateLimitWindow>,
+    pub tokens: Option<RateLimitWindow>,
+    pub input_tokens: Option<RateLimitWindow>,
+    pub output_tokens: Option<RateLimitWindow>,
+    /// Set when the provider asked us to back off (`retry-after`), unix ms.
+    pub retry_at: Option<i64>,
+}
+
+// ── Header parsing ───────────────────────────────────────────────────────────
+
+pub fn key_fingerprint(api_key: Option<&str>) -> String {
+    match api_key {
+        Some(key) => {
+            let hash = Sha256::digest(key.as_bytes());
+            format!("{:x}", hash)[..12].to_string()
+        }
+        None => "unknown".to_string(),
+    }
+}
+
+fn header<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|v| v.to_str().ok()).map(str::trim)
+}
+
+fn header_u64(headers: &HeaderMap, name: &str) -> Option<u64> {
+    header(headers, name).and_then(|v| v.parse::<f64>().ok()).map(|v| v.max(0.0) as u64)
+}
+
+/// OpenAI-style durations: "20ms", "1s", "6m0s", "1h2m3.5s".
+fn parse_duration_ms(value: &str) -> Option<i64> {
+    let mut total = 0.0;
+    let mut number = String::new();
+    let mut chars = value.chars().peekable();
+    let mut parsed_any = false;
+
+    while let Some(c) = chars.next() {
+        if c.is_ascii_digit() || c == '.' {
+            number.push(c);
+            continue;
+        }
+        let amount: f64 = number.parse().ok()?;
+        number.clear();
+        let unit_ms = match c {
+            'h' => 3_600_000.0,
+            'm' if chars.peek() == Some(&'s') => {
+                chars.next();
+                1.0
+            }
+            'm' => 60_000.0,
+            's' => 1_000.0,
+            _ => return None,
+        };
+        total += amount * unit_ms;
+        parsed_any = true;
+    }
+
+    if !number.is_empty() {
+        // Bare number: seconds
+        total += number.parse::<f64>().ok()? * 1_000.0;
+        parsed_any = true;
+    }
+    parsed_any.then_some(total as i64)
+}
+
+/// RFC 3339 timestamp ("2025-01-01T12:00:00Z", with optional fraction/offset) to unix ms.
+fn parse_rfc3339_ms(value: &str) -> Option<i64> {
+    let (date, time) = value.split_once(['T', 't', ' '])?;
+    let mut date_parts = date.splitn(3, '-');
+    let year: i64 = date_parts.next()?.parse().ok()?;
+    let month: i64 = date_parts.next()?.parse().ok()?;
+    let day: i64 = date_parts.next()?.parse().ok()?;
+
+    let (clock, offset_ms) = if let Some(clock) = time.strip_suffix(['Z', 'z']) {
+        (clock, 0)
+    } else {
+        let split_at = time.rfind(['+', '-'])?;
+        let (clock, offset) = time.split_at(split_at);
+        let sign = if offset.starts_with('-') { -1 } else { 1 };
+        let (h, m) = offset[1..].split_once(':')?;
+        (clock, sign * (h.parse::<i64>().ok()? * 3_600_000 + m.parse::<i64>().ok()? * 60_000))
+    };
+
+    let mut clock_parts = clock.splitn(3, ':');
+    let hour: i64 = clock_parts.next()?.parse().ok()?;
+    let minute: i64 = clock_parts.next()?.parse().ok()?;
+    let seconds: f64 = clock_parts.next()?.parse().ok()?;
+
+    // Days from civil (Howard Hinnant's algorithm)
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+
+    Some(days * 86_400_000 + hour * 3_600_000 + minute * 60_000 + (seconds * 1000.0) as i64 - offset_ms)
+}
+
+/// Reset values are either a duration, an RFC 3339 timestamp, or a number of
+/// seconds (a unix timestamp when large enough to be one).
+fn parse_reset(value: &str, now: i64) -> Option<i64> {
+    if let Ok(secs) = value.parse::<f64>() {
+        return Some(if secs > 1_000_000_000.0 { (secs * 1000.0) as i64 } else { now + (secs * 1000.0) as i64 });
+    }
+    parse_rfc3339_ms(value).or_else(|| parse_duration_ms(value).map(|ms| now + ms))
+}
+
+fn window(headers: &HeaderMap, limit: &str, remaining: &str, reset: &str, now: i64) -> Option<RateLimitWindow> {
+    let window = RateLimitWindow {
+        limit: header_u64(headers, limit),
+        remaining: header_u64(headers, remaining),
+        reset_at: header(headers, reset).and_then(|v| parse_reset(v, now)),
+    };
+    (!window.is_empty()).then_some(window)
+}
+
+/// Extract quota information from a provider response.
+///
+/// Understands OpenAI's `x-ratelimit-{limit,remaining,reset}-{requests,tokens}`,
+/// Anthropic's `anthropic-ratelimit-{requests,tokens,input-tokens,output-tokens}-*`,
+/// the IETF `ratelimit-*` draft headers and `retry-after`.
+pub fn parse_rate_limit_headers(headers: &HeaderMap, now: i64) -> Option<RateLimits> {
+    let anthropic = |dimension: &str| {
+        window(
+            headers,
+            &format!("anthropic-ratelimit-{}-limit", dimension),
+            &format!("anthropic-ratelimit-{}-remaining", dimension),
+            &format!("anthropic-ratelimit-{}-reset", dimension),
+            now,
+        )
+    };
+    let openai = |dimension: &str| {
+        window(
+            headers,
+            &format!("x-ratelimit-limit-{}", dimension),
+            &format!("x-ratelimit-remaining-{}", dimension),
+            &format!("x-ratelimit-reset-{}", dimension),
+            now,
+        )
+    };
+
+    let limits = RateLimits {
+        requests: anthropic("requests")
+            .or_else(|| openai("requests"))
+            .or_else(|| window(headers, "ratelimit-limit", "ratelimit-remaining", "ratelimit-reset", now))
+            .or_else(|| window(headers, "x-ratelimit-limit", "x-ratelimit-remaining", "x-ratelimit-reset", now)),
+        tokens: anthropic("tokens").or_else(|| openai("tokens")),
+        input_tokens: anthropic("input-tokens"),
+        output_tokens: anthropic("output-tokens"),
+        retry_at: header(headers, "retry-after").and_then(|v| parse_reset(v, now)),
+    };
+
+    let found = limits.requests.is_some()
+        || limits.tokens.is_some()
+        || limits.input_tokens.is_some()
+        || limits.output_tokens.is_some()
+        || limits.retry_at.is_some();
+    found.then_some(limits)
+}
+
+/// Record the limits reported on a proxied response and notify the frontend.
+///
+/// Event emitted on `AppHandle`:
+///   "rate-limits-updated" → RateLimitSnapshot
+pub fn observe(app_handle: &AppHandle, provider: &str, api_key: Option<&str>, model: Option<&str>, headers: &HeaderMap) {
+    let now = now_ms();
+    let Some(limits) = parse_rate_limit_headers(headers, now) else {
+        return;
+    };
+
+    let snapshot = RateLimitSnapshot {
+        provider: provider.to_string(),
+        key_id: key_fingerprint(api_key),
+        model: model.unwrap_or_default().to_string(),
+        limits,
+        observed_at: now,
+    };
+
+    let state: tauri::State<RateLimitState> = app_handle.state();
+    state.lock().unwrap().snapshots.insert(
+        (snapshot.provider.clone(), snapshot.key_id.clone(), snapshot.model.clone()),
+        snapshot.clone(),
+    );
+    app_handle.emit("rate-limits-updated", snapshot).ok();
+}
+
+// ── Commands ─────────────────────────────────────────────────────────────────
+
+/// Latest known quota per provider, key and model, most recently observed first.
+/// Pass `provider` to only get that provider's entries.
+#[tauri::command]
+pub fn get_rate_limits(
+    provider: Option<String>,
+    state: tauri::State<'_, RateLimitState>,
+) -> Vec<RateLimitSnapshot> {
+    let tracker = state.lock().unwrap();
+    let mut snapshots: Vec<RateLimitSnapshot> = tracker
+        .snapshots
+        .values()
+        .filter(|s| provider.as_deref().is_none_or(|p| p == s.provider))
+        .cloned()
+        .collect();
+    snapshots.sort_by(|a, b| b.observed_at.cmp(&a.observed_at));
+    snapshots
+}
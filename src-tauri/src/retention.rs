@@ -0,0 +1,230 @@
+use std::time::Duration;
+
+use anyhow::Result as AnyhowResult;
+use rusqlite::{Connection, ToSql};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::database::{now_ms, ChangeOp, DbChange};
+use crate::pool::DbState;
+
+// ── Retention ────────────────────────────────────────────────────────────────
+//
+// Old runs and telemetry are pruned by a background task according to a policy
+// stored in `settings`. Nothing is pruned until a policy is set. Runs that are still
+// in progress are never pruned, and starred runs are kept unless `keep_starred` is
+// turned off. `eval_results` go with their `eval_runs` (ON DELETE CASCADE).
+
+/// `settings.key` holding the policy (JSON RetentionPolicy).
+const RETENTION_SETTING: &str = "retention_policy";
+/// First pass shortly after startup, then periodically.
+const FIRST_RUN_DELAY: Duration = Duration::from_secs(60);
+const RUN_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+/// VACUUM rewrites the whole file under the writer lock, so it only runs once at least
+/// this many pages, and this share of the file, are free.
+const VACUUM_MIN_FREE_PAGES: i64 = 2048;
+const VACUUM_MIN_FREE_RATIO: f64 = 0.25;
+
+fn default_true() -> bool {
+    true
+}
+
+/// Limits for a run table; a run is pruned when it breaks any of them.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct RunRetention {
+    /// Prune runs created more than this many days ago
+    #[serde(default)]
+    pub max_age_days: Option<u32>,
+    /// Keep only this many of the newest runs per scenario/agent
+    #[serde(default)]
+    pub max_per_runnable: Option<u32>,
+    #[serde(default = "default_true")]
+    pub keep_starred: bool,
+}
+
+impl Default for RunRetention {
+    fn default() -> Self {
+        Self { max_age_days: None, max_per_runnable: None, keep_starred: true }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+pub struct RetentionPolicy {
+    #[serde(default)]
+    pub executions: RunRetention,
+    /// Also applies to the runs' `eval_results`
+    #[serde(default)]
+    pub eval_runs: RunRetention,
+    /// Prune telemetry events that occurred more than this many days ago
+    #[serde(default)]
+    pub telemetry_max_age_days: Option<u32>,
+}
+
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct PruneReport {
+    pub executions: usize,
+    pub eval_runs: usize,
+    pub eval_results: usize,
+    pub telemetry_events: usize,
+    /// Whether the database was vacuumed afterwards
+    pub vacuumed: bool,
+    /// Deleted rows, for `DB_CHANGE_EVENT`
+    #[serde(skip)]
+    pub changes: Vec<DbChange>,
+}
+
+/// Policy persisted in the `settings` table (the default, which prunes nothing, when unset).
+pub fn stored_policy(conn: &Connection) -> RetentionPolicy {
+    crate::settings::get_json(conn, RETENTION_SETTING).unwrap_or_default()
+}
+
+/// Build the `SELECT id` of runs in `table` that `rules` prune.
+/// `runnable` lists the columns identifying the scenario/agent a run belongs to.
+fn prunable_runs_sql(table: &str, runnable: &str, unfinished: &str, rules: &RunRetention, now: i64) -> Option<String> {
+    let mut reasons = Vec::new();
+    if let Some(days) = rules.max_age_days {
+        reasons.push(format!("created_at < {}", now - days as i64 * DAY_MS));
+    }
+    if let Some(keep) = rules.max_per_runnable {
+        reasons.push(format!("recency > {}", keep));
+    }
+    if reasons.is_empty() {
+        return None;
+    }
+
+    let mut sql = format!(
+        "SELECT id FROM (
+           SELECT id, status, starred, created_at,
+                  ROW_NUMBER() OVER (PARTITION BY {} ORDER BY created_at DESC, id DESC) AS recency
+           FROM {}
+         )
+         WHERE status NOT IN ({}) AND ({})",
+        runnable,
+        table,
+        unfinished,
+        reasons.join(" OR ")
+    );
+    if rules.keep_starred {
+        sql.push_str(" AND starred = 0");
+    }
+    Some(sql)
+}
+
+/// Ids of the rows `sql` selects.
+fn selected_ids(conn: &Connection, sql: &str, params: &[&dyn ToSql]) -> rusqlite::Result<Vec<String>> {
+    let mut stmt = conn.prepare(sql)?;
+    let ids = stmt.query_map(params, |row| row.get(0))?.collect();
+    ids
+}
+
+/// Delete what `policy` prunes, in one transaction.
+pub fn prune(conn: &mut Connection, policy: &RetentionPolicy, now: i64) -> AnyhowResult<PruneReport> {
+    let tx = conn.transaction()?;
+    let mut report = PruneReport::default();
+
+    if let Some(runs) = prunable_runs_sql("executions", "type, runnable_id", "'queued', 'running'", &policy.executions, now) {
+        let ids = selected_ids(&tx, &runs, &[])?;
+        report.executions = tx.execute(&format!("DELETE FROM executions WHERE id IN ({})", runs), [])?;
+        report.changes.extend(DbChange::touched("executions", ChangeOp::Delete, ids));
+    }
+    if let Some(runs) = prunable_runs_sql("eval_runs", "runnable_type, runnable_id", "'running'", &policy.eval_runs, now) {
+        let ids = selected_ids(&tx, &runs, &[])?;
+        let results = selected_ids(&tx, &format!("SELECT id FROM eval_results WHERE eval_run_id IN ({})", runs), &[])?;
+        report.eval_results = results.len();
+        report.eval_runs = tx.execute(&format!("DELETE FROM eval_runs WHERE id IN ({})", runs), [])?;
+        report.changes.extend(DbChange::touched("eval_runs", ChangeOp::Delete, ids));
+        report.changes.extend(DbChange::touched("eval_results", ChangeOp::Delete, results));
+    }
+    if let Some(days) = policy.telemetry_max_age_days {
+        let cutoff = now - days as i64 * DAY_MS;
+        let ids = selected_ids(&tx, "SELECT id FROM telemetry_events WHERE occurred_at < ?1", &[&cutoff])?;
+        report.telemetry_events = tx.execute("DELETE FROM telemetry_events WHERE occurred_at < ?1", [cutoff])?;
+        report.changes.extend(DbChange::touched("telemetry_events", ChangeOp::Delete, ids));
+    }
+
+    tx.commit()?;
+    Ok(report)
+}
+
+/// Tell every window about the rows a pruning pass deleted.
+fn notify(app: &AppHandle, report: &PruneReport) {
+    for change in &report.changes {
+        crate::notify_change(app, Some(change.clone()));
+    }
+}
+
+/// Whether enough of the file is free pages to be worth a VACUUM.
+fn worth_vacuuming(conn: &Connection) -> AnyhowResult<bool> {
+    let pages: i64 = conn.pragma_query_value(None, "page_count", |row| row.get(0))?;
+    let free: i64 = conn.pragma_query_value(None, "freelist_count", |row| row.get(0))?;
+    Ok(free >= VACUUM_MIN_FREE_PAGES && free as f64 >= pages as f64 * VACUUM_MIN_FREE_RATIO)
+}
+
+/// Prune, then give the space back: VACUUM once enough pages are free, and truncate the WAL.
+/// The daily usage rollup is refreshed first so it still covers the pruned runs.
+pub fn apply(conn: &mut Connection, policy: &RetentionPolicy) -> AnyhowResult<PruneReport> {
+    crate::analytics::refresh_daily_usage(conn)?;
+    let mut report = prune(conn, policy, now_ms())?;
+    if worth_vacuuming(conn)? {
+        conn.execute_batch("VACUUM")?;
+        report.vacuumed = true;
+    }
+    conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+    Ok(report)
+}
+
+/// Apply the stored policy periodically for the lifetime of the app.
+pub async fn run_background(app: AppHandle) {
+    tokio::time::sleep(FIRST_RUN_DELAY).await;
+    loop {
+        let db = app.state::<DbState>().inner().clone();
+        let result = db
+            .write(|conn| {
+                let policy = stored_policy(conn);
+                apply(conn, &policy)
+            })
+            .await;
+        match result {
+            Ok(report) => notify(&app, &report),
+            Err(e) => log::error!("Retention run failed: {}", e),
+        }
+        tokio::time::sleep(RUN_INTERVAL).await;
+    }
+}
+
+// ── Commands ─────────────────────────────────────────────────────────────────
+
+#[tauri::command]
+pub async fn get_retention_policy(
+    state: tauri::State<'_, DbState>,
+) -> Result<RetentionPolicy, String> {
+    state.read(|conn| Ok(stored_policy(conn))).await
+}
+
+/// Persist the retention policy; the background task picks it up on its next pass.
+#[tauri::command]
+pub async fn set_retention_policy(
+    policy: RetentionPolicy,
+    state: tauri::State<'_, DbState>,
+) -> Result<RetentionPolicy, String> {
+    let stored = policy.clone();
+    state.write(move |conn| crate::settings::set_json(conn, RETENTION_SETTING, &stored)).await?;
+    Ok(policy)
+}
+
+/// Apply the stored policy now instead of waiting for the background task.
+#[tauri::command]
+pub async fn apply_retention_policy(
+    state: tauri::State<'_, DbState>,
+    app: AppHandle,
+) -> Result<PruneReport, String> {
+    let report = state
+        .write(|conn| {
+            let policy = stored_policy(conn);
+            apply(conn, &policy)
+        })
+        .await?;
+    notify(&app, &report);
+    Ok(report)
+}
@@ -2,7 +2,7 @@ use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
 use serde::{Deserialize, Serialize};
-use tauri::{AppHandle, Emitter};
+use tauri::{AppHandle, Emitter, Manager};
 use tauri_plugin_shell::process::{CommandChild, CommandEvent};
 use tauri_plugin_shell::ShellExt;
 
@@ -36,6 +36,9 @@ pub struct RunnerExit {
     pub code: Option<i32>,
 }
 
+/// Environment variable holding a tool's env variables as a JSON object.
+pub const TOOL_ENV_VAR: &str = "RETICLE_TOOL_ENV";
+
 // ── Deno permission config ────────────────────────────────────────────────────
 
 #[derive(Deserialize)]
@@ -55,6 +58,7 @@ pub struct RunnerPermissions {
 /// `script`      – path to the .ts/.js file Deno should run
 /// `args`        – extra CLI args forwarded to the script (after `--`)
 /// `permissions` – Deno permission flags; omit a field to deny that permission
+/// `with_env`    – pass the env variables (secrets decrypted) in `TOOL_ENV_VAR`
 ///
 /// Events emitted on `AppHandle`:
 ///   "runner-stdout"  → RunnerOutput { id, data }
@@ -66,6 +70,7 @@ pub async fn runner_spawn(
     script: String,
     args: Vec<String>,
     permissions: Option<RunnerPermissions>,
+    with_env: Option<bool>,
     state: tauri::State<'_, RunnerState>,
     app: AppHandle,
 ) -> Result<(), String> {
@@ -106,11 +111,19 @@ pub async fn runner_spawn(
         deno_args.extend(args);
     }
 
-    let (mut rx, child) = app
+    let mut command = app
         .shell()
         .sidecar("deno")
         .map_err(|e| e.to_string())?
-        .args(&deno_args)
+        .args(&deno_args);
+    if with_env.unwrap_or(false) {
+        let db = app.state::<crate::pool::DbState>().inner().clone();
+        let secrets = app.state::<crate::secrets::SecretsState>().inner().clone();
+        let env = db.read(move |conn| secrets.tool_env(conn)).await?;
+        command = command.env(TOOL_ENV_VAR, env);
+    }
+
+    let (mut rx, child) = command
         .spawn()
         .map_err(|e| e.to_string())?;
 
@@ -201,11 +214,18 @@ pub fn runner_list(state: tauri::State<'_, RunnerState>) -> Vec<String> {
 }
 
 /// Write a tool code block to a temp .ts file and return its path.
+/// With `with_env`, the code is preceded by `const env = ...;` parsed from `TOOL_ENV_VAR`,
+/// which `runner_spawn` sets; no env value is ever written to the file.
 /// The caller is responsible for deleting it after use.
 #[tauri::command]
-pub fn write_temp_script(id: String, code: String) -> Result<String, String> {
+pub fn write_temp_script(id: String, code: String, with_env: Option<bool>) -> Result<String, String> {
+    let preamble = if with_env.unwrap_or(false) {
+        format!("const env = JSON.parse(Deno.env.get(\"{}\") ?? \"{{}}\");\n", TOOL_ENV_VAR)
+    } else {
+        String::new()
+    };
     let path = std::env::temp_dir().join(format!("reticle_tool_{}.ts", id));
-    std::fs::write(&path, &code).map_err(|e| e.to_string())?;
+    std::fs::write(&path, preamble + &code).map_err(|e| e.to_string())?;
     Ok(path.to_string_lossy().into_owned())
 }
 
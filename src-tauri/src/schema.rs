@@ -0,0 +1,227 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+use rusqlite::Connection;
+use serde_json::Value;
+
+// ── Webview access ───────────────────────────────────────────────────────────
+//
+// The generic db_*_cmd commands splice table and column names into SQL, and any
+// script in the webview can call them. Every identifier they receive is checked
+// against the live schema (read once at startup, after migrations), and each
+// table lists the operations the webview may perform on it. Tables that are not
+// listed are not reachable from the webview at all.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operation {
+    Select,
+    Count,
+    Insert,
+    Update,
+    Delete,
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Operation::Select => "select",
+            Operation::Count => "count",
+            Operation::Insert => "insert",
+            Operation::Update => "update",
+            Operation::Delete => "delete",
+        };
+        f.write_str(name)
+    }
+}
+
+use Operation::*;
+
+const READ_WRITE: &[Operation] = &[Select, Count, Insert, Update, Delete];
+/// Tables written by the backend only (proxy logs); the webview may read and clear them.
+const BACKEND_WRITTEN: &[Operation] = &[Select, Count, Delete];
+
+const WEBVIEW_ACCESS: &[(&str, &[Operation])] = &[
+    ("accounts", READ_WRITE),
+    ("agent_memories", READ_WRITE),
+    ("agents", READ_WRITE),
+    ("api_keys", READ_WRITE),
+    ("attachments", READ_WRITE),
+    ("collections", READ_WRITE),
+    ("env_variables", READ_WRITE),
+    ("eval_results", READ_WRITE),
+    ("eval_runs", READ_WRITE),
+    ("eval_test_cases", READ_WRITE),
+    ("executions", READ_WRITE),
+    ("prompt_templates", READ_WRITE),
+    ("scenarios", READ_WRITE),
+    ("settings", READ_WRITE),
+    ("telemetry_events", READ_WRITE),
+    ("tool_links", READ_WRITE),
+    ("tools", READ_WRITE),
+    ("proxy_requests", BACKEND_WRITTEN),
+    ("shadow_responses", BACKEND_WRITTEN),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaError {
+    UnknownTable(String),
+    UnknownColumn { table: String, column: String },
+    OperationNotAllowed { table: String, operation: Operation },
+    InvalidQuery(String),
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchemaError::UnknownTable(table) => write!(f, "Unknown table '{}'", table),
+            SchemaError::UnknownColumn { table, column } => {
+                write!(f, "Unknown column '{}' on table '{}'", column, table)
+            }
+            SchemaError::OperationNotAllowed { table, operation } => {
+                write!(f, "Operation '{}' is not allowed on table '{}'", operation, table)
+            }
+            SchemaError::InvalidQuery(message) => write!(f, "Invalid query: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for SchemaError {}
+
+// ── Schema ───────────────────────────────────────────────────────────────────
+
+struct TableSchema {
+    columns: HashSet<String>,
+    operations: &'static [Operation],
+}
+
+pub struct Schema {
+    tables: RwLock<HashMap<String, TableSchema>>,
+}
+
+pub type SchemaState = Arc<Schema>;
+
+impl Schema {
+    /// Read the tables and columns from `sqlite_master` / `pragma table_info`.
+    pub fn load(conn: &Connection) -> rusqlite::Result<Self> {
+        Ok(Self { tables: RwLock::new(Self::read_tables(conn)?) })
+    }
+
+    /// Re-read the schema after migrating the database.
+    pub fn reload(&self, conn: &Connection) -> rusqlite::Result<()> {
+        let tables = Self::read_tables(conn)?;
+        *self.tables.write().unwrap() = tables;
+        Ok(())
+    }
+
+    fn read_tables(conn: &Connection) -> rusqlite::Result<HashMap<String, TableSchema>> {
+        let mut stmt = conn.prepare(
+            "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'",
+        )?;
+        let names: Vec<String> = stmt.query_map([], |row| row.get(0))?.collect::<Result<_, _>>()?;
+
+        let mut tables = HashMap::new();
+        for name in names {
+            let Some((_, operations)) = WEBVIEW_ACCESS.iter().find(|(table, _)| *table == name) else {
+                continue;
+            };
+            let mut info = conn.prepare("SELECT name FROM pragma_table_info(?1)")?;
+            let columns = info.query_map([&name], |row| row.get(0))?.collect::<Result<_, _>>()?;
+            tables.insert(name, TableSchema { columns, operations });
+        }
+        Ok(tables)
+    }
+
+    /// Check that the webview may run `operation` on `table` and that every column
+    /// named in `query` (`where`, `columns`, `orderBy`) and `data` exists. `where` keys
+    /// and `orderBy` may be `column.$path` JSON paths on an existing column.
+    pub fn authorize(
+        &self,
+        table: &str,
+        operation: Operation,
+        query: Option<&Value>,
+        data: Option<&Value>,
+    ) -> Result<(), SchemaError> {
+        let tables = self.tables.read().unwrap();
+        let schema = tables
+            .get(table)
+            .ok_or_else(|| SchemaError::UnknownTable(table.to_string()))?;
+        if !schema.operations.contains(&operation) {
+            return Err(SchemaError::OperationNotAllowed { table: table.to_string(), operation });
+        }
+
+        let check = |column: &str| {
+            if schema.columns.contains(column) {
+                Ok(())
+            } else {
+                Err(SchemaError::UnknownColumn { table: table.to_string(), column: column.to_string() })
+            }
+        };
+        // `where` keys and `orderBy` may also address a path inside a JSON column
+        let check_key = |key: &str| match crate::database::split_json_path(key) {
+            Some((column, path)) => {
+                check(column)?;
+                if crate::database::is_valid_json_path(path) {
+                    Ok(())
+                } else {
+                    Err(SchemaError::InvalidQuery(format!("invalid JSON path '{}'", path)))
+                }
+            }
+            None => check(key),
+        };
+
+        if let Some(data) = data {
+            let data = data
+                .as_object()
+                .ok_or_else(|| SchemaError::InvalidQuery("data must be a JSON object".to_string()))?;
+            data.keys().try_for_each(|column| check(column))?;
+        }
+
+        let Some(query) = query.and_then(|q| q.as_object()) else {
+            return Ok(());
+        };
+        if let Some(where_value) = query.get("where").filter(|w| !w.is_null()) {
+            check_where(where_value, &check_key)?;
+        }
+        if let Some(columns) = query.get("columns").and_then(|c| c.as_array()) {
+            for column in columns {
+                let column = column
+                    .as_str()
+                    .ok_or_else(|| SchemaError::InvalidQuery("columns must be strings".to_string()))?;
+                check(column)?;
+            }
+        }
+        if let Some(order_by) = query.get("orderBy").filter(|o| !o.is_null()) {
+            let order_by = order_by
+                .as_str()
+                .ok_or_else(|| SchemaError::InvalidQuery("orderBy must be a column name".to_string()))?;
+            check_key(order_by)?;
+        }
+        Ok(())
+    }
+}
+
+/// Walk a `where` object (see the query DSL in database.rs) and check its columns.
+fn check_where(
+    where_value: &Value,
+    check: &impl Fn(&str) -> Result<(), SchemaError>,
+) -> Result<(), SchemaError> {
+    let where_obj = where_value
+        .as_object()
+        .ok_or_else(|| SchemaError::InvalidQuery("where must be a JSON object".to_string()))?;
+    for (key, cond) in where_obj {
+        match key.as_str() {
+            "and" | "or" => {
+                let items = cond
+                    .as_array()
+                    .ok_or_else(|| SchemaError::InvalidQuery(format!("'{}' expects an array", key)))?;
+                for item in items {
+                    check_where(item, check)?;
+                }
+            }
+            "not" => check_where(cond, check)?,
+            column => check(column)?,
+        }
+    }
+    Ok(())
+}
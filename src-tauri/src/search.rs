@@ -0,0 +1,104 @@
+use rusqlite::{params_from_iter, Connection};
+use serde::Serialize;
+
+use crate::pool::DbState;
+
+// ── Full-text search ─────────────────────────────────────────────────────────
+//
+// `search_index` (FTS5) and `search_entities` are maintained by triggers, see
+// migrations/0024_create_search_index.sql.
+
+/// Matched terms in `SearchHit::snippet` are wrapped in these markers (private-use
+/// characters, so they never collide with indexed text and are not rendered as HTML).
+pub const HIGHLIGHT_START: &str = "\u{E000}";
+pub const HIGHLIGHT_END: &str = "\u{E001}";
+
+const ENTITY_TYPES: &[&str] = &["scenario", "agent", "prompt_template", "tool", "execution"];
+const DEFAULT_LIMIT: usize = 50;
+
+#[derive(Serialize, Clone, Debug)]
+pub struct SearchHit {
+    pub entity_type: String,
+    pub entity_id: String,
+    pub title: Option<String>,
+    pub snippet: String,
+    /// Relevance (negated bm25, title matches weigh more); higher is better.
+    pub score: f64,
+}
+
+/// Turn free text into an FTS5 query: every word must match, the last one as a
+/// prefix so results update while typing. FTS5 operators in the input are quoted.
+fn to_match_query(text: &str) -> Option<String> {
+    let words: Vec<String> = text
+        .split_whitespace()
+        .map(|word| format!("\"{}\"", word.replace('"', "\"\"")))
+        .collect();
+    (!words.is_empty()).then(|| format!("{}*", words.join(" ")))
+}
+
+pub fn search_entities(
+    conn: &Connection,
+    text: &str,
+    entity_types: Option<&[String]>,
+    limit: usize,
+) -> anyhow::Result<Vec<SearchHit>> {
+    let Some(match_query) = to_match_query(text) else {
+        return Ok(Vec::new());
+    };
+
+    let mut sql = format!(
+        "SELECT e.entity_type, e.entity_id, s.title,
+                snippet(search_index, -1, '{}', '{}', '…', 16),
+                -bm25(search_index, 10.0, 1.0)
+         FROM search_index s
+         JOIN search_entities e ON e.rowid = s.rowid
+         WHERE search_index MATCH ?1",
+        HIGHLIGHT_START, HIGHLIGHT_END
+    );
+    let mut params = vec![match_query];
+
+    if let Some(types) = entity_types.filter(|t| !t.is_empty()) {
+        if let Some(unknown) = types.iter().find(|t| !ENTITY_TYPES.contains(&t.as_str())) {
+            return Err(anyhow::anyhow!("Unknown entity type '{}'", unknown));
+        }
+        let placeholders: Vec<String> = (0..types.len()).map(|i| format!("?{}", i + 2)).collect();
+        sql.push_str(&format!(" AND e.entity_type IN ({})", placeholders.join(", ")));
+        params.extend(types.iter().cloned());
+    }
+    sql.push_str(&format!(" ORDER BY bm25(search_index, 10.0, 1.0) LIMIT {}", limit));
+
+    let mut stmt = conn.prepare(&sql)?;
+    let hits = stmt
+        .query_map(params_from_iter(params), |row| {
+            Ok(SearchHit {
+                entity_type: row.get(0)?,
+                entity_id: row.get(1)?,
+                title: row.get(2)?,
+                snippet: row.get(3)?,
+                score: row.get(4)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(hits)
+}
+
+// ── Commands ─────────────────────────────────────────────────────────────────
+
+/// Ranked full-text search across scenarios, agents, prompt templates, tools and
+/// execution results.
+///
+/// `query`        – free text; all words must match, the last one as a prefix
+/// `entity_types` – optional subset of scenario|agent|prompt_template|tool|execution
+/// `limit`        – maximum number of hits (default 50)
+#[tauri::command]
+pub async fn search(
+    query: String,
+    entity_types: Option<Vec<String>>,
+    limit: Option<usize>,
+    state: tauri::State<'_, DbState>,
+) -> Result<Vec<SearchHit>, String> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).max(1);
+    state
+        .read(move |conn| search_entities(conn, &query, entity_types.as_deref(), limit))
+        .await
+}
@@ -0,0 +1,326 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use anyhow::{anyhow, Result as AnyhowResult};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use rusqlite::Connection;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+use crate::pool::DbState;
+
+// ── Secrets at rest ──────────────────────────────────────────────────────────
+//
+// `api_keys.key` and the `value` of `env_variables` rows with `is_secret = 1` are
+// stored as "enc:v1:" + base64(nonce || AES-256-GCM ciphertext). The key comes from
+// RETICLE_PASSPHRASE (PBKDF2-HMAC-SHA256 with a per-install salt file) or, when no
+// passphrase is set, from a random key file next to the database.
+//
+// The generic db commands seal these columns before writing and mask them when
+// reading, so the webview never receives a stored secret. Plaintext is only
+// produced in Rust: the proxy injects API keys (`decrypt_api_key`) and fills in
+// `{{KEY}}` placeholders of secret variables in outgoing request bodies
+// (`fill_placeholders`), and tool scripts get their `env` from `tool_env`, handed
+// to the Deno process through its environment rather than written to disk.
+
+pub const PASSPHRASE_ENV: &str = "RETICLE_PASSPHRASE";
+const KEY_FILE: &str = "reticle.key";
+const SALT_FILE: &str = "reticle.salt";
+const PBKDF2_ROUNDS: u32 = 600_000;
+const PREFIX: &str = "enc:v1:";
+const NONCE_LEN: usize = 12;
+
+pub struct Secrets {
+    cipher: Aes256Gcm,
+}
+
+pub type SecretsState = Arc<Secrets>;
+
+/// Read a file of exactly `len` random bytes, creating it (owner-only) when missing.
+fn read_or_create_random(path: &Path, len: usize) -> AnyhowResult<Vec<u8>> {
+    if path.exists() {
+        let bytes = std::fs::read(path)?;
+        if bytes.len() != len {
+            return Err(anyhow!("{} is corrupt (expected {} bytes)", path.display(), len));
+        }
+        return Ok(bytes);
+    }
+
+    let mut bytes = vec![0u8; len];
+    OsRng.fill_bytes(&mut bytes);
+    std::fs::write(path, &bytes)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+    }
+    Ok(bytes)
+}
+
+impl Secrets {
+    pub fn from_key(key: &[u8; 32]) -> Self {
+        Self {
+            cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key)),
+        }
+    }
+
+    /// Key from RETICLE_PASSPHRASE when set, otherwise from the key file in `dir`.
+    pub fn load(dir: &Path) -> AnyhowResult<Self> {
+        let mut key = [0u8; 32];
+        match std::env::var(PASSPHRASE_ENV) {
+            Ok(passphrase) if !passphrase.is_empty() => {
+                let salt = read_or_create_random(&dir.join(SALT_FILE), 16)?;
+                pbkdf2::pbkdf2_hmac::<sha2::Sha256>(passphrase.as_bytes(), &salt, PBKDF2_ROUNDS, &mut key);
+            }
+            _ => key.copy_from_slice(&read_or_create_random(&dir.join(KEY_FILE), 32)?),
+        }
+        Ok(Self::from_key(&key))
+    }
+
+    pub fn is_encrypted(value: &str) -> bool {
+        value.starts_with(PREFIX)
+    }
+
+    pub fn encrypt(&self, plaintext: &str) -> AnyhowResult<String> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, plaintext.as_bytes())
+            .map_err(|_| anyhow!("Failed to encrypt secret"))?;
+        let mut payload = nonce.to_vec();
+        payload.extend(ciphertext);
+        Ok(format!("{}{}", PREFIX, STANDARD.encode(payload)))
+    }
+
+    /// Decrypt a stored value; values written before encryption are returned as-is.
+    pub fn decrypt(&self, value: &str) -> AnyhowResult<String> {
+        let Some(encoded) = value.strip_prefix(PREFIX) else {
+            return Ok(value.to_string());
+        };
+        let payload = STANDARD.decode(encoded)?;
+        if payload.len() < NONCE_LEN {
+            return Err(anyhow!("Encrypted secret is truncated"));
+        }
+        let (nonce, ciphertext) = payload.split_at(NONCE_LEN);
+        let plaintext = self
+            .cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| anyhow!("Failed to decrypt secret (wrong passphrase or key file?)"))?;
+        Ok(String::from_utf8(plaintext)?)
+    }
+
+    fn encrypt_value(&self, value: &mut Value) -> AnyhowResult<()> {
+        if let Some(text) = value.as_str().filter(|t| !Self::is_encrypted(t)) {
+            *value = json!(self.encrypt(text)?);
+        }
+        Ok(())
+    }
+
+    // ── Generic db command layer ─────────────────────────────────────────────
+
+    /// Encrypt secret columns in `data` before an insert (`query` None) or update.
+    pub fn seal(&self, conn: &Connection, table: &str, query: Option<&Value>, data: &mut Value) -> AnyhowResult<()> {
+        let Some(data) = data.as_object_mut() else {
+            return Ok(());
+        };
+        match table {
+            "api_keys" => {
+                if let Some(key) = data.get_mut("key") {
+                    self.encrypt_value(key)?;
+                }
+            }
+            "env_variables" if data.contains_key("value") => {
+                let secret = match data.get("is_secret") {
+                    Some(flag) => flag.as_i64() == Some(1) || flag.as_bool() == Some(true),
+                    // Updating the value of existing rows: secret if any of them is
+                    None => match query {
+                        Some(query) => {
+                            let mut lookup = query.clone();
+                            if let Some(lookup) = lookup.as_object_mut() {
+                                lookup.insert("columns".to_string(), json!(["is_secret"]));
+                            }
+                            crate::database::db_select(conn, table, lookup)?
+                                .iter()
+                                .any(|row| row.get("is_secret").and_then(|v| v.as_i64()) == Some(1))
+                        }
+                        None => false,
+                    },
+                };
+                if secret {
+                    self.encrypt_value(data.get_mut("value").unwrap())?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// After a write: encrypt rows that became secret and clear rows that no longer are. A secret
+    /// is never decrypted back into plaintext; un-marking one requires entering a new value.
+    pub fn reconcile(&self, conn: &Connection, table: &str) -> AnyhowResult<()> {
+        match table {
+            "api_keys" => {
+                for row in crate::database::db_select(conn, table, json!({ "columns": ["id", "key"] }))? {
+                    let (Some(id), Some(key)) = (row["id"].as_str(), row["key"].as_str()) else {
+                        continue;
+                    };
+                    if !Self::is_encrypted(key) {
+                        conn.execute("UPDATE api_keys SET key = ?1 WHERE id = ?2", [self.encrypt(key)?, id.to_string()])?;
+                    }
+                }
+            }
+            "env_variables" => {
+                for row in crate::database::db_select(conn, table, json!({ "columns": ["id", "value", "is_secret"] }))? {
+                    let (Some(id), Some(value)) = (row["id"].as_str(), row["value"].as_str()) else {
+                        continue;
+                    };
+                    let secret = row["is_secret"].as_i64() == Some(1);
+                    let stored = match (secret, Self::is_encrypted(value)) {
+                        (true, false) => self.encrypt(value)?,
+                        (false, true) => String::new(),
+                        _ => continue,
+                    };
+                    conn.execute("UPDATE env_variables SET value = ?1 WHERE id = ?2", [stored, id.to_string()])?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Replace secret columns in rows returned to the webview with a masked hint.
+    pub fn mask(&self, table: &str, rows: &mut [Map<String, Value>]) {
+        for row in rows {
+            let column = match table {
+                "api_keys" => "key",
+                "env_variables" if row.get("is_secret").and_then(|v| v.as_i64()) != Some(0) => "value",
+                "env_variables" => continue,
+                _ => return,
+            };
+            if let Some(value) = row.get_mut(column) {
+                if let Some(text) = value.as_str() {
+                    *value = json!(self.masked(text));
+                }
+            }
+        }
+    }
+
+    fn masked(&self, stored: &str) -> String {
+        match self.decrypt(stored) {
+            Ok(plain) if plain.is_empty() => String::new(),
+            Ok(plain) if plain.chars().count() > 8 => {
+                let tail: String = plain.chars().rev().take(4).collect::<Vec<_>>().into_iter().rev().collect();
+                format!("••••{}", tail)
+            }
+            _ => "••••••••".to_string(),
+        }
+    }
+
+    /// Key and plaintext value of the env variables (only secret ones when `secret_only`).
+    fn env_variables(&self, conn: &Connection, secret_only: bool) -> AnyhowResult<Vec<(String, String)>> {
+        let filter = if secret_only { json!({ "is_secret": 1 }) } else { json!({}) };
+        crate::database::db_select(conn, "env_variables", json!({
+            "where": filter,
+            "orderBy": "created_at",
+            "orderDirection": "asc"
+        }))?
+        .iter()
+        .map(|row| {
+            let key = row.get("key").and_then(|v| v.as_str()).unwrap_or_default().to_string();
+            let stored = row.get("value").and_then(|v| v.as_str()).unwrap_or_default();
+            Ok((key, self.decrypt(stored)?))
+        })
+        .collect()
+    }
+
+    /// `body` (a JSON request body) with `{{KEY}}` placeholders of secret env
+    /// variables replaced by their JSON-escaped values, or None when it has none.
+    pub fn fill_placeholders(&self, conn: &Connection, body: &[u8]) -> AnyhowResult<Option<Vec<u8>>> {
+        let Some(text) = std::str::from_utf8(body).ok().filter(|t| t.contains("{{")) else {
+            return Ok(None);
+        };
+        let mut filled = text.to_string();
+        for (key, value) in self.env_variables(conn, true)? {
+            let placeholder = format!("{{{{{}}}}}", key);
+            if filled.contains(&placeholder) {
+                let escaped = serde_json::to_string(&value)?;
+                filled = filled.replace(&placeholder, &escaped[1..escaped.len() - 1]);
+            }
+        }
+        Ok((filled != text).then(|| filled.into_bytes()))
+    }
+
+    /// Every env variable decrypted, as the JSON object tool scripts read as `env`.
+    pub fn tool_env(&self, conn: &Connection) -> AnyhowResult<String> {
+        let env: Map<String, Value> = self
+            .env_variables(conn, false)?
+            .into_iter()
+            .map(|(key, value)| (key, json!(value)))
+            .collect();
+        Ok(Value::Object(env).to_string())
+    }
+
+    /// Plaintext API key for a provider, for injection by the proxy. None when no key
+    /// is stored; an error when the stored key cannot be decrypted.
+    pub fn decrypt_api_key(&self, conn: &Connection, provider: &str) -> AnyhowResult<Option<String>> {
+        let mut keys = crate::database::db_select(conn, "api_keys", json!({
+            "where": { "provider": provider }
+        }))?;
+        let Some(stored) = keys.pop().and_then(|row| row.get("key")?.as_str().map(String::from)) else {
+            return Ok(None);
+        };
+        let key = self
+            .decrypt(&stored)
+            .map_err(|e| anyhow!("Stored API key for {} cannot be decrypted: {}", provider, e))?;
+        Ok(Some(key))
+    }
+}
+
+// ── Commands ─────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Clone, Debug)]
+pub struct EnvVariable {
+    pub id: String,
+    pub key: String,
+    pub value: String,
+    pub is_secret: i64,
+}
+
+/// Environment variables for `{{variable}}` substitution in the webview. The value
+/// of a secret one is its own `{{KEY}}` placeholder, so substitution leaves it in
+/// place for the proxy to fill in (`Secrets::fill_placeholders`).
+#[tauri::command]
+pub async fn resolve_env_variables(
+    state: tauri::State<'_, DbState>,
+    secrets: tauri::State<'_, SecretsState>,
+) -> Result<Vec<EnvVariable>, String> {
+    let secrets = secrets.inner().clone();
+    state
+        .read(move |conn| {
+            crate::database::db_select(conn, "env_variables", json!({
+                "orderBy": "created_at",
+                "orderDirection": "asc"
+            }))?
+            .into_iter()
+            .map(|row| {
+                let key = row.get("key").and_then(|v| v.as_str()).unwrap_or_default().to_string();
+                let is_secret = row.get("is_secret").and_then(|v| v.as_i64()).unwrap_or(0);
+                let value = if is_secret == 1 {
+                    format!("{{{{{}}}}}", key)
+                } else {
+                    secrets.decrypt(row.get("value").and_then(|v| v.as_str()).unwrap_or_default())?
+                };
+                Ok(EnvVariable {
+                    id: row.get("id").and_then(|v| v.as_str()).unwrap_or_default().to_string(),
+                    key,
+                    value,
+                    is_secret,
+                })
+            })
+            .collect()
+        })
+        .await
+}
@@ -1,21 +1,33 @@
 use axum::{
+    body::Bytes,
     extract::State,
-    http::{Request, StatusCode},
+    http::{HeaderValue, Request, StatusCode},
     response::Response,
     routing::any,
     Router,
 };
+use futures_util::{Stream, StreamExt};
+use http_body_util::BodyDataStream;
+use hyper_util::rt::TokioExecutor;
 use reqwest::Client;
+use std::future::Future;
 use std::net::SocketAddr;
-use std::sync::{Arc, Mutex};
-use tower_http::cors::{Any, CorsLayer};
+use std::sync::Arc;
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
 use tauri::{AppHandle, Manager};
-use rusqlite::Connection;
-use serde_json::json;
+use serde_json::{json, Value};
+use ulid::Ulid;
+
+use crate::database::now_ms;
+use crate::governor::{estimate_tokens, GovernorState};
+use crate::pool::DbState;
+use crate::timing::{RequestTimer, TimedBody, TimedConnector};
 
 #[derive(Clone)]
 struct ProxyState {
     client: Client,
+    /// Client for the proxied request itself, timed phase by phase (see timing.rs)
+    upstream: hyper_util::client::legacy::Client<TimedConnector, TimedBody>,
     app_handle: AppHandle,
 }
 
@@ -28,7 +40,8 @@ async fn proxy_handler(
         .uri()
         .path_and_query()
         .map(|v| v.as_str())
-        .unwrap_or(path);
+        .unwrap_or(path)
+        .to_string();
 
     let target_url_base = match req.headers().get("X-Proxy-Target-Url") {
         Some(url) => url.to_str().map_err(|_| StatusCode::BAD_REQUEST)?.to_string(),
@@ -43,8 +56,9 @@ async fn proxy_handler(
     let headers = req.headers().clone();
     
     // --- API Key Handling ---
-    let mut request_builder = state.client.request(method.clone(), &target_url); // Now method.clone() is okay
+    let mut request_builder = http::Request::builder().method(method.clone()).uri(&target_url);
     let mut api_key_found = false;
+    let mut api_key_used = None;
 
     let api_auth_header_name_option: Option<String> = headers.get("X-Api-Auth-Header")
         .and_then(|h| h.to_str().ok())
@@ -54,30 +68,23 @@ async fn proxy_handler(
         .and_then(|provider| provider.to_str().ok())
         .map(String::from);
 
-    if let (Some(provider), Some(api_auth_header_name)) = (provider_option, api_auth_header_name_option) {
-        let db_state: tauri::State<Arc<Mutex<Connection>>> = state.app_handle.state();
-        let db_conn = db_state.lock().unwrap();
+    // Keys and secrets never leave for a target other than the provider's own base URL.
+    let trusted_provider = provider_option
+        .as_deref()
+        .and_then(|provider| crate::providers::trusted_target(provider, &target_url_base));
 
-        let api_key_result = crate::database::db_select(&db_conn, "api_keys", json!({
-            "where": {
-                "provider": &provider
-            }
-        }));
-
-        if let Ok(mut keys) = api_key_result {
-            if let Some(key_entry) = keys.pop() {
-                if let Some(api_key_val) = key_entry.get("key") {
-                    if let Some(api_key) = api_key_val.as_str() {
-                        api_key_found = true;
-                        if api_auth_header_name == "Authorization" {
-                            let auth_header = format!("Bearer {}", api_key);
-                            request_builder = request_builder.header("Authorization", &auth_header);
-                        } else { // All other headers are set directly
-                            request_builder = request_builder.header(&api_auth_header_name, api_key);
-                        }
-                    }
-                }
+    // Reported in the request log once there is an entry to write it to
+    let mut secrets_error = None;
+    if let (Some(provider), Some(api_auth_header_name)) = (trusted_provider.map(|p| p.id), api_auth_header_name_option) {
+        match lookup_api_key(&state.app_handle, provider).await {
+            Ok(Some(api_key)) => {
+                api_key_found = true;
+                let (name, value) = api_key_header(&api_auth_header_name, &api_key);
+                request_builder = request_builder.header(name, value);
+                api_key_used = Some(api_key);
             }
+            Ok(None) => {}
+            Err(e) => secrets_error = Some(e),
         }
     }
     // --- End API Key Handling ---
@@ -90,12 +97,57 @@ async fn proxy_handler(
     let body_is_empty = body_bytes.is_empty();
     let has_content_type = headers.contains_key("content-type");
 
-    let body = reqwest::Body::from(body_bytes);
+    let body_json = match serde_json::from_slice::<Value>(&body_bytes) {
+        Ok(Value::Object(map)) => Some(map),
+        _ => None,
+    };
+    let model = body_json
+        .as_ref()
+        .and_then(|b| b.get("model"))
+        .and_then(|m| m.as_str())
+        .map(String::from);
+
+    // --- Shadow Traffic ---
+    let mut primary_record = None;
+    if is_post && trusted_provider.is_some() {
+        if let (Some(provider), Some(body_json), Some(model)) = (&provider_option, &body_json, &model) {
+            let shadow_header = headers
+                .get(crate::shadow::SHADOW_TARGETS_HEADER)
+                .and_then(|h| h.to_str().ok());
+            let targets = crate::shadow::resolve_targets(&state.app_handle, shadow_header, provider, model).await;
+
+            if !targets.is_empty() {
+                let group_id = Ulid::new().to_string();
+                crate::shadow::spawn_shadows(
+                    &state.client,
+                    &state.app_handle,
+                    &group_id,
+                    provider,
+                    &path_query,
+                    body_json,
+                    targets,
+                );
+                primary_record = Some(crate::shadow::ShadowRecord {
+                    group_id,
+                    role: "primary",
+                    provider: provider.clone(),
+                    model: model.clone(),
+                    request: Value::Object(body_json.clone()),
+                    status_code: None,
+                    body: None,
+                    latency_ms: 0,
+                    error: None,
+                });
+            }
+        }
+    }
+    // --- End Shadow Traffic ---
 
     let mut excluded_headers = vec![
         "x-proxy-target-url",
         "x-api-provider",
         "x-api-auth-header",
+        crate::shadow::SHADOW_TARGETS_HEADER,
         "host",
         "connection",
         "keep-alive",
@@ -131,22 +183,79 @@ async fn proxy_handler(
         request_builder = request_builder.header("Content-Type", "application/json");
     }
     
-    let start_time = std::time::Instant::now();
-    
-    let response = request_builder
-        .body(body)
-        .send()
-        .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-    
-    let latency_ms = start_time.elapsed().as_millis() as u64;
+    // Wait for the client-side rate limiter before starting the clock on the upstream call.
+    let admission = match &provider_option {
+        Some(provider) => {
+            let governor = state.app_handle.state::<GovernorState>().inner().clone();
+            let tokens = estimate_tokens(body_json.as_ref(), body_bytes.len());
+            Some(governor.admit(provider, model.as_deref(), tokens).await)
+        }
+        None => None,
+    };
+    let queue_wait_ms = admission.as_ref().map_or(0, |a| a.waited.as_millis() as u64);
+
+    let request_id = Ulid::new().to_string();
+    let timer = RequestTimer::start();
+    let mut log_entry = json!({
+        "id": request_id,
+        "provider": provider_option,
+        "method": method.as_str(),
+        "url": target_url,
+        "model": model,
+        "queue_wait_ms": queue_wait_ms,
+        "started_at": now_ms(),
+    });
+
+    let body_bytes = match (secrets_error, trusted_provider) {
+        (Some(e), _) => Err(e),
+        (None, Some(_)) => fill_secrets(&state.app_handle, body_bytes).await,
+        (None, None) => Ok(body_bytes),
+    };
+    let body_bytes = match body_bytes {
+        Ok(body_bytes) => body_bytes,
+        Err(e) => {
+            log_entry["error"] = json!(e);
+            log_entry["ended_at"] = json!(now_ms());
+            write_request_log(&state.app_handle, log_entry).await;
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+    let request = request_builder
+        .body(timer.body(body_bytes))
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    let send_result = timer.scope(state.upstream.request(request)).await;
+
+    let latency_ms = timer.started().elapsed().as_millis() as u64;
+    let timing = timer.summary(None);
+    log_entry["latency_ms"] = json!(latency_ms);
+    log_entry["timing_json"] = json!(serde_json::to_string(&timing).ok());
+
+    let response = match send_result {
+        Ok(response) => response,
+        Err(e) => {
+            log_entry["error"] = json!(e.to_string());
+            log_entry["ended_at"] = json!(now_ms());
+            write_request_log(&state.app_handle, log_entry).await;
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
 
     let response_status = response.status();
     let response_headers = response.headers().clone();
 
+    log_entry["status_code"] = json!(response_status.as_u16());
+    write_request_log(&state.app_handle, log_entry).await;
+
+    if let Some(provider) = &provider_option {
+        crate::ratelimits::observe(&state.app_handle, provider, api_key_used.as_deref(), model.as_deref(), &response_headers);
+    }
+
     let mut response_builder = Response::builder().status(response_status);
     
     response_builder = response_builder.header("X-Request-Latency-Ms", latency_ms.to_string());
+    response_builder = response_builder.header("X-Proxy-Request-Id", &request_id);
+    response_builder = response_builder.header("Server-Timing", timing.server_timing_header());
+    response_builder = response_builder.header("X-Queue-Wait-Ms", queue_wait_ms.to_string());
     
     for (name, value) in response_headers.iter() {
         let header_name_lower = name.as_str().to_lowercase();
@@ -159,28 +268,132 @@ async fn proxy_handler(
 
     // Stream the response body instead of buffering. This enables real-time streaming
     // for LLM responses (e.g. OpenAI/Anthropic streaming APIs).
-    let body = axum::body::Body::from_stream(response.bytes_stream());
+    // The body transfer time is only known once the stream ends, so it goes to the
+    // request log rather than the response headers.
+    let app_handle = state.app_handle.clone();
+    let finish_log = async move {
+        let timing = timer.summary(Some(std::time::Instant::now()));
+        let update = json!({
+            "timing_json": serde_json::to_string(&timing).ok(),
+            "ended_at": now_ms(),
+        });
+        let db_state: tauri::State<DbState> = app_handle.state();
+        let _ = db_state
+            .write(move |conn| {
+                crate::database::db_update(conn, "proxy_requests", json!({ "where": { "id": request_id } }), update)
+            })
+            .await;
+        // Release the concurrency slots only once the response has been fully received.
+        drop(admission);
+    };
+
+    let body = match primary_record {
+        Some(mut record) => {
+            response_builder = response_builder.header(crate::shadow::SHADOW_GROUP_HEADER, &record.group_id);
+            record.status_code = Some(response_status.as_u16());
+            record.latency_ms = latency_ms;
+            axum::body::Body::from_stream(on_stream_end(
+                crate::shadow::capture_primary(BodyDataStream::new(response.into_body()), state.app_handle.clone(), record),
+                finish_log,
+            ))
+        }
+        None => axum::body::Body::from_stream(on_stream_end(BodyDataStream::new(response.into_body()), finish_log)),
+    };
     Ok(response_builder.body(body).unwrap())
 }
 
+/// Run `on_end` once `stream` has been fully consumed.
+fn on_stream_end<S>(stream: S, on_end: impl Future<Output = ()> + Send + 'static) -> impl Stream<Item = S::Item>
+where
+    S: Stream,
+{
+    let end = futures_util::stream::once(async move {
+        on_end.await;
+        None::<S::Item>
+    })
+    .filter_map(futures_util::future::ready);
+    stream.chain(end)
+}
+
+async fn write_request_log(app_handle: &AppHandle, entry: Value) {
+    let db_state: tauri::State<DbState> = app_handle.state();
+    let result = db_state
+        .write(move |conn| crate::database::db_insert(conn, "proxy_requests", entry))
+        .await;
+    if let Err(e) = result {
+        log::error!("Failed to write proxy request log: {}", e);
+    }
+}
+
+/// `body` with the placeholders of secret env variables filled in. Prompts keep the
+/// placeholders everywhere else (webview, stored runs, shadow records).
+pub(crate) async fn fill_secrets(app_handle: &AppHandle, body: Bytes) -> Result<Bytes, String> {
+    let db_state = app_handle.state::<DbState>().inner().clone();
+    let secrets = app_handle.state::<crate::secrets::SecretsState>().inner().clone();
+    let input = body.clone();
+    let filled = db_state
+        .read(move |conn| secrets.fill_placeholders(conn, &input))
+        .await
+        .map_err(|e| format!("Failed to fill secret placeholders: {}", e))?;
+    Ok(filled.map_or(body, Bytes::from))
+}
+
+/// Look up the stored API key for a provider (None when there is none).
+pub(crate) async fn lookup_api_key(app_handle: &AppHandle, provider: &str) -> Result<Option<String>, String> {
+    let db_state: tauri::State<DbState> = app_handle.state();
+    let secrets = app_handle.state::<crate::secrets::SecretsState>().inner().clone();
+    let provider = provider.to_string();
+    db_state.read(move |conn| secrets.decrypt_api_key(conn, &provider)).await
+}
+
+/// Header carrying the API key, using a Bearer token for "Authorization".
+fn api_key_header(api_auth_header_name: &str, api_key: &str) -> (String, String) {
+    if api_auth_header_name.eq_ignore_ascii_case("Authorization") {
+        ("Authorization".to_string(), format!("Bearer {}", api_key))
+    } else { // All other headers are set directly
+        (api_auth_header_name.to_string(), api_key.to_string())
+    }
+}
+
+/// Set the API key on an upstream request (see `api_key_header`).
+pub(crate) fn apply_api_key(
+    request_builder: reqwest::RequestBuilder,
+    api_auth_header_name: &str,
+    api_key: &str,
+) -> reqwest::RequestBuilder {
+    let (name, value) = api_key_header(api_auth_header_name, api_key);
+    request_builder.header(name, value)
+}
+
 async fn hello_world() -> &'static str {
     "Hello from proxy server!"
 }
 
+/// Origins the Tauri webview loads from (macOS/Linux, Windows/Android, dev server).
+const WEBVIEW_ORIGINS: &[&str] = &[
+    "tauri://localhost",
+    "http://tauri.localhost",
+    "https://tauri.localhost",
+    "http://localhost:1420",
+];
+
 pub async fn start_proxy_server(app_handle: AppHandle) {
     let client = Client::builder()
         .user_agent("reticle-proxy/1.0")
         .build()
         .expect("Failed to create HTTP client");
+    let upstream = hyper_util::client::legacy::Client::builder(TokioExecutor::new())
+        .build(crate::timing::timed_connector());
     
     let state = Arc::new(ProxyState {
         client,
+        upstream,
         app_handle,
     });
 
     let cors = CorsLayer::new()
         .allow_methods(Any)
-        .allow_origin(Any)
+        .allow_origin(AllowOrigin::list(WEBVIEW_ORIGINS.iter().map(|o| HeaderValue::from_static(o))))
         .allow_headers(Any)
         .expose_headers(Any);
 
@@ -190,7 +403,7 @@ pub async fn start_proxy_server(app_handle: AppHandle) {
         .with_state(state)
         .layer(cors);
 
-    let addr = SocketAddr::from(([0, 0, 0, 0], 11513));
+    let addr = SocketAddr::from(([127, 0, 0, 1], 11513));
     let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
     axum::serve(listener, app).await.unwrap();
 }
\ No newline at end of file
@@ -0,0 +1,35 @@
+use anyhow::Result as AnyhowResult;
+use rusqlite::Connection;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::json;
+
+// ── Backend settings ─────────────────────────────────────────────────────────
+//
+// Policies and other backend state live in the `settings` key/value table, one JSON
+// document per key.
+
+/// The value stored under `key`; None when unset or not a valid `T`.
+pub fn get_json<T: DeserializeOwned>(conn: &Connection, key: &str) -> Option<T> {
+    crate::database::db_select(conn, "settings", json!({ "where": { "key": key }, "limit": 1 }))
+        .ok()?
+        .pop()?
+        .get("value")?
+        .as_str()
+        .and_then(|value| serde_json::from_str(value).ok())
+}
+
+/// Store `value` under `key`, replacing what was there.
+pub fn set_json<T: Serialize>(conn: &Connection, key: &str, value: &T) -> AnyhowResult<()> {
+    let value = serde_json::to_string(value)?;
+    let updated = crate::database::db_update(
+        conn,
+        "settings",
+        json!({ "where": { "key": key } }),
+        json!({ "value": value }),
+    )?;
+    if updated == 0 {
+        crate::database::db_insert(conn, "settings", json!({ "key": key, "value": value }))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,307 @@
+use std::sync::{Arc, Mutex};
+
+use axum::body::Bytes;
+use futures_util::{Stream, StreamExt};
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use tauri::{AppHandle, Manager};
+
+use crate::pool::DbState;
+use crate::providers::{find_provider, rewrite_request_body};
+
+// ── Shadow traffic ───────────────────────────────────────────────────────────
+//
+// A proxied request can be mirrored to extra provider/model targets ("shadows").
+// The caller only ever sees the primary response; the primary and every shadow
+// response are stored in `shadow_responses` under one `group_id` for comparison.
+
+/// Request header carrying a JSON array of targets; overrides the settings entry.
+pub const SHADOW_TARGETS_HEADER: &str = "x-shadow-targets";
+/// Response header returned to the caller so it can look up the comparison rows.
+pub const SHADOW_GROUP_HEADER: &str = "X-Shadow-Group-Id";
+/// `settings.key` holding the persistent shadow configuration (JSON array of targets).
+const SHADOW_TARGETS_SETTING: &str = "shadow_targets";
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct ShadowTarget {
+    pub provider: String,
+    pub model: String,
+    /// Only mirror requests sent to this provider (any when omitted).
+    #[serde(default)]
+    pub match_provider: Option<String>,
+    /// Only mirror requests for this model (any when omitted).
+    #[serde(default)]
+    pub match_model: Option<String>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl ShadowTarget {
+    fn matches(&self, provider: &str, model: &str) -> bool {
+        self.enabled
+            && self.match_provider.as_deref().is_none_or(|p| p == provider)
+            && self.match_model.as_deref().is_none_or(|m| m == model)
+            // Mirroring a request onto itself would only duplicate the primary.
+            && !(self.provider == provider && self.model == model)
+    }
+}
+
+/// Resolve the shadow targets for a primary request: the request header wins,
+/// otherwise the `shadow_targets` setting is used.
+pub async fn resolve_targets(
+    app_handle: &AppHandle,
+    header_value: Option<&str>,
+    provider: &str,
+    model: &str,
+) -> Vec<ShadowTarget> {
+    let raw = match header_value {
+        Some(value) => Some(value.to_string()),
+        None => read_targets_setting(app_handle).await,
+    };
+
+    let targets: Vec<ShadowTarget> = raw
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default();
+
+    targets
+        .into_iter()
+        .filter(|t| t.matches(provider, model))
+        .collect()
+}
+
+async fn read_targets_setting(app_handle: &AppHandle) -> Option<String> {
+    let db_state: tauri::State<DbState> = app_handle.state();
+    let mut rows = db_state
+        .read(|conn| {
+            crate::database::db_select(conn, "settings", json!({
+                "where": { "key": SHADOW_TARGETS_SETTING },
+                "limit": 1
+            }))
+        })
+        .await
+        .ok()?;
+    rows.pop()?.get("value")?.as_str().map(String::from)
+}
+
+/// One stored response (primary or shadow) belonging to a shadow group.
+pub struct ShadowRecord {
+    pub group_id: String,
+    pub role: &'static str,
+    pub provider: String,
+    pub model: String,
+    pub request: Value,
+    pub status_code: Option<u16>,
+    pub body: Option<String>,
+    pub latency_ms: u64,
+    pub error: Option<String>,
+}
+
+impl ShadowRecord {
+    async fn store(self, app_handle: &AppHandle) {
+        let (output_text, usage) = self
+            .body
+            .as_deref()
+            .map(summarize_body)
+            .unwrap_or((None, None));
+
+        let row = json!({
+            "group_id": self.group_id,
+            "role": self.role,
+            "provider": self.provider,
+            "model": self.model,
+            "request_json": self.request.to_string(),
+            "status_code": self.status_code,
+            "response_body": self.body,
+            "output_text": output_text,
+            "usage_json": usage.map(|u| u.to_string()),
+            "latency_ms": self.latency_ms,
+            "error": self.error,
+        });
+        let db_state: tauri::State<DbState> = app_handle.state();
+        let result = db_state
+            .write(move |conn| crate::database::db_insert(conn, "shadow_responses", row))
+            .await;
+        if let Err(e) = result {
+            log::error!("Failed to store shadow response: {}", e);
+        }
+    }
+}
+
+/// Extract the assistant text and usage from a chat completion body,
+/// either a single JSON document or an SSE stream of chunks.
+fn summarize_body(body: &str) -> (Option<String>, Option<Value>) {
+    if let Ok(doc) = serde_json::from_str::<Value>(body) {
+        let text = doc
+            .pointer("/choices/0/message/content")
+            .and_then(|v| v.as_str())
+            .map(String::from);
+        return (text, doc.get("usage").filter(|u| !u.is_null()).cloned());
+    }
+
+    let mut text = String::new();
+    let mut usage = None;
+    for line in body.lines() {
+        let Some(data) = line.strip_prefix("data:").map(str::trim) else {
+            continue;
+        };
+        let Ok(chunk) = serde_json::from_str::<Value>(data) else {
+            continue; // e.g. "[DONE]"
+        };
+        if let Some(delta) = chunk.pointer("/choices/0/delta/content").and_then(|v| v.as_str()) {
+            text.push_str(delta);
+        }
+        if let Some(u) = chunk.get("usage").filter(|u| !u.is_null()) {
+            usage = Some(u.clone());
+        }
+    }
+    ((!text.is_empty()).then_some(text), usage)
+}
+
+/// Fire one request per shadow target in the background.
+///
+/// `path_query` is the incoming path of the primary request; the primary provider's
+/// API prefix is swapped for the shadow provider's (e.g. `/v1` -> `/v1beta/openai`).
+pub fn spawn_shadows(
+    client: &Client,
+    app_handle: &AppHandle,
+    group_id: &str,
+    primary_provider: &str,
+    path_query: &str,
+    body: &Map<String, Value>,
+    targets: Vec<ShadowTarget>,
+) {
+    let rest = find_provider(primary_provider)
+        .and_then(|p| path_query.strip_prefix(p.api_path))
+        .unwrap_or(path_query)
+        .to_string();
+
+    for target in targets {
+        let Some(provider) = find_provider(&target.provider) else {
+            let record = ShadowRecord {
+                group_id: group_id.to_string(),
+                role: "shadow",
+                error: Some(format!("Unknown shadow provider '{}'", target.provider)),
+                provider: target.provider,
+                model: target.model,
+                request: Value::Object(body.clone()),
+                status_code: None,
+                body: None,
+                latency_ms: 0,
+            };
+            let app_handle = app_handle.clone();
+            tauri::async_runtime::spawn(async move { record.store(&app_handle).await });
+            continue;
+        };
+
+        let mut shadow_body = body.clone();
+        rewrite_request_body(&mut shadow_body, provider.id, &target.model);
+
+        let url = format!("{}{}{}", provider.base_url, provider.api_path, rest);
+        let mut request_builder = client.post(&url).header("Content-Type", "application/json");
+        for (name, value) in provider.extra_headers {
+            request_builder = request_builder.header(*name, *value);
+        }
+
+        let app_handle = app_handle.clone();
+        let group_id = group_id.to_string();
+        let body_len = serde_json::to_string(&shadow_body).map_or(0, |s| s.len());
+        let tokens = crate::governor::estimate_tokens(Some(&shadow_body), body_len);
+        tauri::async_runtime::spawn(async move {
+            let body = serde_json::to_vec(&shadow_body).unwrap_or_default();
+            let mut record = ShadowRecord {
+                group_id,
+                role: "shadow",
+                provider: target.provider,
+                model: target.model,
+                request: Value::Object(shadow_body),
+                status_code: None,
+                body: None,
+                latency_ms: 0,
+                error: None,
+            };
+
+            let prepared = async {
+                let body = crate::server::fill_secrets(&app_handle, body.into()).await?;
+                let api_key = crate::server::lookup_api_key(&app_handle, provider.id).await?;
+                Ok::<_, String>((body, api_key))
+            }
+            .await;
+            let (body, api_key) = match prepared {
+                Ok(prepared) => prepared,
+                Err(e) => {
+                    record.error = Some(e);
+                    record.store(&app_handle).await;
+                    return;
+                }
+            };
+            let mut request_builder = request_builder.body(body);
+            if let Some(api_key) = &api_key {
+                request_builder = crate::server::apply_api_key(request_builder, provider.auth_header, api_key);
+            }
+            let governor = app_handle.state::<crate::governor::GovernorState>().inner().clone();
+            let _admission = governor.admit(provider.id, Some(&record.model), tokens).await;
+
+            let start_time = std::time::Instant::now();
+
+            match request_builder.send().await {
+                Ok(response) => {
+                    crate::ratelimits::observe(
+                        &app_handle,
+                        &record.provider,
+                        api_key.as_deref(),
+                        Some(&record.model),
+                        response.headers(),
+                    );
+                    record.status_code = Some(response.status().as_u16());
+                    match response.text().await {
+                        Ok(text) => record.body = Some(text),
+                        Err(e) => record.error = Some(e.to_string()),
+                    }
+                }
+                Err(e) => record.error = Some(e.to_string()),
+            }
+            record.latency_ms = start_time.elapsed().as_millis() as u64;
+            record.store(&app_handle).await;
+        });
+    }
+}
+
+/// Pass the primary response stream through unchanged while keeping a copy,
+/// and store it once the stream has been fully delivered to the caller.
+pub fn capture_primary<S, E>(
+    stream: S,
+    app_handle: AppHandle,
+    mut record: ShadowRecord,
+) -> impl Stream<Item = Result<Bytes, E>>
+where
+    S: Stream<Item = Result<Bytes, E>>,
+    E: std::fmt::Display,
+{
+    let captured: Arc<Mutex<(Vec<u8>, Option<String>)>> = Arc::default();
+    let sink = captured.clone();
+
+    let passthrough = stream.map(move |chunk| {
+        let mut sink = sink.lock().unwrap();
+        match &chunk {
+            Ok(bytes) => sink.0.extend_from_slice(bytes),
+            Err(e) => sink.1 = Some(e.to_string()),
+        }
+        chunk
+    });
+
+    let finish = futures_util::stream::once(async move {
+        let (bytes, error) = std::mem::take(&mut *captured.lock().unwrap());
+        record.body = Some(String::from_utf8_lossy(&bytes).into_owned());
+        record.error = error;
+        record.store(&app_handle).await;
+        None::<Result<Bytes, E>>
+    })
+    .filter_map(futures_util::future::ready);
+
+    passthrough.chain(finish)
+}
@@ -0,0 +1,262 @@
+use std::convert::Infallible;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Instant;
+
+use axum::body::Bytes;
+use http::Uri;
+use http_body::{Frame, SizeHint};
+use hyper_tls::HttpsConnector;
+use hyper_util::client::legacy::connect::dns::Name;
+use hyper_util::client::legacy::connect::HttpConnector;
+use serde::Serialize;
+
+// ── Connection timing ────────────────────────────────────────────────────────
+//
+// The proxy sends upstream requests through a hyper client whose connector is put
+// together here: a timing DNS resolver inside an `HttpConnector`, wrapped in a timing
+// connector (TCP done), inside the TLS connector, wrapped in a second timing connector
+// (TLS done). All of them record into the marks of the request currently being sent,
+// found through a task-local that `RequestTimer::scope` sets around the request. A
+// request that never resolves or connects was served by a pooled (reused) connection.
+
+tokio::task_local! {
+    static CURRENT: Arc<Mutex<Marks>>;
+}
+
+#[derive(Default)]
+struct Marks {
+    dns: Option<(Instant, Instant)>,
+    tcp: Option<(Instant, Instant)>,
+    /// End of the TLS handshake (https only)
+    tls_done: Option<Instant>,
+    body_sent: Option<Instant>,
+}
+
+fn record(f: impl FnOnce(&mut Marks)) {
+    // Outside a scope (e.g. a connect finishing in the pool's background task) there is
+    // no request to attribute the timing to.
+    let _ = CURRENT.try_with(|marks| f(&mut marks.lock().unwrap()));
+}
+
+/// Breakdown of one upstream call, in milliseconds from the start of the request.
+#[derive(Serialize, Clone, Debug, Default)]
+pub struct ConnectionTiming {
+    /// True when no new connection was opened (served from the pool).
+    pub reused: bool,
+    pub dns_ms: Option<u64>,
+    /// TCP connect.
+    pub connect_ms: Option<u64>,
+    /// TLS handshake (https only).
+    pub tls_ms: Option<u64>,
+    /// From connection ready to the request body handed to the connection.
+    pub send_ms: Option<u64>,
+    /// From request start to response headers.
+    pub ttfb_ms: u64,
+    /// From response headers to the end of the response body.
+    pub body_ms: Option<u64>,
+    pub total_ms: Option<u64>,
+}
+
+impl ConnectionTiming {
+    /// `Server-Timing` header value, e.g. `dns;dur=3, connect;dur=12, tls;dur=29, send;dur=0, ttfb;dur=812`.
+    pub fn server_timing_header(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(dns) = self.dns_ms {
+            parts.push(format!("dns;dur={}", dns));
+        }
+        if let Some(connect) = self.connect_ms {
+            parts.push(format!("connect;dur={}", connect));
+        }
+        if let Some(tls) = self.tls_ms {
+            parts.push(format!("tls;dur={}", tls));
+        }
+        if let Some(send) = self.send_ms {
+            parts.push(format!("send;dur={}", send));
+        }
+        parts.push(format!("ttfb;dur={}", self.ttfb_ms));
+        parts.push(format!("reused;desc=\"{}\"", self.reused));
+        parts.join(", ")
+    }
+}
+
+/// Collects the phases of a single upstream request.
+#[derive(Clone)]
+pub struct RequestTimer {
+    started: Instant,
+    headers_at: Arc<Mutex<Option<Instant>>>,
+    marks: Arc<Mutex<Marks>>,
+}
+
+impl RequestTimer {
+    pub fn start() -> Self {
+        Self {
+            started: Instant::now(),
+            headers_at: Arc::default(),
+            marks: Arc::default(),
+        }
+    }
+
+    pub fn started(&self) -> Instant {
+        self.started
+    }
+
+    /// Run the request future with this timer as the recording target.
+    pub async fn scope<F: Future>(&self, future: F) -> F::Output {
+        let output = CURRENT.scope(self.marks.clone(), future).await;
+        *self.headers_at.lock().unwrap() = Some(Instant::now());
+        output
+    }
+
+    /// Request body that notes when it was handed to the connection.
+    pub fn body(&self, bytes: Bytes) -> TimedBody {
+        TimedBody {
+            data: Some(bytes).filter(|bytes| !bytes.is_empty()),
+            marks: self.marks.clone(),
+        }
+    }
+
+    /// Timing summary; `finished` is the end of the response body when known.
+    pub fn summary(&self, finished: Option<Instant>) -> ConnectionTiming {
+        let marks = self.marks.lock().unwrap();
+        let headers_at = self.headers_at.lock().unwrap().unwrap_or_else(Instant::now);
+        let ms = |from: Instant, to: Instant| to.saturating_duration_since(from).as_millis() as u64;
+
+        let dns_ms = marks.dns.map(|(start, end)| ms(start, end));
+        // The connector resolves DNS itself, so its span includes the lookup.
+        let connect_ms = marks
+            .tcp
+            .map(|(start, end)| ms(start, end).saturating_sub(dns_ms.unwrap_or(0)));
+        let tls_ms = marks.tcp.zip(marks.tls_done).map(|((_, tcp_end), done)| ms(tcp_end, done));
+        let ready_at = marks.tls_done.or(marks.tcp.map(|(_, end)| end)).unwrap_or(self.started);
+
+        ConnectionTiming {
+            reused: marks.tcp.is_none(),
+            dns_ms,
+            connect_ms,
+            tls_ms,
+            send_ms: marks.body_sent.map(|sent| ms(ready_at, sent)),
+            ttfb_ms: ms(self.started, headers_at),
+            body_ms: finished.map(|end| ms(headers_at, end)),
+            total_ms: finished.map(|end| ms(self.started, end)),
+        }
+    }
+}
+
+/// Request body of the proxy's upstream client (see `RequestTimer::body`).
+pub struct TimedBody {
+    data: Option<Bytes>,
+    marks: Arc<Mutex<Marks>>,
+}
+
+impl http_body::Body for TimedBody {
+    type Data = Bytes;
+    type Error = Infallible;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Bytes>, Infallible>>> {
+        let this = self.get_mut();
+        match this.data.take() {
+            Some(data) => {
+                this.marks.lock().unwrap().body_sent = Some(Instant::now());
+                Poll::Ready(Some(Ok(Frame::data(data))))
+            }
+            None => Poll::Ready(None),
+        }
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.data.is_none()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::with_exact(self.data.as_ref().map_or(0, |d| d.len() as u64))
+    }
+}
+
+/// DNS resolver that records lookup time for the current request.
+#[derive(Clone)]
+pub struct TimingResolver;
+
+impl tower_service::Service<Name> for TimingResolver {
+    type Response = std::vec::IntoIter<SocketAddr>;
+    type Error = std::io::Error;
+    type Future = Pin<Box<dyn Future<Output = std::io::Result<Self::Response>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, name: Name) -> Self::Future {
+        let host = name.as_str().to_string();
+        Box::pin(async move {
+            let started = Instant::now();
+            let addrs: Vec<_> = tokio::net::lookup_host((host.as_str(), 0)).await?.collect();
+            record(|marks| marks.dns = Some((started, Instant::now())));
+            Ok(addrs.into_iter())
+        })
+    }
+}
+
+/// Which step of establishing a connection a `TimingConnector` wraps.
+#[derive(Clone, Copy)]
+enum Phase {
+    Tcp,
+    Tls,
+}
+
+/// Connector that records when the connector it wraps is done.
+#[derive(Clone)]
+pub struct TimingConnector<S> {
+    inner: S,
+    phase: Phase,
+}
+
+impl<S> tower_service::Service<Uri> for TimingConnector<S>
+where
+    S: tower_service::Service<Uri>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<S::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, uri: Uri) -> Self::Future {
+        let phase = self.phase;
+        let https = uri.scheme_str() == Some("https");
+        let connecting = self.inner.call(uri);
+        Box::pin(async move {
+            let started = Instant::now();
+            let conn = connecting.await;
+            if conn.is_ok() {
+                let done = Instant::now();
+                record(|marks| match phase {
+                    Phase::Tcp => marks.tcp = Some((started, done)),
+                    Phase::Tls if https => marks.tls_done = Some(done),
+                    Phase::Tls => {}
+                });
+            }
+            conn
+        })
+    }
+}
+
+/// Connector of the proxy's upstream client: DNS, TCP and TLS each timed.
+pub type TimedConnector = TimingConnector<HttpsConnector<TimingConnector<HttpConnector<TimingResolver>>>>;
+
+pub fn timed_connector() -> TimedConnector {
+    let mut http = HttpConnector::new_with_resolver(TimingResolver);
+    // The TLS connector handles https itself
+    http.enforce_http(false);
+    let tcp = TimingConnector { inner: http, phase: Phase::Tcp };
+    TimingConnector { inner: HttpsConnector::new_with_connector(tcp), phase: Phase::Tls }
+}
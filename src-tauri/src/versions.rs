@@ -0,0 +1,373 @@
+use std::collections::{BTreeSet, HashSet};
+
+use anyhow::{anyhow, Result as AnyhowResult};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use tauri::AppHandle;
+
+use crate::database::{ChangeOp, DbChange};
+use crate::pool::DbState;
+
+// ── Version history ──────────────────────────────────────────────────────────
+//
+// Snapshots are written by triggers whenever `version` changes on a scenario or
+// agent (see migrations/0025_create_version_history.sql): `<kind>_versions` keeps
+// the row and its linked tools as they were at the old version, and the live row
+// is the current version.
+
+/// Columns that describe the row rather than its content; not diffed or restored.
+const META_COLUMNS: &[&str] = &["id", "version", "created_at", "updated_at", "archived_at"];
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RunnableType {
+    Scenario,
+    Agent,
+}
+
+impl RunnableType {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            RunnableType::Scenario => "scenario",
+            RunnableType::Agent => "agent",
+        }
+    }
+
+    pub(crate) fn table(self) -> &'static str {
+        match self {
+            RunnableType::Scenario => "scenarios",
+            RunnableType::Agent => "agents",
+        }
+    }
+
+    fn versions_table(self) -> &'static str {
+        match self {
+            RunnableType::Scenario => "scenario_versions",
+            RunnableType::Agent => "agent_versions",
+        }
+    }
+
+    fn id_column(self) -> &'static str {
+        match self {
+            RunnableType::Scenario => "scenario_id",
+            RunnableType::Agent => "agent_id",
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct RunnableVersion {
+    pub version: i64,
+    /// The live row rather than a snapshot
+    pub current: bool,
+    pub snapshot: Map<String, Value>,
+    /// Linked `tools` rows
+    pub tools: Vec<Value>,
+    /// unix ms; when the version was superseded (last update for the current one)
+    pub created_at: i64,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct FieldChange {
+    /// Column name, or "tools" for the linked tools
+    pub field: String,
+    pub from: Value,
+    pub to: Value,
+}
+
+fn live_row(conn: &Connection, kind: RunnableType, id: &str) -> AnyhowResult<Map<String, Value>> {
+    crate::database::db_select(conn, kind.table(), json!({ "where": { "id": id }, "limit": 1 }))?
+        .pop()
+        .ok_or_else(|| anyhow!("{} '{}' not found", kind.as_str(), id))
+}
+
+fn linked_tools(conn: &Connection, kind: RunnableType, id: &str) -> AnyhowResult<Vec<Value>> {
+    let tool_ids: Vec<Value> = crate::database::db_select(conn, "tool_links", json!({
+        "where": { "toolable_type": kind.as_str(), "toolable_id": id },
+        "columns": ["tool_id"]
+    }))?
+    .into_iter()
+    .filter_map(|mut link| link.remove("tool_id"))
+    .collect();
+    let tools = crate::database::db_select(conn, "tools", json!({
+        "where": { "id": { "in": tool_ids } },
+        "orderBy": "sort_order",
+        "orderDirection": "asc"
+    }))?;
+    Ok(tools.into_iter().map(Value::Object).collect())
+}
+
+fn current_version(conn: &Connection, kind: RunnableType, id: &str) -> AnyhowResult<RunnableVersion> {
+    let snapshot = live_row(conn, kind, id)?;
+    Ok(RunnableVersion {
+        version: snapshot.get("version").and_then(|v| v.as_i64()).unwrap_or(1),
+        current: true,
+        tools: linked_tools(conn, kind, id)?,
+        created_at: snapshot.get("updated_at").and_then(|v| v.as_i64()).unwrap_or(0),
+        snapshot,
+    })
+}
+
+fn stored_version(row: Map<String, Value>) -> AnyhowResult<RunnableVersion> {
+    let parse = |column: &str| -> AnyhowResult<Value> {
+        let text = row.get(column).and_then(|v| v.as_str()).unwrap_or("null");
+        Ok(serde_json::from_str(text)?)
+    };
+    let Value::Object(snapshot) = parse("snapshot_json")? else {
+        return Err(anyhow!("Version snapshot is not an object"));
+    };
+    let tools = match parse("tools_json")? {
+        Value::Array(tools) => tools,
+        _ => Vec::new(),
+    };
+    Ok(RunnableVersion {
+        version: row.get("version").and_then(|v| v.as_i64()).unwrap_or(0),
+        current: false,
+        snapshot,
+        tools,
+        created_at: row.get("created_at").and_then(|v| v.as_i64()).unwrap_or(0),
+    })
+}
+
+/// All versions of a scenario or agent, newest (the live row) first.
+pub fn list_versions(conn: &Connection, kind: RunnableType, id: &str) -> AnyhowResult<Vec<RunnableVersion>> {
+    let mut versions = vec![current_version(conn, kind, id)?];
+    let rows = crate::database::db_select(conn, kind.versions_table(), json!({
+        "where": { kind.id_column(): id },
+        "orderBy": "version",
+        "orderDirection": "desc"
+    }))?;
+    for row in rows {
+        versions.push(stored_version(row)?);
+    }
+    Ok(versions)
+}
+
+pub fn get_version(conn: &Connection, kind: RunnableType, id: &str, version: i64) -> AnyhowResult<RunnableVersion> {
+    let current = current_version(conn, kind, id)?;
+    if current.version == version {
+        return Ok(current);
+    }
+    crate::database::db_select(conn, kind.versions_table(), json!({
+        "where": { kind.id_column(): id, "version": version },
+        "limit": 1
+    }))?
+    .pop()
+    .map(stored_version)
+    .unwrap_or_else(|| Err(anyhow!("Version {} of {} '{}' not found", version, kind.as_str(), id)))
+}
+
+/// Tool rows compared by content, in id order; timestamps change whenever a tool is saved.
+fn tools_content(tools: &[Value]) -> Vec<Value> {
+    let mut tools: Vec<Value> = tools
+        .iter()
+        .map(|tool| {
+            let mut tool = tool.clone();
+            if let Some(tool) = tool.as_object_mut() {
+                tool.remove("created_at");
+                tool.remove("updated_at");
+            }
+            tool
+        })
+        .collect();
+    tools.sort_by(|a, b| a["id"].as_str().cmp(&b["id"].as_str()));
+    tools
+}
+
+/// Fields that differ between two versions (content columns, then "tools").
+pub fn diff_versions(
+    conn: &Connection,
+    kind: RunnableType,
+    id: &str,
+    from: i64,
+    to: i64,
+) -> AnyhowResult<Vec<FieldChange>> {
+    let from = get_version(conn, kind, id, from)?;
+    let to = get_version(conn, kind, id, to)?;
+
+    let fields: BTreeSet<&String> = from.snapshot.keys().chain(to.snapshot.keys()).collect();
+    let mut changes: Vec<FieldChange> = fields
+        .into_iter()
+        .filter(|field| !META_COLUMNS.contains(&field.as_str()))
+        .filter_map(|field| {
+            let before = from.snapshot.get(field).cloned().unwrap_or(Value::Null);
+            let after = to.snapshot.get(field).cloned().unwrap_or(Value::Null);
+            (before != after).then(|| FieldChange { field: field.clone(), from: before, to: after })
+        })
+        .collect();
+
+    let (before, after) = (tools_content(&from.tools), tools_content(&to.tools));
+    if before != after {
+        changes.push(FieldChange { field: "tools".to_string(), from: json!(before), to: json!(after) });
+    }
+    Ok(changes)
+}
+
+/// Keep the current content as a version and continue under the next version number.
+/// Returns the new version.
+pub fn create_version(conn: &Connection, kind: RunnableType, id: &str) -> AnyhowResult<i64> {
+    let version = current_version(conn, kind, id)?.version + 1;
+    crate::database::db_update(conn, kind.table(), json!({ "where": { "id": id } }), json!({ "version": version }))?;
+    Ok(version)
+}
+
+/// Bring back the content and tools of an old version as a new version (the content
+/// being replaced is kept as a version too). Tools shared across runnables
+/// (`is_global`) are relinked but their content is left as it is now.
+/// Returns the new version.
+pub fn restore_version(conn: &mut Connection, kind: RunnableType, id: &str, version: i64) -> AnyhowResult<i64> {
+    let target = get_version(conn, kind, id, version)?;
+    if target.current {
+        return Err(anyhow!("Version {} is already the current version", version));
+    }
+
+    let tx = conn.transaction()?;
+    let new_version = current_version(&tx, kind, id)?.version + 1;
+
+    let columns: HashSet<String> = {
+        let mut stmt = tx.prepare("SELECT name FROM pragma_table_info(?1)")?;
+        let columns = stmt.query_map([kind.table()], |row| row.get(0))?.collect::<Result<_, _>>()?;
+        columns
+    };
+    let mut data: Map<String, Value> = target
+        .snapshot
+        .into_iter()
+        .filter(|(column, _)| columns.contains(column) && !META_COLUMNS.contains(&column.as_str()))
+        .collect();
+    data.insert("version".to_string(), json!(new_version));
+    crate::database::db_update(&tx, kind.table(), json!({ "where": { "id": id } }), Value::Object(data))?;
+
+    let mut tool_ids = Vec::new();
+    for tool in target.tools {
+        let Value::Object(mut tool) = tool else { continue };
+        let Some(tool_id) = tool.get("id").and_then(|v| v.as_str()).map(String::from) else {
+            continue;
+        };
+        let exists = crate::database::db_count(&tx, "tools", json!({ "where": { "id": tool_id } }))? > 0;
+        if !exists {
+            crate::database::db_insert(&tx, "tools", Value::Object(tool))?;
+        } else if tool.get("is_global").and_then(|v| v.as_i64()) != Some(1) {
+            for column in ["id", "created_at", "updated_at"] {
+                tool.remove(column);
+            }
+            crate::database::db_update(&tx, "tools", json!({ "where": { "id": tool_id } }), Value::Object(tool))?;
+        }
+        tool_ids.push(tool_id);
+    }
+
+    crate::database::db_delete(&tx, "tool_links", json!({
+        "where": { "toolable_type": kind.as_str(), "toolable_id": id, "tool_id": { "not_in": tool_ids } }
+    }))?;
+    for tool_id in &tool_ids {
+        let link = json!({ "tool_id": tool_id, "toolable_type": kind.as_str(), "toolable_id": id });
+        if crate::database::db_count(&tx, "tool_links", json!({ "where": link }))? == 0 {
+            crate::database::db_insert(&tx, "tool_links", link)?;
+        }
+    }
+
+    tx.commit()?;
+    Ok(new_version)
+}
+
+/// Rows a new version touched, for `DB_CHANGE_EVENT`: the live row, the snapshot of the
+/// `superseded` version and, with `with_tools`, the runnable's tools and tool links.
+fn version_changes(
+    conn: &Connection,
+    kind: RunnableType,
+    id: &str,
+    superseded: i64,
+    with_tools: bool,
+) -> AnyhowResult<Vec<DbChange>> {
+    let ids = |rows: Vec<Map<String, Value>>, column: &str| -> Vec<String> {
+        rows.iter().filter_map(|row| row.get(column).and_then(|v| v.as_str()).map(String::from)).collect()
+    };
+    let snapshots = crate::database::db_select(conn, kind.versions_table(), json!({
+        "where": { kind.id_column(): id, "version": superseded },
+        "columns": ["id"]
+    }))?;
+    let mut changes: Vec<DbChange> = [
+        DbChange::touched(kind.table(), ChangeOp::Update, vec![id.to_string()]),
+        DbChange::touched(kind.versions_table(), ChangeOp::Insert, ids(snapshots, "id")),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    if with_tools {
+        let links = crate::database::db_select(conn, "tool_links", json!({
+            "where": { "toolable_type": kind.as_str(), "toolable_id": id },
+            "columns": ["id", "tool_id"]
+        }))?;
+        changes.extend(DbChange::touched("tools", ChangeOp::Update, ids(links.clone(), "tool_id")));
+        changes.extend(DbChange::touched("tool_links", ChangeOp::Update, ids(links, "id")));
+    }
+    Ok(changes)
+}
+
+// ── Commands ─────────────────────────────────────────────────────────────────
+
+/// Versions of a scenario or agent, newest first; the first entry is the live row.
+///
+/// `runnable_type` – scenario | agent
+/// `runnable_id`   – id of the scenario or agent
+#[tauri::command]
+pub async fn list_versions_cmd(
+    runnable_type: RunnableType,
+    runnable_id: String,
+    state: tauri::State<'_, DbState>,
+) -> Result<Vec<RunnableVersion>, String> {
+    state.read(move |conn| list_versions(conn, runnable_type, &runnable_id)).await
+}
+
+/// Content fields (and linked tools) that changed between versions `from` and `to`.
+#[tauri::command]
+pub async fn diff_versions_cmd(
+    runnable_type: RunnableType,
+    runnable_id: String,
+    from: i64,
+    to: i64,
+    state: tauri::State<'_, DbState>,
+) -> Result<Vec<FieldChange>, String> {
+    state.read(move |conn| diff_versions(conn, runnable_type, &runnable_id, from, to)).await
+}
+
+/// Keep the current content as a version; returns the new current version.
+#[tauri::command]
+pub async fn create_version_cmd(
+    runnable_type: RunnableType,
+    runnable_id: String,
+    state: tauri::State<'_, DbState>,
+    app: AppHandle,
+) -> Result<i64, String> {
+    let (version, changes) = state
+        .write(move |conn| {
+            let version = create_version(conn, runnable_type, &runnable_id)?;
+            Ok((version, version_changes(conn, runnable_type, &runnable_id, version - 1, false)?))
+        })
+        .await?;
+    for change in changes {
+        crate::notify_change(&app, Some(change));
+    }
+    Ok(version)
+}
+
+/// Restore an old version as a new version; returns the new current version.
+#[tauri::command]
+pub async fn restore_version_cmd(
+    runnable_type: RunnableType,
+    runnable_id: String,
+    version: i64,
+    state: tauri::State<'_, DbState>,
+    app: AppHandle,
+) -> Result<i64, String> {
+    let (version, changes) = state
+        .write(move |conn| {
+            let restored = restore_version(conn, runnable_type, &runnable_id, version)?;
+            Ok((restored, version_changes(conn, runnable_type, &runnable_id, restored - 1, true)?))
+        })
+        .await?;
+    for change in changes {
+        crate::notify_change(&app, Some(change));
+    }
+    Ok(version)
+}
@@ -0,0 +1,339 @@
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use anyhow::{anyhow, Result as AnyhowResult};
+use rusqlite::{Connection, OpenFlags};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+use ulid::Ulid;
+
+use crate::database::now_ms;
+use crate::pool::DbState;
+
+// ── Workspaces ───────────────────────────────────────────────────────────────
+//
+// A workspace is a data directory with its own reticle.db (and so its own API keys,
+// environment variables and settings), backups folder and attachment blobs under
+// workspaces/<account_id>/blobs. The default workspace is the app data root itself,
+// where everything lived before workspaces existed; the others live in
+// workspace-data/<id>/. workspaces.json in the root lists them and names the active
+// one.
+//
+// Switching swaps the connections inside the pool, so everything holding DbState
+// (commands, the proxy server, background retention) moves to the new database.
+// Secrets stay encrypted with the per-install key in the root.
+
+pub const DEFAULT_WORKSPACE: &str = "default";
+const REGISTRY_FILE: &str = "workspaces.json";
+const WORKSPACE_DATA_DIR: &str = "workspace-data";
+const MAX_NAME_LEN: usize = 100;
+
+/// Event sent to every window after the active workspace changed (payload: Workspace).
+pub const WORKSPACE_EVENT: &str = "workspace-changed";
+
+/// Serializes read-modify-write of the registry file.
+static REGISTRY_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Workspace {
+    pub id: String,
+    pub name: String,
+    /// unix ms; 0 for the default workspace
+    pub created_at: i64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Registry {
+    pub active: String,
+    pub workspaces: Vec<Workspace>,
+}
+
+fn default_workspace() -> Workspace {
+    Workspace { id: DEFAULT_WORKSPACE.to_string(), name: "Default".to_string(), created_at: 0 }
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        Self { active: DEFAULT_WORKSPACE.to_string(), workspaces: vec![default_workspace()] }
+    }
+}
+
+impl Registry {
+    /// The registry in `root`; a missing file means only the default workspace.
+    pub fn load(root: &Path) -> AnyhowResult<Self> {
+        let path = root.join(REGISTRY_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let mut registry: Registry = serde_json::from_slice(&std::fs::read(&path)?)
+            .map_err(|e| anyhow!("{} is corrupt: {}", path.display(), e))?;
+        if !registry.workspaces.iter().any(|w| w.id == DEFAULT_WORKSPACE) {
+            registry.workspaces.insert(0, default_workspace());
+        }
+        if registry.get(&registry.active).is_err() {
+            registry.active = DEFAULT_WORKSPACE.to_string();
+        }
+        Ok(registry)
+    }
+
+    fn save(&self, root: &Path) -> AnyhowResult<()> {
+        std::fs::create_dir_all(root)?;
+        let path = root.join(REGISTRY_FILE);
+        let partial = path.with_extension("partial");
+        std::fs::write(&partial, serde_json::to_vec_pretty(self)?)?;
+        std::fs::rename(&partial, &path)?;
+        Ok(())
+    }
+
+    fn get(&self, id: &str) -> AnyhowResult<&Workspace> {
+        self.workspaces
+            .iter()
+            .find(|w| w.id == id)
+            .ok_or_else(|| anyhow!("Workspace not found: {}", id))
+    }
+}
+
+fn clean_name(name: &str) -> AnyhowResult<String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(anyhow!("Workspace name cannot be empty"));
+    }
+    if name.chars().count() > MAX_NAME_LEN {
+        return Err(anyhow!("Workspace name is longer than {} characters", MAX_NAME_LEN));
+    }
+    Ok(name.to_string())
+}
+
+/// Data directory of workspace `id`.
+pub fn data_dir(root: &Path, id: &str) -> PathBuf {
+    if id == DEFAULT_WORKSPACE {
+        root.to_path_buf()
+    } else {
+        root.join(WORKSPACE_DATA_DIR).join(id)
+    }
+}
+
+/// Data directory of the active workspace (database, backups and blobs).
+pub fn active_data_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    let root = crate::paths::app_data_root(app)?;
+    let registry = Registry::load(&root).map_err(|e| e.to_string())?;
+    Ok(data_dir(&root, &registry.active))
+}
+
+/// Reload the state read from the database at startup, after the database behind
+/// the pool was replaced (workspace switch, backup restore).
+pub fn reload_state(app: &AppHandle, conn: &Connection) -> AnyhowResult<()> {
+    let secrets = app.state::<crate::secrets::SecretsState>();
+    for table in ["api_keys", "env_variables"] {
+        secrets.reconcile(conn, table)?;
+    }
+    let governor = app.state::<crate::governor::GovernorState>();
+    governor.set_rules(crate::governor::stored_rules(conn));
+    Ok(())
+}
+
+/// Tell every window that the active workspace's data was replaced (`WORKSPACE_EVENT`).
+pub fn notify_replaced(app: &AppHandle) -> Result<(), String> {
+    let root = crate::paths::app_data_root(app)?;
+    let registry = Registry::load(&root).map_err(|e| e.to_string())?;
+    let workspace = registry.get(&registry.active).map_err(|e| e.to_string())?;
+    app.emit(WORKSPACE_EVENT, workspace).ok();
+    Ok(())
+}
+
+/// Add a workspace with an empty, migrated database.
+pub fn create(root: &Path, name: &str) -> AnyhowResult<Workspace> {
+    let workspace = Workspace { id: Ulid::new().to_string(), name: clean_name(name)?, created_at: now_ms() };
+    crate::database::open_migrated(&data_dir(root, &workspace.id))?;
+
+    let _lock = REGISTRY_LOCK.lock().unwrap();
+    let mut registry = Registry::load(root)?;
+    registry.workspaces.push(workspace.clone());
+    registry.save(root)?;
+    Ok(workspace)
+}
+
+pub fn rename(root: &Path, id: &str, name: &str) -> AnyhowResult<Workspace> {
+    let name = clean_name(name)?;
+    let _lock = REGISTRY_LOCK.lock().unwrap();
+    let mut registry = Registry::load(root)?;
+    let workspace = registry
+        .workspaces
+        .iter_mut()
+        .find(|w| w.id == id)
+        .ok_or_else(|| anyhow!("Workspace not found: {}", id))?;
+    workspace.name = name;
+    let workspace = workspace.clone();
+    registry.save(root)?;
+    Ok(workspace)
+}
+
+fn copy_dir(from: &Path, to: &Path) -> AnyhowResult<()> {
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            std::fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+/// Copy workspace `id` (database snapshot and blobs) into a new workspace. Attachment
+/// paths in the copy are moved to its own blob directory.
+pub fn duplicate(root: &Path, id: &str, name: Option<&str>) -> AnyhowResult<Workspace> {
+    let source = Registry::load(root)?.get(id)?.clone();
+    let name = match name {
+        Some(name) => clean_name(name)?,
+        None => clean_name(&format!("{} (copy)", source.name))?,
+    };
+    let workspace = Workspace { id: Ulid::new().to_string(), name, created_at: now_ms() };
+    let source_dir = data_dir(root, &source.id);
+    let target_dir = data_dir(root, &workspace.id);
+
+    let copied = (|| -> AnyhowResult<()> {
+        // A separate connection still reads a consistent snapshot, WAL included
+        let source_conn = Connection::open_with_flags(
+            source_dir.join(crate::database::DB_FILE),
+            OpenFlags::SQLITE_OPEN_READ_ONLY,
+        )?;
+        crate::backup::backup_to(&source_conn, &target_dir.join(crate::database::DB_FILE))?;
+
+        let source_blobs = source_dir.join("workspaces");
+        let target_blobs = target_dir.join("workspaces");
+        if source_blobs.is_dir() {
+            copy_dir(&source_blobs, &target_blobs)?;
+        }
+        let (conn, _) = crate::database::open_migrated(&target_dir)?;
+        conn.execute(
+            "UPDATE attachments SET path = ?2 || substr(path, length(?1) + 1)
+             WHERE substr(path, 1, length(?1)) = ?1",
+            [source_blobs.to_string_lossy(), target_blobs.to_string_lossy()],
+        )?;
+        Ok(())
+    })();
+    if let Err(e) = copied {
+        std::fs::remove_dir_all(&target_dir).ok();
+        return Err(e);
+    }
+
+    let _lock = REGISTRY_LOCK.lock().unwrap();
+    let mut registry = Registry::load(root)?;
+    registry.workspaces.push(workspace.clone());
+    registry.save(root)?;
+    Ok(workspace)
+}
+
+/// Remove workspace `id` and its data directory. The default and the active
+/// workspace cannot be deleted.
+pub fn delete(root: &Path, id: &str) -> AnyhowResult<()> {
+    if id == DEFAULT_WORKSPACE {
+        return Err(anyhow!("The default workspace cannot be deleted"));
+    }
+    {
+        let _lock = REGISTRY_LOCK.lock().unwrap();
+        let mut registry = Registry::load(root)?;
+        registry.get(id)?;
+        if registry.active == id {
+            return Err(anyhow!("Switch to another workspace before deleting this one"));
+        }
+        registry.workspaces.retain(|w| w.id != id);
+        registry.save(root)?;
+    }
+    let dir = data_dir(root, id);
+    if dir.exists() {
+        std::fs::remove_dir_all(dir)?;
+    }
+    Ok(())
+}
+
+/// Make `id` the active workspace: migrate its database and move `pool` onto it.
+pub fn switch(root: &Path, pool: &crate::pool::DbPool, id: &str) -> AnyhowResult<Workspace> {
+    let _lock = REGISTRY_LOCK.lock().unwrap();
+    let mut registry = Registry::load(root)?;
+    let workspace = registry.get(id)?.clone();
+    let (writer, db_path) = crate::database::open_migrated(&data_dir(root, id))?;
+    pool.swap(writer, &db_path)?;
+    registry.active = workspace.id.clone();
+    registry.save(root)?;
+    Ok(workspace)
+}
+
+// ── Commands ─────────────────────────────────────────────────────────────────
+
+/// All workspaces and the id of the active one.
+#[tauri::command]
+pub async fn list_workspaces(app: AppHandle) -> Result<Registry, String> {
+    let root = crate::paths::app_data_root(&app)?;
+    tauri::async_runtime::spawn_blocking(move || Registry::load(&root))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn create_workspace(name: String, app: AppHandle) -> Result<Workspace, String> {
+    let root = crate::paths::app_data_root(&app)?;
+    tauri::async_runtime::spawn_blocking(move || create(&root, &name))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn rename_workspace(id: String, name: String, app: AppHandle) -> Result<Workspace, String> {
+    let root = crate::paths::app_data_root(&app)?;
+    tauri::async_runtime::spawn_blocking(move || rename(&root, &id, &name))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// Copy a workspace, including its API keys and blobs.
+///
+/// `id`   – workspace to copy (it may be the active one)
+/// `name` – name of the copy (default: "<name> (copy)")
+#[tauri::command]
+pub async fn duplicate_workspace(id: String, name: Option<String>, app: AppHandle) -> Result<Workspace, String> {
+    let root = crate::paths::app_data_root(&app)?;
+    tauri::async_runtime::spawn_blocking(move || duplicate(&root, &id, name.as_deref()))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_workspace(id: String, app: AppHandle) -> Result<(), String> {
+    let root = crate::paths::app_data_root(&app)?;
+    tauri::async_runtime::spawn_blocking(move || delete(&root, &id))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+/// Switch the active workspace. Windows receive `workspace-changed` and should reload
+/// their data.
+#[tauri::command]
+pub async fn switch_workspace(
+    id: String,
+    state: tauri::State<'_, DbState>,
+    app: AppHandle,
+) -> Result<Workspace, String> {
+    let root = crate::paths::app_data_root(&app)?;
+    let pool = state.inner().clone();
+    let handle = app.clone();
+    let workspace = tauri::async_runtime::spawn_blocking(move || -> AnyhowResult<Workspace> {
+        let workspace = switch(&root, &pool, &id)?;
+        reload_state(&handle, &pool.writer())?;
+        Ok(workspace)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())?;
+
+    app.emit(WORKSPACE_EVENT, &workspace).ok();
+    Ok(workspace)
+}
@@ -10,7 +10,6 @@ import {
   onRunnerStderr,
   onRunnerExit,
 } from '@/lib/runner';
-import { listEnvVariables } from '@/lib/storage';
 
 // Matches the boilerplate in gateway/helpers.ts — reads args from stdin, calls handler, prints result
 const HANDLER_BOILERPLATE = `
@@ -123,13 +122,9 @@ export function useToolExecution(tool: Tool) {
     setStatus('running');
     setLogs([{ type: 'call', args: parsedArgs, timestamp: Date.now() }]);
 
-    const envVars = await listEnvVariables();
-    const envMap = Object.fromEntries(envVars.map((v) => [v.key, v.value]));
-    const envPreamble = `const env = ${JSON.stringify(envMap)};\n`;
-
     let scriptPath: string;
     try {
-      scriptPath = await writeTempScript(runnerId, envPreamble + tool.code + HANDLER_BOILERPLATE);
+      scriptPath = await writeTempScript(runnerId, tool.code + HANDLER_BOILERPLATE, { withEnv: true });
     } catch (err) {
       setLogs((prev) => [
         ...prev,
@@ -200,6 +195,7 @@ export function useToolExecution(tool: Tool) {
         id: runnerId,
         script: scriptPath,
         permissions: { allow_net: '*', allow_env: true },
+        withEnv: true,
       });
       await runnerSend(runnerId, JSON.stringify(parsedArgs) + '\n');
     } catch (err) {
@@ -230,7 +230,10 @@ export const StudioProvider: React.FC<StudioProviderProps> = ({ children }) => {
 
   const fetchCollections = useCallback(async () => {
     try {
-      const collections: Collection[] = await invoke('db_select_cmd', { table: 'collections', query: {} });
+      const collections: Collection[] = await invoke('db_select_cmd', {
+        table: 'collections',
+        query: { where: { archived_at: null } },
+      });
       setStudioState(prev => ({ ...prev, collections }));
       return collections;
     } catch (error) {
@@ -241,7 +244,11 @@ export const StudioProvider: React.FC<StudioProviderProps> = ({ children }) => {
 
   const fetchScenarios = useCallback(async () => {
     try {
-      const scenarios: Scenario[] = await invoke('db_select_cmd', { table: 'scenarios', query: {} });
+      // A delete archives scenarios that still have runs (see the delete policy)
+      const scenarios: Scenario[] = await invoke('db_select_cmd', {
+        table: 'scenarios',
+        query: { where: { archived_at: null } },
+      });
       setStudioState(prev => ({ ...prev, savedScenarios: scenarios }));
     } catch (error) {
       console.error("Failed to fetch scenarios:", error);
@@ -21,6 +21,7 @@ interface AgentListProps {
   onSelectAgent: (agentId: string) => void;
   onToggleStar: (agentId: string, e: React.MouseEvent) => void;
   onDeleteAgent: (agentId: string) => void;
+  onDuplicateAgent: (agentId: string) => void;
   onCreateAgent?: () => void;
 }
 
@@ -32,6 +33,7 @@ export function AgentList({
   onSelectAgent,
   onToggleStar,
   onDeleteAgent,
+  onDuplicateAgent,
   onCreateAgent,
 }: AgentListProps) {
   return (
@@ -80,7 +82,7 @@ export function AgentList({
                     : []
                 }
                 menuItems={[
-                  { label: "Duplicate", icon: Copy, destructive: false, onClick: () => { } },
+                  { label: "Duplicate", icon: Copy, destructive: false, onClick: () => onDuplicateAgent(agent.id) },
                   { label: "Delete", icon: Trash2, destructive: true, onClick: () => onDeleteAgent(agent.id) },
                 ]}
               />
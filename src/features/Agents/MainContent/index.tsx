@@ -12,7 +12,7 @@ import {
   DialogTitle,
 } from "@/components/ui/dialog";
 import Header from "../Header";
-import { listAgents, agentRecordToListAgent, listExecutions, updateAgent } from "@/lib/storage";
+import { listAgents, agentRecordToListAgent, listExecutions, updateAgent, cloneEntity } from "@/lib/storage";
 import { calculateRequestCost } from "@/lib/modelPricing";
 import { formatDuration } from "@/lib/helpers/time";
 import { formatTokens, formatCost } from "@/lib/helpers/format";
@@ -151,6 +151,18 @@ function AgentsMainContent({ filter }: AgentsMainContentProps) {
     [agents]
   );
 
+  const handleDuplicateAgent = useCallback(
+    async (id: string) => {
+      try {
+        await cloneEntity("agent", id);
+        await refreshAgents();
+      } catch (error) {
+        console.error("Failed to duplicate agent:", error);
+      }
+    },
+    [refreshAgents]
+  );
+
   const handleConfirmDelete = useCallback(async () => {
     if (!agentToDelete) return;
     await updateAgent(agentToDelete.id, { archived_at: Date.now() });
@@ -246,6 +258,7 @@ function AgentsMainContent({ filter }: AgentsMainContentProps) {
               onSelectAgent={handleSelectAgent}
               onToggleStar={toggleStar}
               onDeleteAgent={handleDeleteClick}
+              onDuplicateAgent={handleDuplicateAgent}
               onCreateAgent={handleCreateAgent}
             />
           )}
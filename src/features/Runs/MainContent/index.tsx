@@ -1,4 +1,4 @@
-import { useState, useEffect, useCallback } from "react";
+import { useState, useEffect, useCallback, useRef } from "react";
 import { Activity, CheckCircle, XCircle } from "lucide-react";
 import { FilterEmptyState } from "@/components/ui/EmptyState";
 
@@ -6,7 +6,7 @@ import type { Execution, ExecutionType, ExecutionStatus, Scenario, AgentRecord }
 import MainContent from "@/components/Layout/MainContent";
 import { Pagination } from "@/components/ui/Pagination";
 import { Tooltip, TooltipContent, TooltipProvider, TooltipTrigger } from "@/components/ui/tooltip";
-import { listExecutions, listScenarios, listAgents, countExecutions } from "@/lib/storage";
+import { listExecutions, listExecutionsPage, listScenarios, listAgents, countExecutions } from "@/lib/storage";
 import { formatDuration, formatRelativeTime } from "@/lib/helpers/time";
 import { calculateRequestCost } from "@/lib/modelPricing";
 import type { RunFilterId } from "../index";
@@ -134,6 +134,9 @@ function Runs({ filter }: RunsProps) {
   const [page, setPage] = useState(1);
   const [isLoading, setIsLoading] = useState(true);
   const [selectedRun, setSelectedRun] = useState<Run | null>(null);
+  // Cursor for each page reached so far (keyed by filter and page number), so stepping
+  // through pages continues from the previous one instead of skipping rows with OFFSET.
+  const cursorsRef = useRef(new Map<string, string>());
 
   useEffect(() => {
     setSelectedRun(null);
@@ -181,8 +184,20 @@ function Runs({ filter }: RunsProps) {
         setRuns(filtered.slice(offset, offset + PAGE_SIZE));
         setTotalRuns(filtered.length);
       } else {
+        const cursor = cursorsRef.current.get(`${currentFilter}:${pageNum}`);
+        const loadExecutions = async () => {
+          if (pageNum > 1 && cursor === undefined) {
+            // Jumped straight to a page we have no cursor for
+            return listExecutions({ offset, limit: PAGE_SIZE, ...filterOptions });
+          }
+          const result = await listExecutionsPage({ limit: PAGE_SIZE, cursor, ...filterOptions });
+          if (result.next_cursor) {
+            cursorsRef.current.set(`${currentFilter}:${pageNum + 1}`, result.next_cursor);
+          }
+          return result.rows;
+        };
         [executions, scenarios, agents, total] = await Promise.all([
-          listExecutions({ offset, limit: PAGE_SIZE, ...filterOptions }),
+          loadExecutions(),
           listScenarios(),
           listAgents(),
           countExecutions(filterOptions),
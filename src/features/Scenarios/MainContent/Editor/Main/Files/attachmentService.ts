@@ -2,7 +2,8 @@ import { invoke } from "@tauri-apps/api/core";
 import { getOrCreateAccount } from "@/lib/storage";
 
 /**
- * Deletes a blob file from the app data workspace folder.
+ * Deletes a blob file from the app data workspace folder, unless an attachment row
+ * still references it (blobs are shared by content and by cloned scenarios).
  */
 export async function deleteBlob(blobPath: string): Promise<void> {
   await invoke("delete_attachment_blob", { blobPath });
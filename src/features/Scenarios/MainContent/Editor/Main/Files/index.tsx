@@ -80,12 +80,13 @@ function Files() {
       if (!file) return;
 
       try {
-        if (file.path) {
-          await deleteBlob(file.path);
-        }
+        // Rows first: a blob still referenced by another attachment is kept
         if (scenarioId) {
           await deleteAttachmentById(id);
         }
+        if (file.path) {
+          await deleteBlob(file.path);
+        }
         setFiles((prev) => prev.filter((f) => f.id !== id));
       } catch (err) {
         console.error("Failed to remove file:", err);
@@ -97,14 +98,14 @@ function Files() {
 
   const handleClearAll = useCallback(async () => {
     try {
+      if (scenarioId) {
+        await deleteAttachmentsByScenarioId(scenarioId);
+      }
       for (const file of files) {
         if (file.path) {
           await deleteBlob(file.path);
         }
       }
-      if (scenarioId) {
-        await deleteAttachmentsByScenarioId(scenarioId);
-      }
       setFiles([]);
     } catch (err) {
       console.error("Failed to clear files:", err);
@@ -33,6 +33,7 @@ interface ScenarioListProps {
   onSelectScenario: (id: string) => void;
   onRunScenario: (id: string) => void;
   onDeleteScenario: (scenario: Scenario) => void;
+  onDuplicateScenario: (id: string) => void;
   hasCollectionSelected: boolean;
   hasScenarios: boolean;
   hasSearch: boolean;
@@ -46,6 +47,7 @@ export function ScenarioList({
   onSelectScenario,
   onRunScenario,
   onDeleteScenario,
+  onDuplicateScenario,
   hasCollectionSelected,
   hasScenarios,
   hasSearch,
@@ -98,7 +100,7 @@ export function ScenarioList({
                     : []
                 }
                 menuItems={[
-                  { label: "Duplicate", icon: Copy, destructive: false, onClick: () => { } },
+                  { label: "Duplicate", icon: Copy, destructive: false, onClick: () => onDuplicateScenario(scenario.id!) },
                   { label: "Export JSON", icon: Download, destructive: false, onClick: () => { } },
                   {
                     label: "Delete",
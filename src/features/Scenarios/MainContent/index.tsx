@@ -8,6 +8,7 @@ import Editor from './Editor';
 import Test from './Test';
 import Visualizer from './Visualizer';
 import { ScenarioList } from './List';
+import { cloneEntity } from '@/lib/storage';
 import { fetchScenarioStats, type ScenarioStats } from './List/scenarioStats';
 import { Button } from '@/components/ui/button';
 import {
@@ -35,6 +36,7 @@ function Studio() {
     createScenario,
     createCollection,
     deleteScenario,
+    fetchScenarios,
     runScenarioById,
   } = context ?? {};
 
@@ -135,6 +137,15 @@ function Studio() {
     }
   };
 
+  const handleDuplicateScenario = async (id: string) => {
+    try {
+      await cloneEntity('scenario', id);
+      await fetchScenarios?.();
+    } catch (error) {
+      console.error('Failed to duplicate scenario:', error);
+    }
+  };
+
   const handleDeleteClick = (scenario: Scenario) => setScenarioToDelete(scenario);
 
   const handleDeleteCurrentScenario = useCallback(() => {
@@ -194,6 +205,7 @@ function Studio() {
               onSelectScenario={(id) => loadScenario?.(id)}
               onRunScenario={(id) => runScenarioById?.(id)}
               onDeleteScenario={handleDeleteClick}
+              onDuplicateScenario={handleDuplicateScenario}
               hasCollectionSelected={selectedCollectionId !== null}
               hasScenarios={savedScenarios.length > 0}
               hasSearch={!!searchQuery.trim()}
@@ -30,6 +30,8 @@ const PROVIDERS = [
 
 function ApiKeys() {
   const [apiKeys, setApiKeys] = useState<Record<string, string>>({});
+  // Stored keys come back masked ("••••abcd"); remember them so an untouched field is not re-saved
+  const [storedKeys, setStoredKeys] = useState<Record<string, string>>({});
   const [visibility, setVisibility] = useState<Record<string, boolean>>({});
   const [saveStatus, setSaveStatus] = useState<Record<string, SaveStatus>>({
     openai: "idle",
@@ -67,6 +69,7 @@ function ApiKeys() {
           {} as Record<string, string>
         );
         setApiKeys(keyMap);
+        setStoredKeys(keyMap);
       } catch (error) {
         console.error("Failed to fetch API keys:", error);
       }
@@ -85,6 +88,8 @@ function ApiKeys() {
   };
 
   const handleSaveApiKey = async (provider: string, apiKey: string) => {
+    if (apiKey === (storedKeys[provider] ?? "")) return;
+
     if (!apiKey) {
       setApiKeys((prev) => ({ ...prev, [provider]: "" }));
       setSaveStatus((prev) => ({ ...prev, [provider]: "idle" }));
@@ -93,6 +98,7 @@ function ApiKeys() {
           table: "api_keys",
           query: { where: { provider } },
         });
+        setStoredKeys((prev) => ({ ...prev, [provider]: "" }));
         clearModelCache();
         const models = await fetchAndNormalizeModels({ forceRefresh: true });
         setProviderModels(models);
@@ -121,6 +127,7 @@ function ApiKeys() {
       }
       clearModelCache();
       setApiKeys((prev) => ({ ...prev, [provider]: apiKey }));
+      setStoredKeys((prev) => ({ ...prev, [provider]: apiKey }));
       const models = await fetchAndNormalizeModels({ forceRefresh: true });
       setProviderModels(models);
       setSaveStatus((prev) => ({ ...prev, [provider]: "saved" }));
@@ -1,6 +1,8 @@
 // src/lib/gateway/GatewayFetchWrapper.ts
 interface ProxyMetadata {
   latency?: number;
+  shadowGroupId?: string;
+  requestId?: string;
   [key: string]: any;
 }
 
@@ -40,13 +42,19 @@ class GatewayFetch {
         }
       }
 
+      // Shadow traffic: id of the stored primary/shadow comparison group
+      const shadowGroupHeader = response.headers.get('x-shadow-group-id');
+      if (shadowGroupHeader) {
+        this.metadata.shadowGroupId = shadowGroupHeader;
+      }
+
+      // Request log id (proxy_requests row with the connection timing breakdown)
+      const requestIdHeader = response.headers.get('x-proxy-request-id');
+      if (requestIdHeader) {
+        this.metadata.requestId = requestIdHeader;
+      }
+
       // --- Future: Add more header extractions here ---
-      // Example: Extract a custom request ID header
-      // const requestIdHeader = response.headers.get('X-Proxy-Request-Id');
-      // if (requestIdHeader) {
-      //   this.metadata.requestId = requestIdHeader;
-      // }
-      // -----------------------------------------------
 
       return response;
     } catch (error) {
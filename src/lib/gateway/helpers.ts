@@ -98,16 +98,14 @@ console.log(JSON.stringify(result));
 
 /** Execute a code-mode tool by writing the code to a temp file and running it in Deno.
  *  User code must export an \`async function handler(args)\` — args are injected via stdin
- *  and the return value is written to stdout as JSON. */
+ *  and the return value is written to stdout as JSON. The backend prepends \`env\`. */
 async function executeCodeTool(
   toolName: string,
   code: string,
   args: Record<string, unknown>,
-  envVars: Record<string, string> = {},
 ): Promise<unknown> {
   const runnerId = crypto.randomUUID();
-  const envPreamble = `const env = ${JSON.stringify(envVars)};\n`;
-  const scriptPath = await writeTempScript(runnerId, envPreamble + code + HANDLER_BOILERPLATE);
+  const scriptPath = await writeTempScript(runnerId, code + HANDLER_BOILERPLATE, { withEnv: true });
 
   let stdout = '';
   let stderr = '';
@@ -152,6 +150,7 @@ async function executeCodeTool(
       id: runnerId,
       script: scriptPath,
       permissions: { allow_net: '*', allow_env: true },
+      withEnv: true,
     });
     await runnerSend(runnerId, JSON.stringify(args) + '\n');
   } catch (err) {
@@ -167,7 +166,7 @@ async function executeCodeTool(
 }
 
 /** Convert scenario tools to AI SDK tool format.
- *  JSON-mode tools return their mockResponse directly.
+ *  JSON-mode tools return their mockResponse directly, with \`envVars\` substituted.
  *  Code-mode tools execute their code block in a Deno sandbox. */
 export function toolConfigToAiSdkTools(tools: Tool[], envVars: Record<string, string> = {}): ToolSet {
   const result: ToolSet = {};
@@ -198,7 +197,7 @@ export function toolConfigToAiSdkTools(tools: Tool[], envVars: Record<string, st
       }),
       execute: async (args: Record<string, unknown>) => {
         if (mockMode === 'code' && t.code?.trim()) {
-          return executeCodeTool(t.name, t.code, args, envVars);
+          return executeCodeTool(t.name, t.code, args);
         }
         // JSON mock — substitute env vars then return static response
         const envVarsList = Object.entries(envVars).map(([key, value]) => ({ id: 0, key, value }));
@@ -37,12 +37,15 @@ export async function runnerSpawn(options: {
   script: string;
   args?: string[];
   permissions?: RunnerPermissions;
+  /** Pass the env variables (secrets decrypted) through the process environment. */
+  withEnv?: boolean;
 }): Promise<void> {
   return invoke('runner_spawn', {
     id: options.id,
     script: options.script,
     args: options.args ?? [],
     permissions: options.permissions ?? null,
+    withEnv: options.withEnv ?? null,
   });
 }
 
@@ -61,9 +64,12 @@ export async function runnerList(): Promise<string[]> {
   return invoke('runner_list');
 }
 
-/** Write a code string to a temp .ts file; returns the file path. */
-export async function writeTempScript(id: string, code: string): Promise<string> {
-  return invoke('write_temp_script', { id, code });
+/**
+ * Write a code string to a temp .ts file; returns the file path. With `withEnv`,
+ * the backend prepends a `const env` read from the environment `runnerSpawn({ withEnv })` sets.
+ */
+export async function writeTempScript(id: string, code: string, options: { withEnv?: boolean } = {}): Promise<string> {
+  return invoke('write_temp_script', { id, code, withEnv: options.withEnv ?? null });
 }
 
 /** Delete a temp script file previously written by writeTempScript. */
@@ -0,0 +1,74 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { RunnableType } from './versions';
+
+export type UsageSource = 'executions' | 'eval_runs';
+export type UsageDimension = 'day' | 'provider' | 'model' | 'scenario' | 'agent' | 'status';
+
+export interface UsageGroup {
+  /** Value of each requested dimension, plus scenario_title / agent_name */
+  key: Record<string, string | null>;
+  runs: number;
+  /** Runs by status */
+  statuses: Record<string, number>;
+  input_tokens: number;
+  output_tokens: number;
+  cached_tokens: number;
+  total_tokens: number;
+  cost_usd: number;
+  /** Runs with tokens but no known pricing, left out of cost_usd */
+  unpriced_runs: number;
+  latency: { avg_ms: number | null; p50_ms: number | null; p90_ms: number | null; p99_ms: number | null };
+  /** Test case outcomes (eval runs only) */
+  cases_passed: number;
+  cases_failed: number;
+  cases_errored: number;
+}
+
+export interface UsageBreakdownOptions {
+  source?: UsageSource;
+  /** unix ms range of the run start */
+  from?: number;
+  to?: number;
+  runnableType?: RunnableType;
+}
+
+/** Usage aggregated by the backend, one entry per combination of `groupBy` values. */
+export async function getUsageBreakdown(
+  groupBy: UsageDimension[],
+  options: UsageBreakdownOptions = {}
+): Promise<UsageGroup[]> {
+  const groups = await invoke<UsageGroup[]>('usage_breakdown_cmd', {
+    source: options.source ?? 'executions',
+    groupBy,
+    from: options.from ?? null,
+    to: options.to ?? null,
+    runnableType: options.runnableType ?? null,
+  });
+  return Array.isArray(groups) ? groups : [];
+}
+
+export interface DailyUsage {
+  /** YYYY-MM-DD, local time */
+  day: string;
+  source: UsageSource;
+  provider: string;
+  model: string;
+  runnable_type: RunnableType;
+  runnable_id: string;
+  status: string;
+  runs: number;
+  input_tokens: number;
+  output_tokens: number;
+  cached_tokens: number;
+  total_tokens: number;
+  cost_usd: number;
+  latency_ms_sum: number;
+  latency_count: number;
+  refreshed_at: number;
+}
+
+/** Daily rollup rows (kept after retention prunes the runs), refreshed before reading. */
+export async function getDailyUsage(fromDay?: string, toDay?: string): Promise<DailyUsage[]> {
+  const rows = await invoke<DailyUsage[]>('usage_daily', { fromDay: fromDay ?? null, toDay: toDay ?? null });
+  return Array.isArray(rows) ? rows : [];
+}
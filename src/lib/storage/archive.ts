@@ -0,0 +1,46 @@
+import { invoke } from '@tauri-apps/api/core';
+
+/** What to do with an imported row whose id already exists. */
+export type ConflictStrategy = 'skip' | 'overwrite' | 're_id';
+
+export interface WorkspaceManifest {
+  format: string;
+  version: number;
+  schema_version: number;
+  /** unix ms */
+  exported_at: number;
+  account_id: string;
+  /** Row count per table */
+  tables: Record<string, number>;
+  blobs: number;
+}
+
+export interface TableImport {
+  inserted: number;
+  overwritten: number;
+  skipped: number;
+}
+
+export interface ImportSummary {
+  manifest: WorkspaceManifest;
+  tables: Record<string, TableImport>;
+  /** Attachment files written to the workspace */
+  blobs: number;
+}
+
+/**
+ * Write the workspace (rows and attachment files) to an archive file. The archive is
+ * assembled in Rust, so nothing passes through the webview.
+ */
+export async function exportWorkspace(path: string, accountId: string): Promise<WorkspaceManifest> {
+  return invoke<WorkspaceManifest>('export_workspace', { path, accountId });
+}
+
+/** Import an archive written by `exportWorkspace`, atomically. */
+export async function importWorkspace(
+  path: string,
+  strategy: ConflictStrategy,
+  accountId?: string
+): Promise<ImportSummary> {
+  return invoke<ImportSummary>('import_workspace', { path, strategy, accountId: accountId ?? null });
+}
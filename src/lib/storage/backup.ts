@@ -0,0 +1,27 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export interface BackupInfo {
+  path: string;
+  size_bytes: number;
+  /** unix ms */
+  created_at: number;
+}
+
+/** Snapshot the database; defaults to a timestamped file in the backups folder. */
+export async function backupDatabase(path?: string): Promise<BackupInfo> {
+  return invoke<BackupInfo>('backup_database', { path: path ?? null });
+}
+
+export async function listBackups(): Promise<BackupInfo[]> {
+  const backups = await invoke<BackupInfo[]>('list_backups');
+  return Array.isArray(backups) ? backups : [];
+}
+
+/**
+ * Replace the database with a backup. Returns the backup of the database as it was
+ * before the restore, so the restore can be undone. Every window then receives
+ * `onWorkspaceChanged` and reloads.
+ */
+export async function restoreDatabase(path: string): Promise<BackupInfo> {
+  return invoke<BackupInfo>('restore_database', { path });
+}
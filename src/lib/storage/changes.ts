@@ -0,0 +1,20 @@
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+
+export type DbChangeOp = 'insert' | 'update' | 'delete';
+
+/** Emitted by the backend after a successful write, to every window. */
+export interface DbChange {
+  table: string;
+  op: DbChangeOp;
+  ids: string[];
+}
+
+/**
+ * Listen for database writes, optionally only to some tables (one event per insert,
+ * update or delete; a batch emits one per operation). Returns an unlisten function.
+ */
+export function onDbChange(callback: (change: DbChange) => void, tables?: string[]): Promise<UnlistenFn> {
+  return listen<DbChange>('db-change', (e) => {
+    if (!tables || tables.includes(e.payload.table)) callback(e.payload);
+  });
+}
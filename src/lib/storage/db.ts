@@ -1,5 +1,36 @@
 import { invoke } from '@tauri-apps/api/core';
 
+type Scalar = string | number | boolean | null;
+
+/** Condition on one column: a value (equality, null → IS NULL) or operators ANDed together. */
+export type ColumnCondition =
+  | Scalar
+  | {
+      eq?: Scalar;
+      ne?: Scalar;
+      gt?: Scalar;
+      gte?: Scalar;
+      lt?: Scalar;
+      lte?: Scalar;
+      like?: string;
+      in?: Scalar[];
+      not_in?: Scalar[];
+      is_null?: boolean;
+      not_null?: boolean;
+      between?: [Scalar, Scalar];
+    };
+
+/**
+ * `where` filter for the generic db commands; columns are ANDed, `or`/`and`/`not` nest.
+ * A key may address a value inside a JSON column as `column.$path`, e.g.
+ * `{ 'usage_json.$.totalTokens': { gt: 10000 } }` (also accepted by `orderBy`).
+ */
+export type WhereFilter = {
+  or?: WhereFilter[];
+  and?: WhereFilter[];
+  not?: WhereFilter;
+} & { [column: string]: ColumnCondition | WhereFilter | WhereFilter[] | undefined };
+
 export async function dbSelect<T>(table: string, query: Record<string, unknown> = {}): Promise<T[]> {
   const rows = await invoke<T[]>('db_select_cmd', { table, query });
   return Array.isArray(rows) ? rows : [];
@@ -10,33 +41,76 @@ export async function dbSelectOne<T>(table: string, query: Record<string, unknow
   return rows[0] ?? null;
 }
 
+/** One page from `dbPage`; pass `next_cursor` back as `cursor` for the following page. */
+export interface Page<T> {
+  rows: T[];
+  /** null on the last page */
+  next_cursor: string | null;
+  /** Rows matching `where`, when requested with `withTotal` */
+  total: number | null;
+}
+
+export interface PageQuery {
+  where?: WhereFilter;
+  /** Sort key (a column or `column.$path`); rows are ordered by it, then by id */
+  orderBy?: string;
+  orderDirection?: 'asc' | 'desc';
+  limit?: number;
+  columns?: string[];
+  cursor?: string | null;
+  withTotal?: boolean;
+}
+
+/** Keyset pagination: unlike `offset`, later pages cost the same as the first. */
+export async function dbPage<T>(table: string, query: PageQuery = {}): Promise<Page<T>> {
+  return invoke<Page<T>>('db_page_cmd', { table, query });
+}
+
 export async function dbInsert(table: string, data: object): Promise<string> {
   return invoke<string>('db_insert_cmd', { table, data });
 }
 
-export async function dbUpdate(table: string, where: Record<string, unknown>, data: object): Promise<void> {
+export async function dbUpdate(table: string, where: WhereFilter, data: object): Promise<void> {
   await invoke('db_update_cmd', { table, query: { where }, data });
 }
 
-export async function dbCount(table: string, where?: Record<string, unknown>): Promise<number> {
+export async function dbCount(table: string, where?: WhereFilter): Promise<number> {
   const query = where != null ? { where } : {};
   const count = await invoke<number>('db_count_cmd', { table, query });
   return typeof count === 'number' ? count : 0;
 }
 
-export async function dbDelete(table: string, where?: Record<string, unknown>): Promise<number> {
+export async function dbDelete(table: string, where?: WhereFilter): Promise<number> {
   const query = where != null ? { where } : {};
   const deleted = await invoke<number>('db_delete_cmd', { table, query });
   return typeof deleted === 'number' ? deleted : 0;
 }
 
+/** Id generated by an earlier insert in the same batch, by its `as` alias or index. */
+export type BatchRef = { $ref: string | number };
+
+export type BatchOp =
+  | { op: 'insert'; table: string; data: object; as?: string }
+  | { op: 'update'; table: string; query: { where: WhereFilter }; data: object }
+  | { op: 'delete'; table: string; query: { where: WhereFilter } };
+
+export interface BatchResult {
+  id: string | null;
+  changes: number;
+}
+
+/** Runs the operations in order in one transaction; nothing is written if any of them fails. */
+export async function dbBatch(ops: BatchOp[]): Promise<BatchResult[]> {
+  return invoke<BatchResult[]>('db_batch_cmd', { ops });
+}
+
 export async function dbExec(sql: string): Promise<void> {
   await invoke('db_exec_cmd', { sql });
 }
 
 export async function dbUpsert(
   table: string,
-  where: Record<string, unknown>,
+  where: WhereFilter,
   insertData: Record<string, unknown>,
   updateData: Record<string, unknown> = insertData
 ): Promise<string> {
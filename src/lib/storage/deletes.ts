@@ -0,0 +1,52 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { WhereFilter } from './db';
+
+/**
+ * What happens to a relation's rows when their scenario or agent is deleted:
+ * `cascade` deletes them, `archive` keeps them and archives the scenario/agent
+ * instead, `block` refuses the delete.
+ */
+export type LinkPolicy = 'cascade' | 'archive' | 'block';
+
+export interface DeletePolicy {
+  /** default: cascade (local tools left unlinked are deleted too) */
+  tool_links?: LinkPolicy;
+  /** default: cascade */
+  eval_test_cases?: LinkPolicy;
+  /** default: archive */
+  eval_runs?: LinkPolicy;
+  /** default: archive */
+  executions?: LinkPolicy;
+}
+
+export interface RowSet {
+  table: string;
+  ids: string[];
+}
+
+export interface DeletePlan {
+  table: string;
+  /** rows of `table` that are deleted */
+  deleted: string[];
+  /** rows archived instead (scenarios/agents, and collections holding them) */
+  archived: RowSet[];
+  /** rows deleted along with them, per table */
+  removed: RowSet[];
+  /** linked rows of relations set to block; the delete is refused unless empty */
+  blocked_by: RowSet[];
+}
+
+export async function getDeletePolicy(): Promise<Required<DeletePolicy>> {
+  return invoke<Required<DeletePolicy>>('get_delete_policy');
+}
+
+/** Applies to deletes of scenarios, agents and collections from then on. */
+export async function setDeletePolicy(policy: DeletePolicy): Promise<Required<DeletePolicy>> {
+  return invoke<Required<DeletePolicy>>('set_delete_policy', { policy });
+}
+
+/** What `dbDelete(table, where)` would delete, archive or be blocked by, without deleting. */
+export async function previewDelete(table: string, where?: WhereFilter): Promise<DeletePlan> {
+  const query = where != null ? { where } : {};
+  return invoke<DeletePlan>('preview_delete', { table, query });
+}
@@ -0,0 +1,79 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { RunnableType } from './versions';
+import type { RowSet } from './deletes';
+
+/** Core tables with a typed, validated backend API (see src-tauri/src/domain.rs) */
+export type EntityType = 'scenario' | 'agent' | 'tool' | 'tool_link' | 'eval_test_case' | 'execution';
+
+/** A row with its *_json columns parsed and 0/1 flags as numbers */
+export type EntityRow = Record<string, unknown> & { id: string };
+
+export async function getEntity(type: EntityType, id: string): Promise<EntityRow> {
+  return invoke<EntityRow>('entity_get', { entityType: type, id });
+}
+
+/** Rows matching `query` (same shape as for dbSelect). */
+export async function listEntities(type: EntityType, query?: Record<string, unknown>): Promise<EntityRow[]> {
+  const rows = await invoke<EntityRow[]>('entity_list', { entityType: type, query: query ?? null });
+  return Array.isArray(rows) ? rows : [];
+}
+
+/** Validate and insert a row; missing columns take their defaults. Returns the stored row. */
+export async function createEntity(type: EntityType, data: Record<string, unknown>): Promise<EntityRow> {
+  return invoke<EntityRow>('entity_create', { entityType: type, data });
+}
+
+/** Validate and overwrite the row `data.id`. Returns the stored row. */
+export async function saveEntity(type: EntityType, data: EntityRow): Promise<EntityRow> {
+  return invoke<EntityRow>('entity_save', { entityType: type, data });
+}
+
+/**
+ * Deleting a scenario or agent follows the delete policy (see `getDeletePolicy`): linked
+ * rows are removed, or it is archived instead, or the delete is refused. Returns the
+ * number of rows deleted or archived.
+ */
+export async function deleteEntity(type: EntityType, id: string): Promise<number> {
+  return invoke<number>('entity_delete', { entityType: type, id });
+}
+
+/** Make `tools` (in order) the tools of a scenario or agent. Returns the linked tools. */
+export async function setLinkedTools(
+  type: RunnableType,
+  id: string,
+  tools: Record<string, unknown>[]
+): Promise<EntityRow[]> {
+  const linked = await invoke<EntityRow[]>('set_linked_tools_cmd', { runnableType: type, runnableId: id, tools });
+  return Array.isArray(linked) ? linked : [];
+}
+
+export type CloneType = 'scenario' | 'agent' | 'collection';
+
+export interface CloneOptions {
+  /** appended to the copy's title/name (default: " (copy)") */
+  suffix?: string;
+  /** also copy agent memories (default: false) */
+  includeMemories?: boolean;
+}
+
+export interface CloneResult {
+  entity_type: CloneType;
+  /** id of the copy */
+  id: string;
+  /** every row created, per table */
+  created: RowSet[];
+}
+
+/**
+ * Deep-copy a scenario, an agent or a collection (with its scenarios) in one
+ * transaction: local tools and their links, eval test cases, attachments and,
+ * optionally, agent memories. Runs and version history are not copied.
+ */
+export async function cloneEntity(type: CloneType, id: string, options: CloneOptions = {}): Promise<CloneResult> {
+  return invoke<CloneResult>('clone_entity', {
+    entityType: type,
+    id,
+    suffix: options.suffix ?? null,
+    includeMemories: options.includeMemories ?? null,
+  });
+}
@@ -1,5 +1,5 @@
 import { Execution, ExecutionStatus, ExecutionType } from '@/types';
-import { dbSelect, dbSelectOne, dbInsert, dbUpdate, dbCount } from './db';
+import { dbSelect, dbSelectOne, dbInsert, dbUpdate, dbCount, dbPage, type Page } from './db';
 
 export async function insertExecution(data: Execution): Promise<string> {
   // created_at and updated_at are now handled by the backend
@@ -17,16 +17,22 @@ export interface ListExecutionsOptions {
   type?: ExecutionType;
   status?: ExecutionStatus;
   runnableId?: string;
+  /** Only runs started at or after this unix ms timestamp */
+  startedAfter?: number;
 }
 
-export async function listExecutions(options?: ListExecutionsOptions): Promise<Execution[]> {
-  const { offset = 0, limit, type, status, runnableId } = options ?? {};
-
-  const where = {
+function executionsWhere({ type, status, runnableId, startedAfter }: ListExecutionsOptions) {
+  return {
     ...(type && { type }),
     ...(status && { status }),
     ...(runnableId && { runnable_id: runnableId }),
+    ...(startedAfter != null && { started_at: { gte: startedAfter } }),
   };
+}
+
+export async function listExecutions(options?: ListExecutionsOptions): Promise<Execution[]> {
+  const { offset = 0, limit } = options ?? {};
+  const where = executionsWhere(options ?? {});
 
   return dbSelect<Execution>('executions', {
     orderBy: 'started_at',
@@ -37,15 +43,39 @@ export async function listExecutions(options?: ListExecutionsOptions): Promise<E
   });
 }
 
+export interface ExecutionsPageOptions extends Omit<ListExecutionsOptions, 'offset'> {
+  /** `next_cursor` of the previous page */
+  cursor?: string | null;
+  withTotal?: boolean;
+}
+
+/** Newest runs first, one keyset page at a time. */
+export async function listExecutionsPage(options?: ExecutionsPageOptions): Promise<Page<Execution>> {
+  const { limit, cursor, withTotal } = options ?? {};
+  const where = executionsWhere(options ?? {});
+
+  return dbPage<Execution>('executions', {
+    orderBy: 'started_at',
+    orderDirection: 'desc',
+    ...(limit != null && limit > 0 && { limit }),
+    ...(cursor && { cursor }),
+    ...(withTotal && { withTotal }),
+    ...(Object.keys(where).length > 0 && { where }),
+  });
+}
+
 export interface CountExecutionsOptions {
   type?: ExecutionType;
   status?: ExecutionStatus;
+  /** Only runs started at or after this unix ms timestamp */
+  startedAfter?: number;
 }
 
 export async function countExecutions(options?: CountExecutionsOptions): Promise<number> {
   const where = {
     ...(options?.type && { type: options.type }),
     ...(options?.status && { status: options.status }),
+    ...(options?.startedAfter != null && { started_at: { gte: options.startedAfter } }),
   };
   return dbCount('executions', Object.keys(where).length > 0 ? where : undefined);
 }
@@ -7,3 +7,15 @@ export * from './tools';
 export * from './attachments';
 export * from './settings';
 export * from './evals';
+export * from './search';
+export * from './backup';
+export * from './archive';
+export * from './versions';
+export * from './retention';
+export * from './changes';
+export * from './entities';
+export * from './deletes';
+export * from './analytics';
+export * from './workspaces';
+export * from './migrations';
+export * from './maintenance';
@@ -0,0 +1,112 @@
+import { invoke } from '@tauri-apps/api/core';
+import type { BackupInfo } from './backup';
+
+export interface IntegrityReport {
+  ok: boolean;
+  /** empty when ok */
+  problems: string[];
+}
+
+export interface ForeignKeyViolation {
+  table: string;
+  /** null for WITHOUT ROWID tables */
+  rowid: number | null;
+  /** table the missing row should be in */
+  parent: string;
+  fk_index: number;
+}
+
+export interface WalCheckpoint {
+  /** another connection kept the checkpoint from completing */
+  busy: boolean;
+  log_frames: number;
+  checkpointed_frames: number;
+}
+
+export interface Compaction {
+  bytes_before: number;
+  bytes_after: number;
+}
+
+export interface TableStorage {
+  name: string;
+  rows: number;
+  /** null when SQLite has no dbstat table */
+  table_bytes: number | null;
+  index_bytes: number | null;
+}
+
+export interface StorageReport {
+  page_size: number;
+  page_count: number;
+  /** pages that are free until the next vacuum */
+  freelist_count: number;
+  file_bytes: number;
+  wal_bytes: number;
+  /** whether per-table sizes are available */
+  dbstat: boolean;
+  /** largest first */
+  tables: TableStorage[];
+}
+
+/** Rows pointing at a scenario or agent that no longer exists (or local tools nothing links to). */
+export interface OrphanGroup {
+  table: string;
+  column: string;
+  ids: string[];
+}
+
+export interface RepairReport {
+  backup: BackupInfo;
+  /** orphans that were deleted */
+  orphans: OrphanGroup[];
+  foreign_keys_fixed: number;
+  /** violations with no ON DELETE action to apply */
+  foreign_keys_left: ForeignKeyViolation[];
+  compaction: Compaction;
+}
+
+/** PRAGMA integrity_check, or the faster quick_check when `full` is false. */
+export async function checkIntegrity(full = true): Promise<IntegrityReport> {
+  return invoke<IntegrityReport>('check_integrity', { full });
+}
+
+export async function checkForeignKeys(): Promise<ForeignKeyViolation[]> {
+  return invoke<ForeignKeyViolation[]>('check_foreign_keys');
+}
+
+export async function vacuumDatabase(): Promise<Compaction> {
+  return invoke<Compaction>('vacuum_database');
+}
+
+export async function analyzeDatabase(): Promise<void> {
+  await invoke('analyze_database');
+}
+
+export async function optimizeDatabase(): Promise<void> {
+  await invoke('optimize_database');
+}
+
+/** PRAGMA wal_checkpoint(TRUNCATE). */
+export async function checkpointWal(): Promise<WalCheckpoint> {
+  return invoke<WalCheckpoint>('checkpoint_wal');
+}
+
+/** Row counts and sizes per table. */
+export async function getStorageReport(): Promise<StorageReport> {
+  return invoke<StorageReport>('get_storage_report');
+}
+
+/** Orphaned rows per table. Runs of deleted scenarios and agents are kept by design. */
+export async function listOrphans(): Promise<OrphanGroup[]> {
+  return invoke<OrphanGroup[]>('list_orphans');
+}
+
+/**
+ * Repair and compact: integrity check, backup, delete orphans (runs too when
+ * `includeRuns`), fix foreign key violations, vacuum, optimize and truncate the WAL.
+ * A database that fails the integrity check is left untouched.
+ */
+export async function repairDatabase(includeRuns = false): Promise<RepairReport> {
+  return invoke<RepairReport>('repair_database', { includeRuns });
+}
@@ -0,0 +1,57 @@
+import { invoke } from '@tauri-apps/api/core';
+
+/** One migration applied (or to apply) in one direction. */
+export interface MigrationStep {
+  version: number;
+  name: string;
+  direction: 'up' | 'down';
+}
+
+export interface MigrationHistoryEntry extends MigrationStep {
+  /** unix ms */
+  applied_at: number;
+}
+
+export interface SchemaInfo {
+  /** schema version of the database (PRAGMA user_version) */
+  user_version: number;
+  /** schema version this build of the app expects */
+  latest_version: number;
+  pending: MigrationStep[];
+  /** most recent first */
+  history: MigrationHistoryEntry[];
+  /** why migrating failed at startup; the database was left unmigrated */
+  startup_error: string | null;
+}
+
+export interface MigrationDryRun {
+  from_version: number;
+  to_version: number;
+  steps: MigrationStep[];
+  /** null when every step applied cleanly on the copy */
+  error: string | null;
+}
+
+export async function getSchemaInfo(): Promise<SchemaInfo> {
+  return invoke<SchemaInfo>('schema_info');
+}
+
+/** Try migrating a copy of the database to `targetVersion` (default: latest). */
+export async function dryRunMigrations(targetVersion?: number): Promise<MigrationDryRun> {
+  return invoke<MigrationDryRun>('dry_run_migrations', { targetVersion: targetVersion ?? null });
+}
+
+/**
+ * Migrate the database up or down to `targetVersion` (default: latest). Nothing is
+ * changed if the dry run fails; otherwise a backup is taken first. Migrating down
+ * deletes data and is refused unless `allowDestructive` is set.
+ */
+export async function migrateDatabase(
+  targetVersion?: number,
+  options: { allowDestructive?: boolean } = {},
+): Promise<SchemaInfo> {
+  return invoke<SchemaInfo>('migrate_database', {
+    targetVersion: targetVersion ?? null,
+    allowDestructive: options.allowDestructive ?? null,
+  });
+}
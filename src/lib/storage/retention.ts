@@ -0,0 +1,39 @@
+import { invoke } from '@tauri-apps/api/core';
+
+/** Limits for a run table; a run is pruned when it breaks any of them. */
+export interface RunRetention {
+  max_age_days?: number | null;
+  /** Keep only this many of the newest runs per scenario/agent */
+  max_per_runnable?: number | null;
+  /** Defaults to true */
+  keep_starred?: boolean;
+}
+
+export interface RetentionPolicy {
+  executions?: RunRetention;
+  /** Also applies to the runs' eval results */
+  eval_runs?: RunRetention;
+  telemetry_max_age_days?: number | null;
+}
+
+export interface PruneReport {
+  executions: number;
+  eval_runs: number;
+  eval_results: number;
+  telemetry_events: number;
+  vacuumed: boolean;
+}
+
+export async function getRetentionPolicy(): Promise<RetentionPolicy> {
+  return invoke<RetentionPolicy>('get_retention_policy');
+}
+
+/** Persist the policy; it is applied in the background every few hours. */
+export async function setRetentionPolicy(policy: RetentionPolicy): Promise<RetentionPolicy> {
+  return invoke<RetentionPolicy>('set_retention_policy', { policy });
+}
+
+/** Apply the stored policy now. */
+export async function applyRetentionPolicy(): Promise<PruneReport> {
+  return invoke<PruneReport>('apply_retention_policy');
+}
@@ -0,0 +1,47 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export type SearchEntityType = 'scenario' | 'agent' | 'prompt_template' | 'tool' | 'execution';
+
+/** Matched terms in `SearchHit.snippet` are wrapped in these markers. */
+export const SEARCH_HIGHLIGHT_START = '\uE000';
+export const SEARCH_HIGHLIGHT_END = '\uE001';
+
+export interface SearchHit {
+  entity_type: SearchEntityType;
+  entity_id: string;
+  title: string | null;
+  snippet: string;
+  /** Higher is more relevant */
+  score: number;
+}
+
+export interface SearchOptions {
+  entityTypes?: SearchEntityType[];
+  limit?: number;
+}
+
+export async function searchEntities(query: string, options?: SearchOptions): Promise<SearchHit[]> {
+  if (!query.trim()) return [];
+  const hits = await invoke<SearchHit[]>('search', {
+    query,
+    entityTypes: options?.entityTypes ?? null,
+    limit: options?.limit ?? null,
+  });
+  return Array.isArray(hits) ? hits : [];
+}
+
+/** Split a snippet into plain and highlighted parts for rendering. */
+export function splitSnippet(snippet: string): { text: string; highlighted: boolean }[] {
+  const parts: { text: string; highlighted: boolean }[] = [];
+  for (const chunk of snippet.split(SEARCH_HIGHLIGHT_START)) {
+    const end = chunk.indexOf(SEARCH_HIGHLIGHT_END);
+    if (end === -1) {
+      if (chunk) parts.push({ text: chunk, highlighted: false });
+      continue;
+    }
+    parts.push({ text: chunk.slice(0, end), highlighted: true });
+    const rest = chunk.slice(end + SEARCH_HIGHLIGHT_END.length);
+    if (rest) parts.push({ text: rest, highlighted: false });
+  }
+  return parts;
+}
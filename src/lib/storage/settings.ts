@@ -1,9 +1,16 @@
+import { invoke } from '@tauri-apps/api/core';
 import { dbSelect, dbSelectOne, dbUpsert } from './db';
 
 export interface EnvVariable { id: string; key: string; value: string; is_secret: number; }
 
+/**
+ * Env variables for variable substitution. A secret's value is its own `{{KEY}}`
+ * placeholder: the proxy fills it in on the way to the provider, and tool scripts get
+ * their `env` from the backend (`runnerSpawn({ ..., withEnv: true })`).
+ */
 export async function listEnvVariables(): Promise<EnvVariable[]> {
-  return dbSelect<EnvVariable>('env_variables', { orderBy: 'created_at', orderDirection: 'asc' });
+  const rows = await invoke<EnvVariable[]>('resolve_env_variables');
+  return Array.isArray(rows) ? rows : [];
 }
 
 /** Check if user has configured at least one API key */
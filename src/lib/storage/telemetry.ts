@@ -1,5 +1,5 @@
 import { TelemetryEvent } from '@/types';
-import { dbSelect, dbInsert, dbCount, dbDelete } from './db';
+import { dbSelect, dbInsert, dbCount, dbDelete, dbPage, type Page, type WhereFilter } from './db';
 
 export type InsertTelemetryEventInput = Omit<TelemetryEvent, 'id' | 'created_at' | 'updated_at'>;
 
@@ -30,14 +30,37 @@ export async function listTelemetryEvents(options?: ListTelemetryEventsOptions):
   return dbSelect<TelemetryEvent>('telemetry_events', query);
 }
 
+export interface TelemetryEventsPageOptions extends Omit<ListTelemetryEventsOptions, 'offset'> {
+  /** `next_cursor` of the previous page */
+  cursor?: string | null;
+  withTotal?: boolean;
+}
+
+export async function listTelemetryEventsPage(options?: TelemetryEventsPageOptions): Promise<Page<TelemetryEvent>> {
+  const { name, trace_id, limit, orderDirection = 'desc', cursor, withTotal } = options ?? {};
+
+  const where: Record<string, string> = {};
+  if (name) where.name = name;
+  if (trace_id) where.trace_id = trace_id;
+
+  return dbPage<TelemetryEvent>('telemetry_events', {
+    orderBy: 'occurred_at',
+    orderDirection,
+    ...(Object.keys(where).length > 0 && { where }),
+    ...(limit != null && limit > 0 && { limit }),
+    ...(cursor && { cursor }),
+    ...(withTotal && { withTotal }),
+  });
+}
+
 export async function countTelemetryEvents(
   where?: Partial<Pick<TelemetryEvent, 'name' | 'trace_id'>>
 ): Promise<number> {
-  return dbCount('telemetry_events', where as Record<string, unknown> | undefined);
+  return dbCount('telemetry_events', where as WhereFilter | undefined);
 }
 
 export async function deleteTelemetryEvents(
   where?: Partial<Pick<TelemetryEvent, 'id' | 'name' | 'trace_id'>>
 ): Promise<number> {
-  return dbDelete('telemetry_events', where as Record<string, unknown> | undefined);
+  return dbDelete('telemetry_events', where as WhereFilter | undefined);
 }
@@ -0,0 +1,43 @@
+import { invoke } from '@tauri-apps/api/core';
+
+export type RunnableType = 'scenario' | 'agent';
+
+export interface RunnableVersion {
+  version: number;
+  /** The live row rather than a snapshot */
+  current: boolean;
+  /** The scenarios/agents row at this version */
+  snapshot: Record<string, unknown>;
+  /** Linked tools rows */
+  tools: Record<string, unknown>[];
+  /** unix ms; when the version was superseded */
+  created_at: number;
+}
+
+export interface FieldChange {
+  /** Column name, or "tools" for the linked tools */
+  field: string;
+  from: unknown;
+  to: unknown;
+}
+
+/** Versions of a scenario or agent, newest first; the first one is the live row. */
+export async function listVersions(type: RunnableType, id: string): Promise<RunnableVersion[]> {
+  const versions = await invoke<RunnableVersion[]>('list_versions_cmd', { runnableType: type, runnableId: id });
+  return Array.isArray(versions) ? versions : [];
+}
+
+export async function diffVersions(type: RunnableType, id: string, from: number, to: number): Promise<FieldChange[]> {
+  const changes = await invoke<FieldChange[]>('diff_versions_cmd', { runnableType: type, runnableId: id, from, to });
+  return Array.isArray(changes) ? changes : [];
+}
+
+/** Keep the current content as a version. Returns the new current version. */
+export async function createVersion(type: RunnableType, id: string): Promise<number> {
+  return invoke<number>('create_version_cmd', { runnableType: type, runnableId: id });
+}
+
+/** Restore an old version as a new version. Returns the new current version. */
+export async function restoreVersion(type: RunnableType, id: string, version: number): Promise<number> {
+  return invoke<number>('restore_version_cmd', { runnableType: type, runnableId: id, version });
+}
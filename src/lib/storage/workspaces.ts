@@ -0,0 +1,50 @@
+import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+
+/** An isolated workspace with its own database, API keys and attachment blobs. */
+export interface Workspace {
+  id: string;
+  name: string;
+  /** unix ms; 0 for the default workspace */
+  created_at: number;
+}
+
+export interface WorkspaceList {
+  /** id of the active workspace */
+  active: string;
+  workspaces: Workspace[];
+}
+
+export const DEFAULT_WORKSPACE_ID = 'default';
+
+export async function listWorkspaces(): Promise<WorkspaceList> {
+  return invoke<WorkspaceList>('list_workspaces');
+}
+
+export async function createWorkspace(name: string): Promise<Workspace> {
+  return invoke<Workspace>('create_workspace', { name });
+}
+
+export async function renameWorkspace(id: string, name: string): Promise<Workspace> {
+  return invoke<Workspace>('rename_workspace', { id, name });
+}
+
+/** Copy a workspace (database, API keys and blobs); the copy defaults to "<name> (copy)". */
+export async function duplicateWorkspace(id: string, name?: string): Promise<Workspace> {
+  return invoke<Workspace>('duplicate_workspace', { id, name: name ?? null });
+}
+
+/** Delete a workspace and its data. The default and the active workspace cannot be deleted. */
+export async function deleteWorkspace(id: string): Promise<void> {
+  await invoke('delete_workspace', { id });
+}
+
+/** Make `id` the active workspace; every window receives `onWorkspaceChanged`. */
+export async function switchWorkspace(id: string): Promise<Workspace> {
+  return invoke<Workspace>('switch_workspace', { id });
+}
+
+/** Listen for workspace switches (data loaded from the previous workspace is stale). */
+export function onWorkspaceChanged(callback: (workspace: Workspace) => void): Promise<UnlistenFn> {
+  return listen<Workspace>('workspace-changed', (e) => callback(e.payload));
+}
@@ -3,8 +3,11 @@ import ReactDOM from 'react-dom/client';
 import App from '@/App';
 import { AppProvider } from '@/contexts/AppContext';
 import { initTelemetry } from '@/lib/telemetry';
+import { onWorkspaceChanged } from '@/lib/storage';
 
 void initTelemetry();
+// Everything on screen was loaded from the previous workspace's database
+void onWorkspaceChanged(() => window.location.reload());
 
 ReactDOM.createRoot(document.getElementById('root') as HTMLElement).render(
   <React.StrictMode>
@@ -129,6 +129,8 @@ export type Execution = {
   ended_at?: number | null;
   usage_json?: string | null;
   error_json?: string | null;
+  /** 1 = kept by retention pruning */
+  starred?: number;
   created_at?: number;
   updated_at?: number;
 };
@@ -271,6 +273,8 @@ export type EvalRun = {
   error_count?: number;
   total_cost_usd?: number | null;
   avg_latency_ms?: number | null;
+  /** 1 = kept by retention pruning */
+  starred?: number;
   created_at?: number;
   updated_at?: number;
 };
@@ -50,11 +50,11 @@ describe("API Keys", () => {
 
   it("loads existing keys from storage on mount", async () => {
     await create("api_key", { provider: "anthropic", key: "sk-ant-persisted" });
-    // Re-navigate to trigger a fresh component mount that reads from DB
+    // Re-navigate to trigger a fresh component mount that reads from DB (keys come back masked)
     await navigateTo("scenarios");
     await navigateTo("settings");
     await waitForSettingsReady();
-    await expect($('input[placeholder="sk-ant-..."]')).toHaveValue("sk-ant-persisted");
+    await expect($('input[placeholder="sk-ant-..."]')).toHaveValue("••••sted");
     await expect($('input[placeholder="Enter Google Cloud API Key"]')).toHaveValue("");
     await expect($('input[placeholder="sk-..."]')).toHaveValue("");
   });
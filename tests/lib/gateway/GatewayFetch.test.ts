@@ -122,6 +122,25 @@ describe('GatewayFetch', () => {
       expect(gf.getProxyMetadata()).toEqual({ latency: 42 });
     });
 
+    it('collects the proxy request id and shadow group id headers', async () => {
+      const mockFetch = vi.fn().mockResolvedValue(
+        makeResponse('{}', {
+          'x-request-latency-ms': '42',
+          'x-proxy-request-id': '01JREQUEST',
+          'x-shadow-group-id': '01JSHADOW',
+        })
+      );
+      const gf = new GatewayFetch(mockFetch);
+
+      await gf.fetch('https://example.com');
+
+      expect(gf.getProxyMetadata()).toEqual({
+        latency: 42,
+        requestId: '01JREQUEST',
+        shadowGroupId: '01JSHADOW',
+      });
+    });
+
     it('returns a copy — mutating the result does not affect internal state', async () => {
       const mockFetch = vi.fn().mockResolvedValue(
         makeResponse('{}', { 'x-request-latency-ms': '10' })
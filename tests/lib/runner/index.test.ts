@@ -33,9 +33,16 @@ describe('runnerSpawn', () => {
       script: 'console.log(1)',
       args: ['--foo'],
       permissions: { allow_net: 'example.com' },
+      withEnv: null,
     });
   });
 
+  it('asks the backend to pass the env variables to the process', async () => {
+    mockInvoke.mockResolvedValue(undefined);
+    await runnerSpawn({ id: 'r1', script: 'x', withEnv: true });
+    expect(mockInvoke).toHaveBeenCalledWith('runner_spawn', expect.objectContaining({ withEnv: true }));
+  });
+
   it('defaults args to [] when not provided', async () => {
     mockInvoke.mockResolvedValue(undefined);
     await runnerSpawn({ id: 'r1', script: 'x' });
@@ -85,7 +92,13 @@ describe('writeTempScript', () => {
   it('invokes write_temp_script with id and code, returns the path', async () => {
     mockInvoke.mockResolvedValue('/tmp/r1.ts');
     expect(await writeTempScript('r1', 'return 42')).toBe('/tmp/r1.ts');
-    expect(mockInvoke).toHaveBeenCalledWith('write_temp_script', { id: 'r1', code: 'return 42' });
+    expect(mockInvoke).toHaveBeenCalledWith('write_temp_script', { id: 'r1', code: 'return 42', withEnv: null });
+  });
+
+  it('asks the backend to prepend the env reader', async () => {
+    mockInvoke.mockResolvedValue('/tmp/r2.ts');
+    await writeTempScript('r2', 'return env.KEY', { withEnv: true });
+    expect(mockInvoke).toHaveBeenCalledWith('write_temp_script', { id: 'r2', code: 'return env.KEY', withEnv: true });
   });
 });
 
@@ -0,0 +1,49 @@
+import { vi, describe, it, expect, beforeEach } from 'vitest';
+vi.mock('@tauri-apps/api/core');
+
+import { invoke } from '@tauri-apps/api/core';
+import { getUsageBreakdown, getDailyUsage } from '@/lib/storage/analytics';
+
+const mockInvoke = vi.mocked(invoke);
+
+beforeEach(() => vi.resetAllMocks());
+
+describe('getUsageBreakdown', () => {
+  it('defaults to executions over all time', async () => {
+    mockInvoke.mockResolvedValue([{ key: { model: 'gpt-4o' }, runs: 3 }]);
+    const groups = await getUsageBreakdown(['model']);
+    expect(groups).toHaveLength(1);
+    expect(mockInvoke).toHaveBeenCalledWith('usage_breakdown_cmd', {
+      source: 'executions',
+      groupBy: ['model'],
+      from: null,
+      to: null,
+      runnableType: null,
+    });
+  });
+
+  it('passes the range and source', async () => {
+    mockInvoke.mockResolvedValue([]);
+    await getUsageBreakdown(['day', 'status'], { source: 'eval_runs', from: 1, to: 2, runnableType: 'agent' });
+    expect(mockInvoke).toHaveBeenCalledWith('usage_breakdown_cmd', {
+      source: 'eval_runs',
+      groupBy: ['day', 'status'],
+      from: 1,
+      to: 2,
+      runnableType: 'agent',
+    });
+  });
+
+  it('returns empty array when invoke returns a non-array', async () => {
+    mockInvoke.mockResolvedValue(null);
+    expect(await getUsageBreakdown([])).toEqual([]);
+  });
+});
+
+describe('getDailyUsage', () => {
+  it('passes the day range to invoke', async () => {
+    mockInvoke.mockResolvedValue([{ day: '2026-01-05', runs: 4 }]);
+    expect(await getDailyUsage('2026-01-01', '2026-01-07')).toHaveLength(1);
+    expect(mockInvoke).toHaveBeenCalledWith('usage_daily', { fromDay: '2026-01-01', toDay: '2026-01-07' });
+  });
+});
@@ -0,0 +1,33 @@
+import { vi, describe, it, expect, beforeEach } from 'vitest';
+vi.mock('@tauri-apps/api/core');
+
+import { invoke } from '@tauri-apps/api/core';
+import { exportWorkspace, importWorkspace } from '@/lib/storage/archive';
+
+const mockInvoke = vi.mocked(invoke);
+
+beforeEach(() => vi.resetAllMocks());
+
+describe('exportWorkspace', () => {
+  it('passes the path and account to invoke', async () => {
+    mockInvoke.mockResolvedValue({ format: 'reticle-workspace', blobs: 2 });
+    const manifest = await exportWorkspace('/tmp/ws.reticle', 'acc-1');
+    expect(manifest.blobs).toBe(2);
+    expect(mockInvoke).toHaveBeenCalledWith('export_workspace', {
+      path: '/tmp/ws.reticle',
+      accountId: 'acc-1',
+    });
+  });
+});
+
+describe('importWorkspace', () => {
+  it('defaults to the exported account', async () => {
+    mockInvoke.mockResolvedValue({ tables: {}, blobs: 0 });
+    await importWorkspace('/tmp/ws.reticle', 're_id');
+    expect(mockInvoke).toHaveBeenCalledWith('import_workspace', {
+      path: '/tmp/ws.reticle',
+      strategy: 're_id',
+      accountId: null,
+    });
+  });
+});
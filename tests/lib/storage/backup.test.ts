@@ -0,0 +1,40 @@
+import { vi, describe, it, expect, beforeEach } from 'vitest';
+vi.mock('@tauri-apps/api/core');
+
+import { invoke } from '@tauri-apps/api/core';
+import { backupDatabase, listBackups, restoreDatabase } from '@/lib/storage/backup';
+
+const mockInvoke = vi.mocked(invoke);
+
+beforeEach(() => vi.resetAllMocks());
+
+const info = { path: '/data/backups/reticle-1.db', size_bytes: 4096, created_at: 1 };
+
+describe('backupDatabase', () => {
+  it('uses the default location when no path is given', async () => {
+    mockInvoke.mockResolvedValue(info);
+    expect(await backupDatabase()).toEqual(info);
+    expect(mockInvoke).toHaveBeenCalledWith('backup_database', { path: null });
+  });
+
+  it('passes an explicit path', async () => {
+    mockInvoke.mockResolvedValue(info);
+    await backupDatabase('/tmp/copy.db');
+    expect(mockInvoke).toHaveBeenCalledWith('backup_database', { path: '/tmp/copy.db' });
+  });
+});
+
+describe('listBackups', () => {
+  it('returns empty array when invoke returns a non-array', async () => {
+    mockInvoke.mockResolvedValue(null);
+    expect(await listBackups()).toEqual([]);
+  });
+});
+
+describe('restoreDatabase', () => {
+  it('returns the pre-restore backup', async () => {
+    mockInvoke.mockResolvedValue(info);
+    expect(await restoreDatabase('/tmp/copy.db')).toEqual(info);
+    expect(mockInvoke).toHaveBeenCalledWith('restore_database', { path: '/tmp/copy.db' });
+  });
+});
@@ -0,0 +1,38 @@
+import { vi, describe, it, expect, beforeEach } from 'vitest';
+vi.mock('@tauri-apps/api/event', () => ({ listen: vi.fn() }));
+
+import { listen } from '@tauri-apps/api/event';
+import { onDbChange, type DbChange } from '@/lib/storage/changes';
+
+const mockListen = vi.mocked(listen);
+
+beforeEach(() => vi.resetAllMocks());
+
+function emit(handler: unknown, payload: DbChange) {
+  (handler as (e: { payload: DbChange }) => void)({ payload });
+}
+
+describe('onDbChange', () => {
+  it('listens on db-change and passes every change to the callback', async () => {
+    const unlisten = vi.fn();
+    mockListen.mockResolvedValue(unlisten);
+    const callback = vi.fn();
+    expect(await onDbChange(callback)).toBe(unlisten);
+    expect(mockListen).toHaveBeenCalledWith('db-change', expect.any(Function));
+
+    emit(mockListen.mock.calls[0][1], { table: 'executions', op: 'insert', ids: ['e1'] });
+    expect(callback).toHaveBeenCalledWith({ table: 'executions', op: 'insert', ids: ['e1'] });
+  });
+
+  it('only passes changes to the given tables', async () => {
+    mockListen.mockResolvedValue(vi.fn());
+    const callback = vi.fn();
+    await onDbChange(callback, ['tools', 'tool_links']);
+    const handler = mockListen.mock.calls[0][1];
+
+    emit(handler, { table: 'executions', op: 'delete', ids: ['e1'] });
+    emit(handler, { table: 'tools', op: 'update', ids: ['t1'] });
+    expect(callback).toHaveBeenCalledTimes(1);
+    expect(callback).toHaveBeenCalledWith({ table: 'tools', op: 'update', ids: ['t1'] });
+  });
+});
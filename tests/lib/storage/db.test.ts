@@ -2,7 +2,7 @@ import { vi, describe, it, expect, beforeEach } from 'vitest';
 vi.mock('@tauri-apps/api/core');
 
 import { invoke } from '@tauri-apps/api/core';
-import { dbSelect, dbSelectOne, dbInsert, dbUpdate, dbCount, dbDelete, dbUpsert } from '@/lib/storage/db';
+import { dbSelect, dbSelectOne, dbPage, dbInsert, dbUpdate, dbCount, dbDelete, dbUpsert, dbBatch } from '@/lib/storage/db';
 
 const mockInvoke = vi.mocked(invoke);
 
@@ -19,6 +19,16 @@ describe('dbSelect', () => {
     expect(mockInvoke).toHaveBeenCalledWith('db_select_cmd', { table: 'executions', query: { where: { id: '1' } } });
   });
 
+  it('passes JSON path keys through unchanged', async () => {
+    mockInvoke.mockResolvedValue([]);
+    const query = {
+      where: { 'usage_json.$.totalTokens': { gt: 10000 } },
+      orderBy: 'snapshot_json.$.configuration.model',
+    };
+    await dbSelect('executions', query);
+    expect(mockInvoke).toHaveBeenCalledWith('db_select_cmd', { table: 'executions', query });
+  });
+
   it('defaults to empty query when none provided', async () => {
     mockInvoke.mockResolvedValue([]);
     await dbSelect('executions');
@@ -64,6 +74,18 @@ describe('dbSelectOne', () => {
   });
 });
 
+// --- dbPage ---
+
+describe('dbPage', () => {
+  it('calls db_page_cmd with table and query', async () => {
+    const page = { rows: [{ id: '1' }], next_cursor: 'abc', total: 7 };
+    mockInvoke.mockResolvedValue(page);
+    const query = { orderBy: 'started_at', orderDirection: 'desc' as const, cursor: 'xyz', withTotal: true };
+    expect(await dbPage('executions', query)).toEqual(page);
+    expect(mockInvoke).toHaveBeenCalledWith('db_page_cmd', { table: 'executions', query });
+  });
+});
+
 // --- dbInsert ---
 
 describe('dbInsert', () => {
@@ -194,3 +216,19 @@ describe('dbUpsert', () => {
     }));
   });
 });
+
+// --- dbBatch ---
+
+describe('dbBatch', () => {
+  it('passes the operations to invoke and returns the results', async () => {
+    const results = [{ id: 'scenario-1', changes: 1 }, { id: 'link-1', changes: 1 }];
+    mockInvoke.mockResolvedValue(results);
+    const ops = [
+      { op: 'insert' as const, table: 'scenarios', data: { title: 'New' }, as: 'scenario' },
+      { op: 'insert' as const, table: 'tool_links', data: { tool_id: 't1', toolable_id: { $ref: 'scenario' } } },
+    ];
+
+    expect(await dbBatch(ops)).toEqual(results);
+    expect(mockInvoke).toHaveBeenCalledWith('db_batch_cmd', { ops });
+  });
+});
@@ -0,0 +1,52 @@
+import { vi, describe, it, expect, beforeEach } from 'vitest';
+vi.mock('@tauri-apps/api/core');
+
+import { invoke } from '@tauri-apps/api/core';
+import { getDeletePolicy, setDeletePolicy, previewDelete, type DeletePlan } from '@/lib/storage/deletes';
+
+const mockInvoke = vi.mocked(invoke);
+
+beforeEach(() => vi.resetAllMocks());
+
+const policy = {
+  tool_links: 'cascade',
+  eval_test_cases: 'cascade',
+  eval_runs: 'archive',
+  executions: 'block',
+} as const;
+
+describe('delete policy', () => {
+  it('gets the policy', async () => {
+    mockInvoke.mockResolvedValue(policy);
+    expect(await getDeletePolicy()).toEqual(policy);
+    expect(mockInvoke).toHaveBeenCalledWith('get_delete_policy');
+  });
+
+  it('sets the policy', async () => {
+    mockInvoke.mockResolvedValue(policy);
+    await setDeletePolicy({ executions: 'block' });
+    expect(mockInvoke).toHaveBeenCalledWith('set_delete_policy', { policy: { executions: 'block' } });
+  });
+});
+
+describe('previewDelete', () => {
+  const plan: DeletePlan = {
+    table: 'scenarios',
+    deleted: ['s1'],
+    archived: [],
+    removed: [{ table: 'tool_links', ids: ['l1'] }],
+    blocked_by: [],
+  };
+
+  it('previews a delete by where filter', async () => {
+    mockInvoke.mockResolvedValue(plan);
+    expect(await previewDelete('scenarios', { id: 's1' })).toEqual(plan);
+    expect(mockInvoke).toHaveBeenCalledWith('preview_delete', { table: 'scenarios', query: { where: { id: 's1' } } });
+  });
+
+  it('sends an empty query without a filter', async () => {
+    mockInvoke.mockResolvedValue(plan);
+    await previewDelete('collections');
+    expect(mockInvoke).toHaveBeenCalledWith('preview_delete', { table: 'collections', query: {} });
+  });
+});
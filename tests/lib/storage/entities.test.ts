@@ -0,0 +1,73 @@
+import { vi, describe, it, expect, beforeEach } from 'vitest';
+vi.mock('@tauri-apps/api/core');
+
+import { invoke } from '@tauri-apps/api/core';
+import { createEntity, listEntities, setLinkedTools, cloneEntity } from '@/lib/storage/entities';
+
+const mockInvoke = vi.mocked(invoke);
+
+beforeEach(() => vi.resetAllMocks());
+
+describe('createEntity', () => {
+  it('returns the stored row', async () => {
+    const row = { id: 'ag-1', name: 'Agent', max_iterations: 10 };
+    mockInvoke.mockResolvedValue(row);
+    expect(await createEntity('agent', { name: 'Agent', provider: 'openai', model: 'gpt-4o' })).toEqual(row);
+    expect(mockInvoke).toHaveBeenCalledWith('entity_create', {
+      entityType: 'agent',
+      data: { name: 'Agent', provider: 'openai', model: 'gpt-4o' },
+    });
+  });
+});
+
+describe('listEntities', () => {
+  it('sends a null query when none is given', async () => {
+    mockInvoke.mockResolvedValue([]);
+    await listEntities('execution');
+    expect(mockInvoke).toHaveBeenCalledWith('entity_list', { entityType: 'execution', query: null });
+  });
+
+  it('returns empty array when invoke returns a non-array', async () => {
+    mockInvoke.mockResolvedValue(null);
+    expect(await listEntities('tool', { where: { is_global: 1 } })).toEqual([]);
+  });
+});
+
+describe('setLinkedTools', () => {
+  it('passes the runnable and tools to invoke', async () => {
+    mockInvoke.mockResolvedValue([{ id: 't-1', name: 'search' }]);
+    const linked = await setLinkedTools('scenario', 'sc-1', [{ name: 'search' }]);
+    expect(linked).toHaveLength(1);
+    expect(mockInvoke).toHaveBeenCalledWith('set_linked_tools_cmd', {
+      runnableType: 'scenario',
+      runnableId: 'sc-1',
+      tools: [{ name: 'search' }],
+    });
+  });
+});
+
+describe('cloneEntity', () => {
+  const result = { entity_type: 'scenario', id: 'sc-2', created: [{ table: 'scenarios', ids: ['sc-2'] }] };
+
+  it('sends null options by default', async () => {
+    mockInvoke.mockResolvedValue(result);
+    expect(await cloneEntity('scenario', 'sc-1')).toEqual(result);
+    expect(mockInvoke).toHaveBeenCalledWith('clone_entity', {
+      entityType: 'scenario',
+      id: 'sc-1',
+      suffix: null,
+      includeMemories: null,
+    });
+  });
+
+  it('passes the suffix and memories option', async () => {
+    mockInvoke.mockResolvedValue({ ...result, entity_type: 'agent' });
+    await cloneEntity('agent', 'ag-1', { suffix: ' v2', includeMemories: true });
+    expect(mockInvoke).toHaveBeenCalledWith('clone_entity', {
+      entityType: 'agent',
+      id: 'ag-1',
+      suffix: ' v2',
+      includeMemories: true,
+    });
+  });
+});
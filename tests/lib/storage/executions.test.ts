@@ -6,6 +6,7 @@ import {
   insertExecution,
   updateExecution,
   listExecutions,
+  listExecutionsPage,
   countExecutions,
   getLastExecutionForScenario,
   getExecutionById,
@@ -17,6 +18,7 @@ const mockDbSelectOne = vi.mocked(db.dbSelectOne);
 const mockDbInsert = vi.mocked(db.dbInsert);
 const mockDbUpdate = vi.mocked(db.dbUpdate);
 const mockDbCount = vi.mocked(db.dbCount);
+const mockDbPage = vi.mocked(db.dbPage);
 
 beforeEach(() => vi.resetAllMocks());
 
@@ -91,6 +93,13 @@ describe('listExecutions', () => {
     }));
   });
 
+  it('filters by start time with a gte condition', async () => {
+    await listExecutions({ status: 'failed', startedAfter: 5000 });
+    expect(mockDbSelect).toHaveBeenCalledWith('executions', expect.objectContaining({
+      where: { status: 'failed', started_at: { gte: 5000 } },
+    }));
+  });
+
   it('omits where clause when no filters provided', async () => {
     await listExecutions({});
     expect(mockDbSelect).toHaveBeenCalledWith('executions',
@@ -135,6 +144,36 @@ describe('listExecutions', () => {
   });
 });
 
+// --- listExecutionsPage ---
+
+describe('listExecutionsPage', () => {
+  beforeEach(() => mockDbPage.mockResolvedValue({ rows: [execution], next_cursor: 'c2', total: null }));
+
+  it('pages by started_at desc with no filters', async () => {
+    await listExecutionsPage();
+    expect(mockDbPage).toHaveBeenCalledWith('executions', {
+      orderBy: 'started_at',
+      orderDirection: 'desc',
+    });
+  });
+
+  it('passes the cursor, limit, filters and withTotal', async () => {
+    await listExecutionsPage({ cursor: 'c1', limit: 20, status: 'failed', withTotal: true });
+    expect(mockDbPage).toHaveBeenCalledWith('executions', {
+      orderBy: 'started_at',
+      orderDirection: 'desc',
+      limit: 20,
+      cursor: 'c1',
+      withTotal: true,
+      where: { status: 'failed' },
+    });
+  });
+
+  it('returns the page', async () => {
+    expect(await listExecutionsPage()).toEqual({ rows: [execution], next_cursor: 'c2', total: null });
+  });
+});
+
 // --- countExecutions ---
 
 describe('countExecutions', () => {
@@ -165,6 +204,11 @@ describe('countExecutions', () => {
     expect(mockDbCount).toHaveBeenCalledWith('executions', { type: 'scenario', status: 'failed' });
   });
 
+  it('filters by start time', async () => {
+    await countExecutions({ startedAfter: 5000 });
+    expect(mockDbCount).toHaveBeenCalledWith('executions', { started_at: { gte: 5000 } });
+  });
+
   it('returns the count', async () => {
     mockDbCount.mockResolvedValue(7);
     expect(await countExecutions()).toBe(7);
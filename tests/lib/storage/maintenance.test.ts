@@ -0,0 +1,90 @@
+import { vi, describe, it, expect, beforeEach } from 'vitest';
+vi.mock('@tauri-apps/api/core');
+
+import { invoke } from '@tauri-apps/api/core';
+import {
+  checkIntegrity,
+  checkForeignKeys,
+  vacuumDatabase,
+  analyzeDatabase,
+  optimizeDatabase,
+  checkpointWal,
+  getStorageReport,
+  listOrphans,
+  repairDatabase,
+} from '@/lib/storage/maintenance';
+
+const mockInvoke = vi.mocked(invoke);
+
+beforeEach(() => vi.resetAllMocks());
+
+describe('checkIntegrity', () => {
+  beforeEach(() => mockInvoke.mockResolvedValue({ ok: true, problems: [] }));
+
+  it('runs the full check by default', async () => {
+    expect(await checkIntegrity()).toEqual({ ok: true, problems: [] });
+    expect(mockInvoke).toHaveBeenCalledWith('check_integrity', { full: true });
+  });
+
+  it('runs the quick check', async () => {
+    await checkIntegrity(false);
+    expect(mockInvoke).toHaveBeenCalledWith('check_integrity', { full: false });
+  });
+});
+
+describe('maintenance commands', () => {
+  it('checks foreign keys', async () => {
+    mockInvoke.mockResolvedValue([]);
+    expect(await checkForeignKeys()).toEqual([]);
+    expect(mockInvoke).toHaveBeenCalledWith('check_foreign_keys');
+  });
+
+  it('vacuums and returns the sizes', async () => {
+    mockInvoke.mockResolvedValue({ bytes_before: 2048, bytes_after: 1024 });
+    expect(await vacuumDatabase()).toEqual({ bytes_before: 2048, bytes_after: 1024 });
+    expect(mockInvoke).toHaveBeenCalledWith('vacuum_database');
+  });
+
+  it('analyzes, optimizes and checkpoints', async () => {
+    mockInvoke.mockResolvedValue(undefined);
+    await analyzeDatabase();
+    await optimizeDatabase();
+    await checkpointWal();
+    expect(mockInvoke.mock.calls.map((call) => call[0])).toEqual([
+      'analyze_database',
+      'optimize_database',
+      'checkpoint_wal',
+    ]);
+  });
+
+  it('returns the storage report', async () => {
+    const report = {
+      page_size: 4096, page_count: 10, freelist_count: 2, file_bytes: 40960, wal_bytes: 0, dbstat: true,
+      tables: [{ name: 'executions', rows: 3, table_bytes: 8192, index_bytes: 4096 }],
+    };
+    mockInvoke.mockResolvedValue(report);
+    expect(await getStorageReport()).toEqual(report);
+    expect(mockInvoke).toHaveBeenCalledWith('get_storage_report');
+  });
+
+  it('lists orphans', async () => {
+    const orphans = [{ table: 'tool_links', column: 'toolable_id', ids: ['l1'] }];
+    mockInvoke.mockResolvedValue(orphans);
+    expect(await listOrphans()).toEqual(orphans);
+    expect(mockInvoke).toHaveBeenCalledWith('list_orphans');
+  });
+});
+
+describe('repairDatabase', () => {
+  beforeEach(() => mockInvoke.mockResolvedValue({ orphans: [], foreign_keys_fixed: 0 }));
+
+  it('keeps orphaned runs by default', async () => {
+    await repairDatabase();
+    expect(mockInvoke).toHaveBeenCalledWith('repair_database', { includeRuns: false });
+  });
+
+  it('can delete orphaned runs too', async () => {
+    await repairDatabase(true);
+    expect(mockInvoke).toHaveBeenCalledWith('repair_database', { includeRuns: true });
+  });
+});
@@ -0,0 +1,59 @@
+import { vi, describe, it, expect, beforeEach } from 'vitest';
+vi.mock('@tauri-apps/api/core');
+
+import { invoke } from '@tauri-apps/api/core';
+import { getSchemaInfo, dryRunMigrations, migrateDatabase, type SchemaInfo } from '@/lib/storage/migrations';
+
+const mockInvoke = vi.mocked(invoke);
+
+beforeEach(() => vi.resetAllMocks());
+
+const info: SchemaInfo = {
+  user_version: 28,
+  latest_version: 29,
+  pending: [{ version: 29, name: '0029_add_keyset_indexes', direction: 'up' }],
+  history: [],
+  startup_error: null,
+};
+
+describe('getSchemaInfo', () => {
+  it('returns the schema info', async () => {
+    mockInvoke.mockResolvedValue(info);
+    expect(await getSchemaInfo()).toEqual(info);
+    expect(mockInvoke).toHaveBeenCalledWith('schema_info');
+  });
+});
+
+describe('dryRunMigrations', () => {
+  it('defaults to the latest version', async () => {
+    mockInvoke.mockResolvedValue({ from_version: 28, to_version: 29, steps: info.pending, error: null });
+    await dryRunMigrations();
+    expect(mockInvoke).toHaveBeenCalledWith('dry_run_migrations', { targetVersion: null });
+  });
+
+  it('passes a target version', async () => {
+    mockInvoke.mockResolvedValue({ from_version: 28, to_version: 20, steps: [], error: null });
+    await dryRunMigrations(20);
+    expect(mockInvoke).toHaveBeenCalledWith('dry_run_migrations', { targetVersion: 20 });
+  });
+});
+
+describe('migrateDatabase', () => {
+  it('migrates to the target version and returns the new info', async () => {
+    mockInvoke.mockResolvedValue({ ...info, user_version: 29, pending: [] });
+    expect((await migrateDatabase(29)).user_version).toBe(29);
+    expect(mockInvoke).toHaveBeenCalledWith('migrate_database', { targetVersion: 29, allowDestructive: null });
+  });
+
+  it('defaults to the latest version', async () => {
+    mockInvoke.mockResolvedValue(info);
+    await migrateDatabase();
+    expect(mockInvoke).toHaveBeenCalledWith('migrate_database', { targetVersion: null, allowDestructive: null });
+  });
+
+  it('passes the confirmation for a down migration', async () => {
+    mockInvoke.mockResolvedValue(info);
+    await migrateDatabase(20, { allowDestructive: true });
+    expect(mockInvoke).toHaveBeenCalledWith('migrate_database', { targetVersion: 20, allowDestructive: true });
+  });
+});
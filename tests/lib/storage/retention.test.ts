@@ -0,0 +1,27 @@
+import { vi, describe, it, expect, beforeEach } from 'vitest';
+vi.mock('@tauri-apps/api/core');
+
+import { invoke } from '@tauri-apps/api/core';
+import { setRetentionPolicy, applyRetentionPolicy } from '@/lib/storage/retention';
+
+const mockInvoke = vi.mocked(invoke);
+
+beforeEach(() => vi.resetAllMocks());
+
+describe('setRetentionPolicy', () => {
+  it('passes the policy to invoke', async () => {
+    const policy = { executions: { max_per_runnable: 50 }, telemetry_max_age_days: 30 };
+    mockInvoke.mockResolvedValue(policy);
+    expect(await setRetentionPolicy(policy)).toEqual(policy);
+    expect(mockInvoke).toHaveBeenCalledWith('set_retention_policy', { policy });
+  });
+});
+
+describe('applyRetentionPolicy', () => {
+  it('returns the prune report', async () => {
+    const report = { executions: 3, eval_runs: 0, eval_results: 0, telemetry_events: 10, vacuumed: true };
+    mockInvoke.mockResolvedValue(report);
+    expect(await applyRetentionPolicy()).toEqual(report);
+    expect(mockInvoke).toHaveBeenCalledWith('apply_retention_policy');
+  });
+});
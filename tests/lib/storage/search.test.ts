@@ -0,0 +1,49 @@
+import { vi, describe, it, expect, beforeEach } from 'vitest';
+vi.mock('@tauri-apps/api/core');
+
+import { invoke } from '@tauri-apps/api/core';
+import { searchEntities, splitSnippet } from '@/lib/storage/search';
+
+const mockInvoke = vi.mocked(invoke);
+
+beforeEach(() => vi.resetAllMocks());
+
+// --- searchEntities ---
+
+describe('searchEntities', () => {
+  it('passes the query and options to invoke', async () => {
+    mockInvoke.mockResolvedValue([]);
+    await searchEntities('refund policy', { entityTypes: ['scenario'], limit: 10 });
+    expect(mockInvoke).toHaveBeenCalledWith('search', {
+      query: 'refund policy',
+      entityTypes: ['scenario'],
+      limit: 10,
+    });
+  });
+
+  it('does not call invoke for a blank query', async () => {
+    expect(await searchEntities('   ')).toEqual([]);
+    expect(mockInvoke).not.toHaveBeenCalled();
+  });
+
+  it('returns empty array when invoke returns a non-array', async () => {
+    mockInvoke.mockResolvedValue(null);
+    expect(await searchEntities('refund')).toEqual([]);
+  });
+});
+
+// --- splitSnippet ---
+
+describe('splitSnippet', () => {
+  it('splits highlighted terms from plain text', () => {
+    expect(splitSnippet('Follow the \uE000refund\uE001 policy')).toEqual([
+      { text: 'Follow the ', highlighted: false },
+      { text: 'refund', highlighted: true },
+      { text: ' policy', highlighted: false },
+    ]);
+  });
+
+  it('returns plain text unchanged', () => {
+    expect(splitSnippet('no match')).toEqual([{ text: 'no match', highlighted: false }]);
+  });
+});
@@ -1,9 +1,12 @@
 import { vi, describe, it, expect, beforeEach } from 'vitest';
 vi.mock('@/lib/storage/db');
+vi.mock('@tauri-apps/api/core');
 
+import { invoke } from '@tauri-apps/api/core';
 import * as db from '@/lib/storage/db';
 import { listEnvVariables, hasApiKeys, getSetting, setSetting } from '@/lib/storage/settings';
 
+const mockInvoke = vi.mocked(invoke);
 const mockDbSelect = vi.mocked(db.dbSelect);
 const mockDbSelectOne = vi.mocked(db.dbSelectOne);
 const mockDbUpsert = vi.mocked(db.dbUpsert);
@@ -13,17 +16,23 @@ beforeEach(() => vi.resetAllMocks());
 // --- listEnvVariables ---
 
 describe('listEnvVariables', () => {
-  it('queries ordered by created_at asc', async () => {
-    mockDbSelect.mockResolvedValue([]);
+  it('resolves the variables through the backend', async () => {
+    mockInvoke.mockResolvedValue([]);
     await listEnvVariables();
-    expect(mockDbSelect).toHaveBeenCalledWith('env_variables', { orderBy: 'created_at', orderDirection: 'asc' });
+    expect(mockInvoke).toHaveBeenCalledWith('resolve_env_variables');
+    expect(mockDbSelect).not.toHaveBeenCalled();
   });
 
   it('returns the rows', async () => {
-    const rows = [{ id: '1', key: 'API_KEY', value: 'secret', is_secret: 1 }];
-    mockDbSelect.mockResolvedValue(rows);
+    const rows = [{ id: '1', key: 'API_KEY', value: '{{API_KEY}}', is_secret: 1 }];
+    mockInvoke.mockResolvedValue(rows);
     expect(await listEnvVariables()).toEqual(rows);
   });
+
+  it('returns empty array when invoke returns a non-array', async () => {
+    mockInvoke.mockResolvedValue(null);
+    expect(await listEnvVariables()).toEqual([]);
+  });
 });
 
 // --- hasApiKeys ---
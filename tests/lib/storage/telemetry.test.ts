@@ -5,6 +5,7 @@ import * as db from '@/lib/storage/db';
 import {
   insertTelemetryEvent,
   listTelemetryEvents,
+  listTelemetryEventsPage,
   countTelemetryEvents,
   deleteTelemetryEvents,
 } from '@/lib/storage/telemetry';
@@ -14,6 +15,7 @@ const mockDbSelect = vi.mocked(db.dbSelect);
 const mockDbInsert = vi.mocked(db.dbInsert);
 const mockDbCount = vi.mocked(db.dbCount);
 const mockDbDelete = vi.mocked(db.dbDelete);
+const mockDbPage = vi.mocked(db.dbPage);
 
 beforeEach(() => vi.resetAllMocks());
 
@@ -111,6 +113,32 @@ describe('listTelemetryEvents', () => {
   });
 });
 
+// --- listTelemetryEventsPage ---
+
+describe('listTelemetryEventsPage', () => {
+  beforeEach(() => mockDbPage.mockResolvedValue({ rows: [], next_cursor: null, total: 0 }));
+
+  it('pages by occurred_at desc with no filters', async () => {
+    await listTelemetryEventsPage();
+    expect(mockDbPage).toHaveBeenCalledWith('telemetry_events', {
+      orderBy: 'occurred_at',
+      orderDirection: 'desc',
+    });
+  });
+
+  it('passes filters, cursor and withTotal', async () => {
+    await listTelemetryEventsPage({ trace_id: 'trace-1', cursor: 'c1', limit: 50, withTotal: true });
+    expect(mockDbPage).toHaveBeenCalledWith('telemetry_events', {
+      orderBy: 'occurred_at',
+      orderDirection: 'desc',
+      where: { trace_id: 'trace-1' },
+      limit: 50,
+      cursor: 'c1',
+      withTotal: true,
+    });
+  });
+});
+
 // --- countTelemetryEvents ---
 
 describe('countTelemetryEvents', () => {
@@ -0,0 +1,44 @@
+import { vi, describe, it, expect, beforeEach } from 'vitest';
+vi.mock('@tauri-apps/api/core');
+
+import { invoke } from '@tauri-apps/api/core';
+import { listVersions, diffVersions, restoreVersion } from '@/lib/storage/versions';
+
+const mockInvoke = vi.mocked(invoke);
+
+beforeEach(() => vi.resetAllMocks());
+
+describe('listVersions', () => {
+  it('passes the runnable to invoke', async () => {
+    mockInvoke.mockResolvedValue([{ version: 2, current: true }]);
+    const versions = await listVersions('scenario', 'sc-1');
+    expect(versions).toHaveLength(1);
+    expect(mockInvoke).toHaveBeenCalledWith('list_versions_cmd', { runnableType: 'scenario', runnableId: 'sc-1' });
+  });
+
+  it('returns empty array when invoke returns a non-array', async () => {
+    mockInvoke.mockResolvedValue(null);
+    expect(await listVersions('agent', 'ag-1')).toEqual([]);
+  });
+});
+
+describe('diffVersions', () => {
+  it('returns the changed fields', async () => {
+    const changes = [{ field: 'system_prompt', from: 'a', to: 'b' }];
+    mockInvoke.mockResolvedValue(changes);
+    expect(await diffVersions('agent', 'ag-1', 1, 2)).toEqual(changes);
+    expect(mockInvoke).toHaveBeenCalledWith('diff_versions_cmd', {
+      runnableType: 'agent',
+      runnableId: 'ag-1',
+      from: 1,
+      to: 2,
+    });
+  });
+});
+
+describe('restoreVersion', () => {
+  it('returns the new current version', async () => {
+    mockInvoke.mockResolvedValue(4);
+    expect(await restoreVersion('scenario', 'sc-1', 1)).toBe(4);
+  });
+});
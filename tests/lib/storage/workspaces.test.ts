@@ -0,0 +1,80 @@
+import { vi, describe, it, expect, beforeEach } from 'vitest';
+vi.mock('@tauri-apps/api/core');
+vi.mock('@tauri-apps/api/event', () => ({ listen: vi.fn() }));
+
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import {
+  listWorkspaces,
+  createWorkspace,
+  renameWorkspace,
+  duplicateWorkspace,
+  deleteWorkspace,
+  switchWorkspace,
+  onWorkspaceChanged,
+  type Workspace,
+} from '@/lib/storage/workspaces';
+
+const mockInvoke = vi.mocked(invoke);
+const mockListen = vi.mocked(listen);
+
+beforeEach(() => vi.resetAllMocks());
+
+const workspace: Workspace = { id: 'ws-1', name: 'Client A', created_at: 1 };
+
+describe('listWorkspaces', () => {
+  it('returns the registry', async () => {
+    const list = { active: 'default', workspaces: [{ id: 'default', name: 'Default', created_at: 0 }, workspace] };
+    mockInvoke.mockResolvedValue(list);
+    expect(await listWorkspaces()).toEqual(list);
+    expect(mockInvoke).toHaveBeenCalledWith('list_workspaces');
+  });
+});
+
+describe('workspace commands', () => {
+  beforeEach(() => mockInvoke.mockResolvedValue(workspace));
+
+  it('creates a workspace by name', async () => {
+    expect(await createWorkspace('Client A')).toEqual(workspace);
+    expect(mockInvoke).toHaveBeenCalledWith('create_workspace', { name: 'Client A' });
+  });
+
+  it('renames a workspace', async () => {
+    await renameWorkspace('ws-1', 'Client B');
+    expect(mockInvoke).toHaveBeenCalledWith('rename_workspace', { id: 'ws-1', name: 'Client B' });
+  });
+
+  it('duplicates with the default name when none is given', async () => {
+    await duplicateWorkspace('ws-1');
+    expect(mockInvoke).toHaveBeenCalledWith('duplicate_workspace', { id: 'ws-1', name: null });
+  });
+
+  it('duplicates with an explicit name', async () => {
+    await duplicateWorkspace('ws-1', 'Staging');
+    expect(mockInvoke).toHaveBeenCalledWith('duplicate_workspace', { id: 'ws-1', name: 'Staging' });
+  });
+
+  it('deletes a workspace', async () => {
+    mockInvoke.mockResolvedValue(undefined);
+    await deleteWorkspace('ws-1');
+    expect(mockInvoke).toHaveBeenCalledWith('delete_workspace', { id: 'ws-1' });
+  });
+
+  it('switches the active workspace', async () => {
+    expect(await switchWorkspace('ws-1')).toEqual(workspace);
+    expect(mockInvoke).toHaveBeenCalledWith('switch_workspace', { id: 'ws-1' });
+  });
+});
+
+describe('onWorkspaceChanged', () => {
+  it('listens on workspace-changed and passes the new workspace', async () => {
+    const unlisten = vi.fn();
+    mockListen.mockResolvedValue(unlisten);
+    const callback = vi.fn();
+    expect(await onWorkspaceChanged(callback)).toBe(unlisten);
+    expect(mockListen).toHaveBeenCalledWith('workspace-changed', expect.any(Function));
+
+    (mockListen.mock.calls[0][1] as (e: { payload: Workspace }) => void)({ payload: workspace });
+    expect(callback).toHaveBeenCalledWith(workspace);
+  });
+});